@@ -0,0 +1,298 @@
+use crate::settings;
+use crate::time::TimeSource;
+use crate::xdg;
+use chrono::{DateTime, Duration, Local, NaiveTime, TimeZone};
+use log::{error, info};
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+use std::sync::{Mutex, MutexGuard, PoisonError};
+use thiserror::Error;
+
+/// The alarms, shared by the alarm apps and the background check
+static SCHEDULE: Lazy<Mutex<AlarmSchedule>> = Lazy::new(|| {
+    Mutex::new(match xdg::config_file("alarms.toml") {
+        Some(path) => AlarmSchedule::load(path),
+        None => AlarmSchedule::default(),
+    })
+});
+
+/// All errors that can be produced while loading or saving the alarms
+#[derive(Error, Debug)]
+pub enum AlarmError {
+    /// Represents an error while reading or writing the alarms file
+    #[error("can't access the alarms file")]
+    Io(#[from] io::Error),
+
+    /// Represents an invalid alarms file
+    #[error("invalid alarms file")]
+    Parse(#[from] toml::de::Error),
+
+    /// Represents an error while serializing the alarms
+    #[error("can't serialize the alarms")]
+    Serialize(#[from] toml::ser::Error),
+}
+
+/// An alarm, ringing every day at the given time
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+pub struct Alarm {
+    pub hour: u32,
+    pub minute: u32,
+    pub enabled: bool,
+}
+
+impl Alarm {
+    /// Create an enabled alarm
+    pub fn new(hour: u32, minute: u32) -> Self {
+        Self {
+            hour,
+            minute,
+            enabled: true,
+        }
+    }
+
+    /// Get the next time the alarm rings, strictly after the given time
+    pub fn next_after(&self, time: &DateTime<Local>) -> Option<DateTime<Local>> {
+        let at = NaiveTime::from_hms_opt(self.hour, self.minute, 0)?;
+        let mut date = time.date_naive();
+        // Today (if still to come) or tomorrow
+        for _ in 0..2 {
+            // A time skipped by a DST change has no local time, try the next day
+            if let Some(next) = Local.from_local_datetime(&date.and_time(at)).earliest() {
+                if next > *time {
+                    return Some(next);
+                }
+            }
+            date = date.succ_opt()?;
+        }
+        None
+    }
+}
+
+/// The content of the alarms file
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(default)]
+pub struct AlarmConfig {
+    /// The snooze duration (in minutes)
+    pub snooze: u32,
+    /// A command run (with `sh -c`) when an alarm rings
+    pub command: Option<String>,
+    pub alarms: Vec<Alarm>,
+}
+
+impl Default for AlarmConfig {
+    fn default() -> Self {
+        Self {
+            snooze: 9,
+            command: None,
+            alarms: Vec::new(),
+        }
+    }
+}
+
+/// The alarms and their state: ringing, snoozed...
+#[derive(Debug, Default)]
+pub struct AlarmSchedule {
+    config: AlarmConfig,
+    /// The file where the alarms are saved
+    path: Option<PathBuf>,
+    /// The last time the alarms were checked
+    last_check: Option<DateTime<Local>>,
+    /// When the snoozed alarm rings again
+    snoozed: Option<DateTime<Local>>,
+    /// When the ringing alarm started to ring
+    ringing: Option<DateTime<Local>>,
+}
+
+impl AlarmSchedule {
+    /// Load the alarms from the given file.
+    /// If the file can't be read, there is no alarm (but they will be saved in this file).
+    pub fn load(path: PathBuf) -> Self {
+        let config = settings::read_toml(&path, "alarms");
+        Self {
+            config,
+            path: Some(path),
+            ..Default::default()
+        }
+    }
+
+    /// Save the alarms in their file (if any)
+    pub fn save(&self) -> Result<(), AlarmError> {
+        if let Some(path) = &self.path {
+            if let Some(dir) = path.parent() {
+                fs::create_dir_all(dir)?;
+            }
+            fs::write(path, toml::to_string(&self.config)?)?;
+        }
+        Ok(())
+    }
+
+    /// Get the alarms
+    pub fn get_alarms(&self) -> &[Alarm] {
+        &self.config.alarms
+    }
+
+    /// Add or replace (if the index is given) an alarm
+    pub fn set_alarm(&mut self, index: Option<usize>, alarm: Alarm) {
+        match index.and_then(|index| self.config.alarms.get_mut(index)) {
+            Some(old) => *old = alarm,
+            None => self.config.alarms.push(alarm),
+        }
+    }
+
+    /// Remove an alarm
+    pub fn remove_alarm(&mut self, index: usize) {
+        if index < self.config.alarms.len() {
+            self.config.alarms.remove(index);
+        }
+    }
+
+    /// Get the command to run when an alarm rings
+    pub fn get_command(&self) -> Option<&str> {
+        self.config.command.as_deref()
+    }
+
+    /// Check if an alarm (or the snoozed one) has to ring since the last check.
+    /// Returns true if an alarm starts to ring.
+    pub fn check(&mut self, now: DateTime<Local>) -> bool {
+        let last = self.last_check.replace(now);
+        if self.ringing.is_some() {
+            return false;
+        }
+        let due = match (self.snoozed, last) {
+            (Some(snoozed), _) if snoozed <= now => true,
+            // The first check only sets the reference time
+            (_, None) => false,
+            (_, Some(last)) => self
+                .config
+                .alarms
+                .iter()
+                .filter(|alarm| alarm.enabled)
+                .filter_map(|alarm| alarm.next_after(&last))
+                .any(|next| next <= now),
+        };
+        if due {
+            self.snoozed = None;
+            self.ringing = Some(now);
+        }
+        due
+    }
+
+    /// Get when the ringing alarm started to ring (None if no alarm is ringing)
+    pub fn get_ringing(&self) -> Option<DateTime<Local>> {
+        self.ringing
+    }
+
+    /// Stop the ringing alarm, it will ring again after the snooze duration
+    pub fn snooze(&mut self, now: DateTime<Local>) {
+        if self.ringing.take().is_some() {
+            self.snoozed = Some(now + Duration::minutes(self.config.snooze as i64));
+        }
+    }
+
+    /// Stop the ringing alarm (and the snoozed one)
+    pub fn dismiss(&mut self) {
+        self.ringing = None;
+        self.snoozed = None;
+    }
+}
+
+/// Get the alarm schedule of the g13 apps
+pub fn schedule() -> MutexGuard<'static, AlarmSchedule> {
+    SCHEDULE.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+/// Check the alarms at the time given by the source.
+/// If an alarm starts to ring, its command is run and true is returned.
+pub fn check<T: TimeSource>(time: &T) -> bool {
+    let mut schedule = schedule();
+    if !schedule.check(time.now()) {
+        return false;
+    }
+    info!("An alarm is ringing");
+    if let Some(command) = schedule.get_command() {
+        // The child is not awaited, tokio reaps it when it exits
+        if let Err(err) = tokio::process::Command::new("sh")
+            .arg("-c")
+            .arg(command)
+            .spawn()
+        {
+            error!("Can't run the alarm command {:?}: {}", command, err);
+        }
+    }
+    true
+}
+
+#[cfg(test)]
+mod test {
+    use crate::alarm::{Alarm, AlarmConfig, AlarmSchedule};
+    use chrono::{DateTime, Duration, Local, TimeZone};
+
+    fn at(hour: u32, minute: u32, second: u32) -> DateTime<Local> {
+        Local
+            .with_ymd_and_hms(2020, 7, 14, hour, minute, second)
+            .unwrap()
+    }
+
+    /// An alarm rings today if still to come, else tomorrow.
+    #[test]
+    fn next_ring() {
+        let alarm = Alarm::new(7, 30);
+        assert_eq!(alarm.next_after(&at(6, 0, 0)), Some(at(7, 30, 0)));
+        assert_eq!(
+            alarm.next_after(&at(7, 30, 0)),
+            Some(at(7, 30, 0) + Duration::days(1))
+        );
+    }
+
+    /// The alarms ring once, between two checks, and can be snoozed.
+    #[test]
+    fn ring_and_snooze() {
+        let mut config = AlarmConfig::default();
+        config.alarms.push(Alarm::new(7, 30));
+        config.alarms.push(Alarm {
+            enabled: false,
+            ..Alarm::new(7, 0)
+        });
+        let mut schedule = AlarmSchedule {
+            config,
+            ..Default::default()
+        };
+
+        assert!(!schedule.check(at(6, 59, 50)));
+        // The disabled alarm does not ring
+        assert!(!schedule.check(at(7, 0, 0)));
+        assert!(!schedule.check(at(7, 29, 59)));
+        assert!(schedule.check(at(7, 30, 0)));
+        assert_eq!(schedule.get_ringing(), Some(at(7, 30, 0)));
+        // Already ringing
+        assert!(!schedule.check(at(7, 30, 1)));
+
+        // Snoozed for 9 minutes
+        schedule.snooze(at(7, 31, 0));
+        assert_eq!(schedule.get_ringing(), None);
+        assert!(!schedule.check(at(7, 39, 59)));
+        assert!(schedule.check(at(7, 40, 0)));
+
+        // Dismissed, it rings again tomorrow
+        schedule.dismiss();
+        assert!(!schedule.check(at(23, 0, 0)));
+        assert!(schedule.check(at(7, 30, 0) + Duration::days(1)));
+    }
+
+    /// The alarms file is a readable TOML file, with defaults.
+    #[test]
+    fn config_file() {
+        let config: AlarmConfig = toml::from_str(
+            "command = \"mpv alarm.ogg\"\n[[alarms]]\nhour = 6\nminute = 45\nenabled = true\n",
+        )
+        .unwrap();
+        assert_eq!(config.snooze, 9);
+        assert_eq!(config.command.as_deref(), Some("mpv alarm.ogg"));
+        assert_eq!(config.alarms, vec![Alarm::new(6, 45)]);
+        let saved = toml::to_string(&config).unwrap();
+        assert_eq!(toml::from_str::<AlarmConfig>(&saved).unwrap(), config);
+    }
+}
@@ -0,0 +1,225 @@
+use crate::alarm::{self, Alarm};
+use crate::app::App;
+use crate::app::Application;
+use crate::component::{AppBar, Button, ButtonBar, List, Spinner};
+use crate::display::G13Display;
+use crate::error::AppError;
+use crate::settings;
+use crate::style::TEXT_BOLD;
+use async_trait::async_trait;
+use embedded_graphics::{fonts::Text, prelude::*};
+use std::marker::Unpin;
+use tokio::io::AsyncWrite;
+use tokio::time;
+
+/// An alarm being edited: its time spinners
+#[derive(Clone, Debug)]
+struct Edit {
+    /// The index of the edited alarm (None for a new alarm)
+    index: Option<usize>,
+    hour: Spinner,
+    minute: Spinner,
+    /// Whether the minute is selected (else the hour)
+    on_minute: bool,
+}
+
+/// The alarms app, to add, edit, toggle and remove the alarms
+#[derive(Clone, Debug, Default)]
+pub struct AlarmEditor {
+    /// Define if the app should end/return
+    end: bool,
+    /// The list of the alarms, then the new alarm entry
+    list: Option<List>,
+    /// The alarm being edited
+    edit: Option<Edit>,
+}
+
+#[async_trait(?Send)]
+impl Application for AlarmEditor {
+    async fn execute<W: Unpin + AsyncWrite>(&mut self, out: &mut W) -> Result<App, AppError>
+    where
+        W: AsyncWrite + Unpin,
+    {
+        let mut interval = time::interval(settings::refresh());
+        let mut display = G13Display::new(out);
+        if self.list.is_none() {
+            self.load();
+        }
+
+        // Draw the time spinners of the edited alarm
+        if let Some(edit) = &self.edit {
+            let title = match edit.index {
+                Some(_) => "Edit alarm:",
+                None => "New alarm:",
+            };
+            AppBar::new(title, Point::zero(), Point::new(160, 8)).draw(&mut display)?;
+            edit.hour
+                .pixels(Point::new(60, 18), !edit.on_minute)
+                .into_iter()
+                .chain(&Text::new(":", Point::new(76, 18)).into_styled(*TEXT_BOLD))
+                .chain(edit.minute.pixels(Point::new(84, 18), edit.on_minute))
+                .draw(&mut display)?;
+
+            let mut buttonbar: ButtonBar = Default::default();
+            let next = if edit.on_minute { "OK" } else { "\u{25BA}" };
+            buttonbar.set_button1(Some(Button::from_str(next)));
+            if edit.index.is_some() {
+                buttonbar.set_button2(Some(Button::from_str("Del")));
+            }
+            buttonbar.set_button3(Some(Button::from_str("\u{25B2}")));
+            buttonbar.set_button4(Some(Button::from_str("\u{25BC}")));
+            buttonbar.draw(&mut display)?;
+            display.flush().await?;
+        } else {
+            AppBar::new("Alarms:", Point::zero(), Point::new(160, 8)).draw(&mut display)?;
+            let mut buttonbar: ButtonBar = Default::default();
+            buttonbar.set_button1(Some(Button::from_str("OK")));
+            if self.selected().is_some() {
+                buttonbar.set_button2(Some(Button::from_str("I/O")));
+            }
+            buttonbar.set_button3(Some(Button::from_str("\u{25B2}")));
+            buttonbar.set_button4(Some(Button::from_str("\u{25BC}")));
+            buttonbar.draw(&mut display)?;
+        }
+
+        let mut last_cursor: usize = usize::MAX;
+        while !self.end {
+            // Tick and wait for new cursor (or a scrolling selection)
+            interval.tick().await;
+            let list = match (self.edit.as_ref(), self.list.as_mut()) {
+                (None, Some(list)) => list,
+                _ => continue,
+            };
+            if last_cursor == list.get_cursor() && !list.tick() {
+                continue;
+            }
+            last_cursor = list.get_cursor();
+
+            // Draw and flush
+            list.draw_default(&mut display)?;
+            display.flush().await?;
+        }
+
+        Ok(App::Menu(Default::default()))
+    }
+
+    /// Represents the edit button (or the next spinner, then save)
+    async fn button_l1(&mut self) -> Result<(), AppError> {
+        match self.edit.as_mut() {
+            Some(edit) if !edit.on_minute => edit.on_minute = true,
+            Some(edit) => {
+                let alarm = Alarm::new(edit.hour.get_value(), edit.minute.get_value());
+                let index = edit.index;
+                self.edit = None;
+                self.update(|schedule| schedule.set_alarm(index, alarm))?;
+            }
+            None => {
+                let index = self.selected();
+                let alarm = index
+                    .and_then(|index| alarm::schedule().get_alarms().get(index).copied())
+                    .unwrap_or_else(|| Alarm::new(7, 0));
+                self.edit = Some(Edit {
+                    index,
+                    hour: Spinner::new(alarm.hour, 0, 23),
+                    minute: Spinner::new(alarm.minute, 0, 59),
+                    on_minute: false,
+                });
+            }
+        }
+        Ok(())
+    }
+
+    /// Represents the toggle button (or delete the edited alarm)
+    async fn button_l2(&mut self) -> Result<(), AppError> {
+        match self.edit.as_ref() {
+            Some(edit) => {
+                if let Some(index) = edit.index {
+                    self.edit = None;
+                    self.update(|schedule| schedule.remove_alarm(index))?;
+                }
+            }
+            None => {
+                if let Some(index) = self.selected() {
+                    self.update(|schedule| {
+                        if let Some(alarm) = schedule.get_alarms().get(index).copied() {
+                            let enabled = !alarm.enabled;
+                            schedule.set_alarm(Some(index), Alarm { enabled, ..alarm });
+                        }
+                    })?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Represents the previous button (or increment the spinner)
+    async fn button_l3(&mut self) -> Result<(), AppError> {
+        match (self.edit.as_mut(), self.list.as_mut()) {
+            (Some(edit), _) if edit.on_minute => edit.minute.increment(),
+            (Some(edit), _) => edit.hour.increment(),
+            (None, Some(list)) => list.previous(),
+            (None, None) => {}
+        }
+        Ok(())
+    }
+
+    /// Represents the next button (or decrement the spinner)
+    async fn button_l4(&mut self) -> Result<(), AppError> {
+        match (self.edit.as_mut(), self.list.as_mut()) {
+            (Some(edit), _) if edit.on_minute => edit.minute.decrement(),
+            (Some(edit), _) => edit.hour.decrement(),
+            (None, Some(list)) => list.next(),
+            (None, None) => {}
+        }
+        Ok(())
+    }
+
+    /// Represents the return button (cancel the edition or go to menu)
+    async fn button_bd(&mut self) -> Result<(), AppError> {
+        if self.edit.take().is_none() {
+            self.end = true;
+        }
+        Ok(())
+    }
+}
+
+impl AlarmEditor {
+    /// Load the list of the alarms
+    fn load(&mut self) {
+        let mut entries: Vec<String> = alarm::schedule()
+            .get_alarms()
+            .iter()
+            .map(|alarm| {
+                let state = if alarm.enabled { "on" } else { "off" };
+                format!("{:02}:{:02}  {}", alarm.hour, alarm.minute, state)
+            })
+            .collect();
+        entries.push("+ New alarm".to_owned());
+        self.list = Some(List::new(entries));
+    }
+
+    /// Get the index of the selected alarm (None if the new alarm entry is selected)
+    fn selected(&self) -> Option<usize> {
+        let cursor = self.list.as_ref()?.get_cursor();
+        if cursor < alarm::schedule().get_alarms().len() {
+            Some(cursor)
+        } else {
+            None
+        }
+    }
+
+    /// Change the alarms, save them and reload the list (keeping the cursor if possible)
+    fn update<F: FnOnce(&mut alarm::AlarmSchedule)>(&mut self, change: F) -> Result<(), AppError> {
+        let cursor = self.list.as_ref().map_or(0, List::get_cursor);
+        {
+            let mut schedule = alarm::schedule();
+            change(&mut schedule);
+            schedule.save()?;
+        }
+        self.load();
+        if let Some(list) = self.list.as_mut() {
+            (0..cursor).for_each(|_| list.next());
+        }
+        Ok(())
+    }
+}
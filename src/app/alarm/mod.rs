@@ -0,0 +1,5 @@
+mod editor;
+mod ringing;
+
+pub use editor::AlarmEditor;
+pub use ringing::AlarmRinging;
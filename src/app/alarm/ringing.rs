@@ -0,0 +1,139 @@
+use crate::alarm;
+use crate::app::App;
+use crate::app::Application;
+use crate::component::{Button, ButtonBar};
+use crate::display::G13Display;
+use crate::driver::{Led, LedStates};
+use crate::error::AppError;
+use crate::style::{FILL_OFF, FILL_ON, TEXT_BOLD, TITLE_BOLD};
+use async_trait::async_trait;
+use chrono::Local;
+use embedded_graphics::egrectangle;
+use embedded_graphics::{fonts::Text, prelude::*};
+use std::marker::Unpin;
+use std::time::Duration;
+use tokio::io::AsyncWrite;
+use tokio::time;
+
+/// The alarm ringing screen, flashing until the alarm is snoozed or dismissed.
+///
+/// It takes over the foreground app, which is restored afterwards.
+#[derive(Debug)]
+pub struct AlarmRinging {
+    /// Define if the app should end/return
+    end: bool,
+    /// The app in foreground when the alarm started to ring
+    previous: Option<Box<App>>,
+}
+
+impl Default for AlarmRinging {
+    // By default, no alarm is ringing, so it's an error to open this app.
+    fn default() -> Self {
+        Self {
+            end: true,
+            previous: None,
+        }
+    }
+}
+
+impl AlarmRinging {
+    /// Create the ringing screen, over the given app
+    pub fn new(previous: App) -> Self {
+        Self {
+            end: false,
+            previous: Some(Box::new(previous)),
+        }
+    }
+
+    /// Get the app covered, restored afterwards
+    pub fn get_previous(&mut self) -> Option<&mut App> {
+        self.previous.as_deref_mut()
+    }
+}
+
+#[async_trait(?Send)]
+impl Application for AlarmRinging {
+    async fn execute<W: Unpin + AsyncWrite>(&mut self, out: &mut W) -> Result<App, AppError>
+    where
+        W: AsyncWrite + Unpin,
+    {
+        let mut interval = time::interval(Duration::from_millis(500));
+        let mut display = G13Display::new(out);
+
+        // Draw the button info
+        let mut buttonbar: ButtonBar = Default::default();
+        buttonbar.set_button1(Some(Button::from_str("Zzz")));
+        buttonbar.set_button2(Some(Button::from_str("Off")));
+        buttonbar.draw(&mut display)?;
+
+        let mut inverted = false;
+        // Flash until the alarm is stopped (here or by another app)
+        while !self.end && alarm::schedule().get_ringing().is_some() {
+            let now = Local::now().format("%H:%M").to_string();
+            let (background, style) = if inverted {
+                (*FILL_ON, *TITLE_BOLD)
+            } else {
+                (*FILL_OFF, *TEXT_BOLD)
+            };
+            egrectangle!(
+                top_left = Point::zero(),
+                bottom_right = Point::new(159, 33),
+                style = background,
+            )
+            .draw(&mut display)?;
+            Text::new("\u{266A} ALARM \u{266A}", Point::new(36, 6))
+                .into_styled(style)
+                .draw(&mut display)?;
+            Text::new(&now, Point::new(60, 20))
+                .into_styled(style)
+                .draw(&mut display)?;
+
+            display.flush().await?;
+            inverted = !inverted;
+            interval.tick().await;
+        }
+        Ok(self
+            .previous
+            .take()
+            .map_or_else(|| App::Menu(Default::default()), |app| *app))
+    }
+
+    /// Represents the snooze button
+    async fn button_l1(&mut self) -> Result<(), AppError> {
+        alarm::schedule().snooze(Local::now());
+        self.end = true;
+        Ok(())
+    }
+
+    /// Represents the dismiss button
+    async fn button_l2(&mut self) -> Result<(), AppError> {
+        alarm::schedule().dismiss();
+        self.end = true;
+        Ok(())
+    }
+
+    /// Not used
+    async fn button_l3(&mut self) -> Result<(), AppError> {
+        Ok(())
+    }
+
+    /// Not used
+    async fn button_l4(&mut self) -> Result<(), AppError> {
+        Ok(())
+    }
+
+    /// Represents the return button (dismiss the alarm)
+    async fn button_bd(&mut self) -> Result<(), AppError> {
+        self.button_l2().await
+    }
+
+    /// All the mode LEDs blink while the alarm rings
+    fn leds(&self) -> LedStates {
+        LedStates {
+            m1: Some(Led::Blink),
+            m2: Some(Led::Blink),
+            m3: Some(Led::Blink),
+            mr: Some(Led::Blink),
+        }
+    }
+}
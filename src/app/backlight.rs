@@ -0,0 +1,181 @@
+use crate::app::App;
+use crate::app::Application;
+use crate::component::{AppBar, Button, ButtonBar};
+use crate::display::G13Display;
+use crate::error::AppError;
+use crate::settings;
+use crate::style::{BORDER_1, FILL_ON, TEXT_BOLD, TEXT_LIGHT, TITLE_BOLD};
+use async_trait::async_trait;
+use embedded_graphics::egrectangle;
+use embedded_graphics::fonts::Text;
+use embedded_graphics::pixelcolor::BinaryColor;
+use embedded_graphics::prelude::*;
+use std::marker::Unpin;
+use std::time::Duration;
+use tokio::io::AsyncWrite;
+use tokio::time;
+
+/// The step of a color channel for each press (a channel has 16 levels)
+const STEP: u8 = 17;
+/// The width of the channel gauges (in pixels)
+const GAUGE_WIDTH: i32 = 100;
+
+/// The backlight app, to pick the color of the keyboard backlight.
+/// The color is shown on the keyboard while it is picked.
+#[derive(Clone, Debug, Default)]
+pub struct Backlight {
+    /// Define if the app should end/return
+    end: bool,
+    /// The picked color, loaded from the settings on first execution
+    color: Option<[u8; 3]>,
+    /// The selected channel (red, green or blue)
+    channel: usize,
+}
+
+#[async_trait(?Send)]
+impl Application for Backlight {
+    async fn execute<W: Unpin + AsyncWrite>(&mut self, out: &mut W) -> Result<App, AppError>
+    where
+        W: AsyncWrite + Unpin,
+    {
+        let mut interval = time::interval(Duration::from_millis(100));
+        let mut display = G13Display::new(out);
+        let color = *self
+            .color
+            .get_or_insert_with(|| settings::settings().backlight);
+
+        let title = format!("Backlight: {}", settings::color_name(color));
+        AppBar::new(title, Point::zero(), Point::new(160, 8)).draw(&mut display)?;
+        make_gauges(color, self.channel)
+            .into_iter()
+            .draw(&mut display)?;
+        let mut buttonbar: ButtonBar = Default::default();
+        buttonbar.set_button1(Some(Button::from_str("\u{25BA}")));
+        buttonbar.set_button2(Some(Button::from_str("Save")));
+        buttonbar.set_button3(Some(Button::from_str("+")));
+        buttonbar.set_button4(Some(Button::from_str("-")));
+        buttonbar.draw(&mut display)?;
+        display.flush().await?;
+
+        // Wait until user ask for the menu app
+        #[warn(clippy::while_immutable_condition)]
+        while !self.end {
+            interval.tick().await;
+        }
+        Ok(App::Menu(Default::default()))
+    }
+
+    /// Represents the next channel button
+    async fn button_l1(&mut self) -> Result<(), AppError> {
+        self.channel = (self.channel + 1) % 3;
+        Ok(())
+    }
+
+    /// Represents the save button (the picked color becomes the default one)
+    async fn button_l2(&mut self) -> Result<(), AppError> {
+        if let Some(color) = self.color {
+            let mut settings = settings::settings();
+            settings.backlight = color;
+            settings.save()?;
+        }
+        self.end = true;
+        Ok(())
+    }
+
+    /// Represents the increment button
+    async fn button_l3(&mut self) -> Result<(), AppError> {
+        if let Some(color) = self.color.as_mut() {
+            color[self.channel] = color[self.channel].saturating_add(STEP);
+        }
+        Ok(())
+    }
+
+    /// Represents the decrement button
+    async fn button_l4(&mut self) -> Result<(), AppError> {
+        if let Some(color) = self.color.as_mut() {
+            color[self.channel] = color[self.channel].saturating_sub(STEP);
+        }
+        Ok(())
+    }
+
+    /// Represents the return button (go to menu, without saving)
+    async fn button_bd(&mut self) -> Result<(), AppError> {
+        self.end = true;
+        Ok(())
+    }
+
+    /// The picked color is shown, until the app is closed
+    fn backlight(&self) -> Option<[u8; 3]> {
+        self.color.filter(|_| !self.end)
+    }
+}
+
+/// Make an drawable iterator of the gauges of the channels, the selected one highlighted
+fn make_gauges(color: [u8; 3], selected: usize) -> Vec<Pixel<BinaryColor>> {
+    let mut pixels: Vec<Pixel<BinaryColor>> = Vec::new();
+    for (channel, (name, value)) in ["R", "G", "B"].iter().zip(color.iter()).enumerate() {
+        let y = 11 + 8 * channel as i32;
+        let label = Text::new(name, Point::new(4, y));
+        if channel == selected {
+            pixels.extend(&label.into_styled(*TITLE_BOLD));
+        } else {
+            pixels.extend(&label.into_styled(*TEXT_BOLD));
+        }
+        pixels.extend(&egrectangle!(
+            top_left = Point::new(16, y + 1),
+            bottom_right = Point::new(17 + GAUGE_WIDTH, y + 6),
+            style = *BORDER_1,
+        ));
+        let width = GAUGE_WIDTH * *value as i32 / 255;
+        if width > 0 {
+            pixels.extend(&egrectangle!(
+                top_left = Point::new(18, y + 3),
+                bottom_right = Point::new(17 + width, y + 4),
+                style = *FILL_ON,
+            ));
+        }
+        let value = format!("{:>3}", value);
+        pixels.extend(&Text::new(&value, Point::new(124, y)).into_styled(*TEXT_LIGHT));
+    }
+    pixels
+}
+
+#[cfg(test)]
+mod test {
+    use crate::app::backlight::{make_gauges, Backlight};
+    use crate::app::Application;
+    use embedded_graphics::pixelcolor::BinaryColor;
+    use tokio::runtime::Runtime;
+
+    /// The channels change by steps, within their bounds.
+    #[test]
+    fn pick_color() {
+        let mut rt = Runtime::new().unwrap();
+        let mut app = Backlight {
+            color: Some([255, 0, 0]),
+            ..Default::default()
+        };
+        rt.block_on(app.button_l3()).unwrap();
+        rt.block_on(app.button_l1()).unwrap();
+        rt.block_on(app.button_l3()).unwrap();
+        rt.block_on(app.button_l1()).unwrap();
+        rt.block_on(app.button_l4()).unwrap();
+        assert_eq!(app.backlight(), Some([255, 17, 0]));
+        // Not shown anymore once closed
+        rt.block_on(app.button_bd()).unwrap();
+        assert_eq!(app.backlight(), None);
+    }
+
+    /// A full channel has a full gauge.
+    #[test]
+    fn gauges() {
+        let lit = |color| {
+            make_gauges(color, 0)
+                .iter()
+                // Without the values, on the right
+                .filter(|pixel| pixel.1 == BinaryColor::On && pixel.0.x < 120)
+                .count()
+        };
+        assert_eq!(lit([255, 0, 0]) - lit([0, 0, 0]), 2 * 100);
+    }
+}
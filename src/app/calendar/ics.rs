@@ -0,0 +1,484 @@
+use chrono::{DateTime, Datelike, Days, NaiveDate, NaiveDateTime, TimeZone, Utc, Weekday};
+use chrono_tz::Tz;
+use ical::parser::ical::component::IcalEvent;
+use ical::parser::ParserError;
+use ical::IcalParser;
+use log::{debug, warn};
+use std::fs::{self, File};
+use std::io::{self, BufRead, BufReader};
+use std::path::Path;
+use thiserror::Error;
+
+/// All errors that can be produced while reading a calendar file
+#[derive(Error, Debug)]
+pub enum CalendarError {
+    /// Represents an error while reading a calendar file
+    #[error("can't read the calendar file")]
+    Io(#[from] io::Error),
+
+    /// Represents an invalid calendar file
+    #[error("invalid calendar file")]
+    Parse(#[from] ParserError),
+}
+
+/// An event of a calendar
+#[derive(Clone, Debug, PartialEq)]
+pub struct Event {
+    /// The start of the event, in the local time
+    pub start: NaiveDateTime,
+    /// Whether the event lasts all the day (its start is then midnight)
+    pub all_day: bool,
+    pub summary: String,
+}
+
+/// Parse a date or time value (DTSTART or UNTIL), telling if it is a date (all day)
+/// and if it is in UTC (ending with Z). A date is given at midnight.
+fn parse_value(value: &str) -> Option<(NaiveDateTime, bool, bool)> {
+    if value.len() == 8 {
+        let date = NaiveDate::parse_from_str(value, "%Y%m%d").ok()?;
+        return Some((date.and_hms_opt(0, 0, 0)?, true, false));
+    }
+    let (value, utc) = match value.strip_suffix('Z') {
+        Some(value) => (value, true),
+        None => (value, false),
+    };
+    let time = NaiveDateTime::parse_from_str(value, "%Y%m%dT%H%M%S").ok()?;
+    Some((time, false, utc))
+}
+
+/// Convert a time of an event to the given time zone: from UTC, from its (known) TZID,
+/// or as is without zone (floating, already local).
+fn localize<Z: TimeZone>(
+    time: NaiveDateTime,
+    utc: bool,
+    tz: Option<&Tz>,
+    zone: &Z,
+) -> NaiveDateTime {
+    let zoned = if utc {
+        Some(DateTime::<Utc>::from_naive_utc_and_offset(time, Utc).with_timezone(zone))
+    } else {
+        tz.and_then(|tz| tz.from_local_datetime(&time).earliest())
+            .map(|time| time.with_timezone(zone))
+    };
+    zoned.map_or(time, |zoned| zoned.naive_local())
+}
+
+/// The frequencies of the recurrence rules
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Frequency {
+    Daily,
+    Weekly,
+    Monthly,
+    Yearly,
+}
+
+/// A simple recurrence rule (RRULE): a frequency and its interval, ending after a count
+/// of occurrences or at a time. The weekly rules can be on several days (BYDAY).
+#[derive(Clone, Debug, PartialEq)]
+struct Recurrence {
+    frequency: Frequency,
+    interval: u32,
+    count: Option<usize>,
+    /// The last possible start, in the time zone of the event start
+    until: Option<NaiveDateTime>,
+    /// The days of a weekly rule (the day of the event start if empty)
+    weekdays: Vec<Weekday>,
+}
+
+impl Recurrence {
+    /// Parse a rule of an event starting in the given time zone (None if in UTC or floating).
+    /// None if the rule is invalid or uses other parts (as BYMONTHDAY or BYSETPOS).
+    fn parse(rule: &str, tz: Option<&Tz>) -> Option<Self> {
+        let mut frequency = None;
+        let mut recurrence = Recurrence {
+            frequency: Frequency::Daily,
+            interval: 1,
+            count: None,
+            until: None,
+            weekdays: Vec::new(),
+        };
+        for part in rule.split(';') {
+            let (name, value) = part.split_once('=')?;
+            match name {
+                "FREQ" => {
+                    frequency = Some(match value {
+                        "DAILY" => Frequency::Daily,
+                        "WEEKLY" => Frequency::Weekly,
+                        "MONTHLY" => Frequency::Monthly,
+                        "YEARLY" => Frequency::Yearly,
+                        _ => return None,
+                    })
+                }
+                "INTERVAL" => recurrence.interval = value.parse().ok().filter(|n| *n > 0)?,
+                "COUNT" => recurrence.count = Some(value.parse().ok()?),
+                "UNTIL" => {
+                    let (until, all_day, utc) = parse_value(value)?;
+                    recurrence.until = Some(match tz {
+                        // The whole last day
+                        _ if all_day => until.date().and_hms_opt(23, 59, 59)?,
+                        Some(tz) if utc => DateTime::<Utc>::from_naive_utc_and_offset(until, Utc)
+                            .with_timezone(tz)
+                            .naive_local(),
+                        _ => until,
+                    });
+                }
+                "BYDAY" => {
+                    for day in value.split(',') {
+                        recurrence.weekdays.push(match day {
+                            "MO" => Weekday::Mon,
+                            "TU" => Weekday::Tue,
+                            "WE" => Weekday::Wed,
+                            "TH" => Weekday::Thu,
+                            "FR" => Weekday::Fri,
+                            "SA" => Weekday::Sat,
+                            "SU" => Weekday::Sun,
+                            // The days of a month or a year ("1MO") are not supported
+                            _ => return None,
+                        });
+                    }
+                }
+                // The weeks start on monday
+                "WKST" => {}
+                _ => return None,
+            }
+        }
+        recurrence.frequency = frequency?;
+        if !recurrence.weekdays.is_empty() && recurrence.frequency != Frequency::Weekly {
+            return None;
+        }
+        Some(recurrence)
+    }
+
+    /// Get the starts of the occurrences in a period of the rule (0 for the period of the first start),
+    /// None if the period begins after the given date
+    fn period(
+        &self,
+        first: NaiveDateTime,
+        index: u32,
+        last: NaiveDate,
+    ) -> Option<Vec<NaiveDateTime>> {
+        let step = index.checked_mul(self.interval)?;
+        let date = first.date();
+        let (begin, dates) = match self.frequency {
+            Frequency::Daily => {
+                let day = date.checked_add_days(Days::new(step.into()))?;
+                (day, vec![day])
+            }
+            Frequency::Weekly => {
+                let offset = date.weekday().num_days_from_monday();
+                let monday = (date - Days::new(offset.into()))
+                    .checked_add_days(Days::new(7 * u64::from(step)))?;
+                let weekdays = if self.weekdays.is_empty() {
+                    vec![date.weekday()]
+                } else {
+                    self.weekdays.clone()
+                };
+                let days = weekdays
+                    .iter()
+                    .map(|weekday| monday + Days::new(weekday.num_days_from_monday().into()))
+                    .collect();
+                (monday, days)
+            }
+            Frequency::Monthly => {
+                let month = date.month0() as i32 + step as i32;
+                let year = date.year() + month.div_euclid(12);
+                let month = month.rem_euclid(12) as u32 + 1;
+                let begin = NaiveDate::from_ymd_opt(year, month, 1)?;
+                // The months without the day are skipped
+                (
+                    begin,
+                    NaiveDate::from_ymd_opt(year, month, date.day())
+                        .into_iter()
+                        .collect(),
+                )
+            }
+            Frequency::Yearly => {
+                let year = date.year() + step as i32;
+                let begin = NaiveDate::from_ymd_opt(year, 1, 1)?;
+                // Same for the years without the day (february 29)
+                (
+                    begin,
+                    NaiveDate::from_ymd_opt(year, date.month(), date.day())
+                        .into_iter()
+                        .collect(),
+                )
+            }
+        };
+        if begin > last {
+            return None;
+        }
+        let mut starts: Vec<_> = dates
+            .into_iter()
+            .map(|day| day.and_time(first.time()))
+            .collect();
+        starts.sort_unstable();
+        Some(starts)
+    }
+
+    /// Get the starts of the occurrences until the given time, the first one included
+    fn occurrences(&self, first: NaiveDateTime, end: NaiveDateTime) -> Vec<NaiveDateTime> {
+        let last = self.until.map_or(end, |until| until.min(end));
+        let mut occurrences = vec![first];
+        let mut index = 0;
+        while let Some(starts) = self.period(first, index, last.date()) {
+            for start in starts
+                .into_iter()
+                .filter(|start| *start > first && *start <= last)
+            {
+                if self.count.is_some_and(|count| occurrences.len() >= count) {
+                    return occurrences;
+                }
+                occurrences.push(start);
+            }
+            index += 1;
+        }
+        occurrences
+    }
+}
+
+/// Make the occurrences of an event from its properties, none if it has no valid start.
+/// A recurring event is repeated until the given time (only its first start is kept
+/// if its rule is not supported).
+fn make_events<Z: TimeZone>(event: &IcalEvent, zone: &Z, end: NaiveDateTime) -> Vec<Event> {
+    let property = |name: &str| {
+        event
+            .properties
+            .iter()
+            .find(|property| property.name == name)
+            .and_then(|property| property.value.as_deref().map(|value| (property, value)))
+    };
+    let (start, value) = match property("DTSTART") {
+        Some(start) => start,
+        None => return Vec::new(),
+    };
+    let (first, all_day, utc) = match parse_value(value) {
+        Some(first) => first,
+        None => return Vec::new(),
+    };
+    let tzid = start.params.as_ref().and_then(|params| {
+        params
+            .iter()
+            .find(|(name, _)| name == "TZID")
+            .and_then(|(_, values)| values.first())
+    });
+    let tz = match tzid.map(|tzid| tzid.parse::<Tz>()) {
+        Some(Ok(tz)) if !utc && !all_day => Some(tz),
+        Some(Err(err)) => {
+            warn!("Unknown time zone of an event: {}", err);
+            None
+        }
+        _ => None,
+    };
+    let summary = property("SUMMARY")
+        .map(|(_, summary)| unescape(summary))
+        .unwrap_or_else(|| "(no title)".to_owned());
+
+    // The occurrences are computed in the time zone of the start, then converted
+    let starts = match property("RRULE") {
+        Some((_, rule)) => match Recurrence::parse(rule, tz.as_ref()) {
+            Some(recurrence) => recurrence.occurrences(first, end),
+            None => {
+                debug!(
+                    "Unsupported recurrence {:?} of {:?}, only its first start is kept",
+                    rule, summary
+                );
+                vec![first]
+            }
+        },
+        None => vec![first],
+    };
+    starts
+        .into_iter()
+        .map(|start| Event {
+            start: if all_day {
+                start
+            } else {
+                localize(start, utc, tz.as_ref(), zone)
+            },
+            all_day,
+            summary: summary.clone(),
+        })
+        .collect()
+}
+
+/// Unescape a text value (backslashed commas, semicolons, backslashes and newlines)
+fn unescape(text: &str) -> String {
+    let mut unescaped = String::with_capacity(text.len());
+    let mut chars = text.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            unescaped.push(c);
+            continue;
+        }
+        match chars.next() {
+            // A single line is shown
+            Some('n') | Some('N') => unescaped.push(' '),
+            Some(c) => unescaped.push(c),
+            None => {}
+        }
+    }
+    unescaped
+}
+
+/// Parse the events of an ICS content, with their start in the given time zone.
+/// Recurring events give their occurrences until the given time.
+pub fn parse<B: BufRead, Z: TimeZone>(
+    reader: B,
+    zone: &Z,
+    end: NaiveDateTime,
+) -> Result<Vec<Event>, CalendarError> {
+    let mut events = Vec::new();
+    for calendar in IcalParser::new(reader) {
+        events.extend(
+            calendar?
+                .events
+                .iter()
+                .flat_map(|event| make_events(event, zone, end)),
+        );
+    }
+    Ok(events)
+}
+
+/// Parse the events of an ICS file
+pub fn parse_file<P: AsRef<Path>, Z: TimeZone>(
+    path: P,
+    zone: &Z,
+    end: NaiveDateTime,
+) -> Result<Vec<Event>, CalendarError> {
+    parse(BufReader::new(File::open(path)?), zone, end)
+}
+
+/// Load the events of all the ICS files of a directory and its subdirectories
+/// (e.g. a vdirsyncer storage). The invalid files are skipped.
+pub fn load_dir<P: AsRef<Path>, Z: TimeZone>(dir: P, zone: &Z, end: NaiveDateTime) -> Vec<Event> {
+    let entries = match fs::read_dir(dir.as_ref()) {
+        Ok(entries) => entries,
+        Err(err) => {
+            warn!(
+                "Can't read the calendar directory {:?}: {}",
+                dir.as_ref(),
+                err
+            );
+            return Vec::new();
+        }
+    };
+    let mut events = Vec::new();
+    for path in entries
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+    {
+        if path.is_dir() {
+            events.extend(load_dir(&path, zone, end));
+        } else if path.extension().is_some_and(|extension| extension == "ics") {
+            match parse_file(&path, zone, end) {
+                Ok(file_events) => events.extend(file_events),
+                Err(err) => warn!("Can't load the calendar file {:?}: {}", path, err),
+            }
+        }
+    }
+    events
+}
+
+#[cfg(test)]
+mod test {
+    use crate::app::calendar::ics::{load_dir, parse_file, Event};
+    use chrono::{NaiveDate, NaiveDateTime};
+    use chrono_tz::Tz;
+
+    /// The end of the recurring events
+    fn end() -> NaiveDateTime {
+        NaiveDate::from_ymd_opt(2025, 1, 1)
+            .unwrap()
+            .and_hms_opt(0, 0, 0)
+            .unwrap()
+    }
+
+    fn event(day: u32, hour: u32, minute: u32, all_day: bool, summary: &str) -> Event {
+        Event {
+            start: NaiveDate::from_ymd_opt(2020, 7, day)
+                .unwrap()
+                .and_hms_opt(hour, minute, 0)
+                .unwrap(),
+            all_day,
+            summary: summary.to_owned(),
+        }
+    }
+
+    /// The starts are given in the local time zone, whatever their form.
+    #[test]
+    fn parse_events() {
+        let events = parse_file(
+            "tests/fixtures/calendar/work/events.ics",
+            &Tz::Europe__Paris,
+            end(),
+        )
+        .unwrap();
+        assert_eq!(
+            events,
+            vec![
+                event(14, 0, 0, true, "Bastille Day"),
+                // 08:00 UTC
+                event(15, 10, 0, false, "Standup, with the whole team"),
+                // 09:30 in New York
+                event(16, 15, 30, false, "Call with the US office"),
+                // Floating
+                event(17, 12, 15, false, "Lunch"),
+            ]
+        );
+    }
+
+    /// All the ICS files of the directories are loaded, the invalid ones are skipped.
+    #[test]
+    fn load_directory() {
+        let mut events = load_dir("tests/fixtures/calendar", &Tz::Europe__Paris, end());
+        events.sort_by_key(|event| event.start);
+        assert_eq!(events.len(), 5);
+        assert_eq!(events[4], event(20, 18, 0, false, "Concert"));
+    }
+
+    /// The simple recurrence rules are repeated until their count, their end or the given end,
+    /// in the time zone of their start. The other rules only give their first start.
+    #[test]
+    fn recurring_events() {
+        let events = parse_file("tests/fixtures/recurring.ics", &Tz::Europe__Paris, end()).unwrap();
+        let starts = |summary: &str| -> Vec<String> {
+            events
+                .iter()
+                .filter(|event| event.summary == summary)
+                .map(|event| event.start.format("%Y-%m-%d %H:%M").to_string())
+                .collect()
+        };
+        // Every day at 08:00 UTC, across the end of the daylight saving time
+        assert_eq!(
+            starts("Standup"),
+            [
+                "2020-10-23 10:00",
+                "2020-10-24 10:00",
+                "2020-10-25 09:00",
+                "2020-10-26 09:00",
+            ]
+        );
+        // Every other week on monday and thursday, until the last thursday included
+        assert_eq!(
+            starts("Training"),
+            [
+                "2020-07-06 19:00",
+                "2020-07-09 19:00",
+                "2020-07-20 19:00",
+                "2020-07-23 19:00",
+            ]
+        );
+        // The months and years without the day are skipped
+        assert_eq!(
+            starts("Rent"),
+            [
+                "2020-01-31 00:00",
+                "2020-03-31 00:00",
+                "2020-05-31 00:00",
+                "2020-07-31 00:00",
+            ]
+        );
+        assert_eq!(starts("Birthday"), ["2020-02-29 00:00", "2024-02-29 00:00"]);
+        assert_eq!(starts("Meetup"), ["2020-07-06 18:30"]);
+    }
+}
@@ -0,0 +1,350 @@
+use crate::app::App;
+use crate::app::Application;
+use crate::component::{AppBar, Button, ButtonBar, List};
+use crate::display::G13Display;
+use crate::error::AppError;
+use crate::settings;
+use crate::style::{FILL_ON, TEXT_BOLD, TEXT_LIGHT, TEXT_SMALL};
+use crate::xdg;
+use async_trait::async_trait;
+use chrono::{Datelike, Local, Months, NaiveDate, NaiveDateTime, NaiveTime};
+use embedded_graphics::egrectangle;
+use embedded_graphics::fonts::{Font6x6, Text};
+use embedded_graphics::pixelcolor::BinaryColor;
+use embedded_graphics::prelude::*;
+use embedded_graphics::style::TextStyleBuilder;
+use ics::Event;
+use log::warn;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::marker::Unpin;
+use std::path::PathBuf;
+use tokio::io::AsyncWrite;
+use tokio::time;
+
+mod ics;
+
+/// The most events listed in the agenda
+const AGENDA_SIZE: usize = 50;
+/// How long the recurring events are repeated (in months from today)
+const RECURRENCE_MONTHS: u32 = 12;
+
+/// The content of the calendar file
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(default)]
+pub struct CalendarConfig {
+    /// The directories of the ICS files (searched recursively), "~/" is the home directory
+    pub directories: Vec<String>,
+}
+
+impl Default for CalendarConfig {
+    fn default() -> Self {
+        Self {
+            directories: vec!["~/.calendars".to_owned()],
+        }
+    }
+}
+
+impl CalendarConfig {
+    /// Load the calendar configuration (from `$XDG_CONFIG_HOME/g13-apps/calendar.toml`)
+    pub fn load() -> Self {
+        let path = match xdg::config_file("calendar.toml") {
+            Some(path) => path,
+            None => return Self::default(),
+        };
+        settings::read_toml(&path, "calendar")
+    }
+
+    /// Get the directories of the ICS files
+    pub fn get_directories(&self) -> Vec<PathBuf> {
+        self.directories
+            .iter()
+            .filter_map(|dir| xdg::expand_home(dir))
+            .collect()
+    }
+}
+
+/// The calendar app: a month grid, and the agenda of the upcoming events
+#[derive(Clone, Debug, Default)]
+pub struct Calendar {
+    /// Define if the app should end/return
+    end: bool,
+    /// The first day of the shown month (None for the current month)
+    month: Option<NaiveDate>,
+    /// The events of the ICS files, sorted by start (loaded on first execution)
+    events: Option<Vec<Event>>,
+    /// The agenda, if shown instead of the month
+    agenda: Option<List>,
+}
+
+#[async_trait(?Send)]
+impl Application for Calendar {
+    async fn execute<W: Unpin + AsyncWrite>(&mut self, out: &mut W) -> Result<App, AppError>
+    where
+        W: AsyncWrite + Unpin,
+    {
+        let mut interval = time::interval(settings::refresh());
+        let mut display = G13Display::new(out);
+        let today = Local::now().date_naive();
+        if self.events.is_none() {
+            // The recurring events are repeated for a while from today
+            let end = today
+                .checked_add_months(Months::new(RECURRENCE_MONTHS))
+                .unwrap_or(today)
+                .and_time(NaiveTime::MIN);
+            let mut events: Vec<Event> = CalendarConfig::load()
+                .get_directories()
+                .iter()
+                .flat_map(|dir| ics::load_dir(dir, &Local, end))
+                .collect();
+            events.sort_by_key(|event| event.start);
+            self.events = Some(events);
+        }
+        let events = self.events.as_deref().unwrap_or_default();
+
+        let agenda = match self.agenda.as_mut() {
+            Some(agenda) => agenda,
+            None => {
+                let month = self.month.unwrap_or_else(|| first_day(today));
+                make_month(month, today, events)
+                    .into_iter()
+                    .draw(&mut display)?;
+                display.flush().await?;
+                // Wait until user ask for the menu app
+                #[warn(clippy::while_immutable_condition)]
+                while !self.end {
+                    interval.tick().await;
+                }
+                return Ok(App::Menu(Default::default()));
+            }
+        };
+
+        AppBar::new("Agenda:", Point::zero(), Point::new(160, 8)).draw(&mut display)?;
+        let mut buttonbar: ButtonBar = Default::default();
+        buttonbar.set_button1(Some(Button::from_str("Month")));
+        buttonbar.set_button3(Some(Button::from_str("\u{25B2}")));
+        buttonbar.set_button4(Some(Button::from_str("\u{25BC}")));
+        buttonbar.draw(&mut display)?;
+
+        let mut last_cursor: usize = usize::MAX;
+        #[warn(clippy::while_immutable_condition)]
+        while !self.end {
+            // Tick and wait for new cursor (or a scrolling selection)
+            interval.tick().await;
+            if last_cursor == agenda.get_cursor() && !agenda.tick() {
+                continue;
+            }
+            last_cursor = agenda.get_cursor();
+
+            // Draw and flush
+            agenda.draw_default(&mut display)?;
+            display.flush().await?;
+        }
+
+        Ok(App::Menu(Default::default()))
+    }
+
+    /// Represents the agenda button (or back to the month)
+    async fn button_l1(&mut self) -> Result<(), AppError> {
+        if self.agenda.take().is_none() {
+            let events = self.events.as_deref().unwrap_or_default();
+            let entries = agenda_entries(events, Local::now().naive_local());
+            self.agenda = Some(List::new(entries));
+        }
+        Ok(())
+    }
+
+    /// Represents the today button (back to the current month)
+    async fn button_l2(&mut self) -> Result<(), AppError> {
+        self.month = None;
+        Ok(())
+    }
+
+    /// Represents the previous month button (or the previous event)
+    async fn button_l3(&mut self) -> Result<(), AppError> {
+        match self.agenda.as_mut() {
+            Some(agenda) => agenda.previous(),
+            None => self.month = self.shown_month().checked_sub_months(Months::new(1)),
+        }
+        Ok(())
+    }
+
+    /// Represents the next month button (or the next event)
+    async fn button_l4(&mut self) -> Result<(), AppError> {
+        match self.agenda.as_mut() {
+            Some(agenda) => agenda.next(),
+            None => self.month = self.shown_month().checked_add_months(Months::new(1)),
+        }
+        Ok(())
+    }
+
+    /// Represents the return button (go to menu)
+    async fn button_bd(&mut self) -> Result<(), AppError> {
+        self.end = true;
+        Ok(())
+    }
+}
+
+impl Calendar {
+    /// Get the first day of the shown month
+    fn shown_month(&self) -> NaiveDate {
+        self.month
+            .unwrap_or_else(|| first_day(Local::now().date_naive()))
+    }
+}
+
+/// Get the first day of the month of a date
+fn first_day(date: NaiveDate) -> NaiveDate {
+    date.with_day(1).unwrap_or(date)
+}
+
+/// Get the cells of a month: the row (week, starting on monday), the column and the day
+fn month_cells(first: NaiveDate) -> Vec<(i32, i32, u32)> {
+    let offset = first.weekday().num_days_from_monday() as i32;
+    first
+        .iter_days()
+        .take_while(|date| date.month() == first.month())
+        .map(|date| {
+            let cell = offset + date.day0() as i32;
+            (cell / 7, cell % 7, date.day())
+        })
+        .collect()
+}
+
+/// Make an drawable iterator of a month: its name and year on the left,
+/// then its days (today inverted, the days with events marked by a dot).
+fn make_month(first: NaiveDate, today: NaiveDate, events: &[Event]) -> Vec<Pixel<BinaryColor>> {
+    let inverted = TextStyleBuilder::new(Font6x6)
+        .text_color(BinaryColor::Off)
+        .background_color(BinaryColor::On)
+        .build();
+    let busy: HashSet<NaiveDate> = events.iter().map(|event| event.start.date()).collect();
+    let mut pixels: Vec<Pixel<BinaryColor>> = Vec::new();
+
+    // The month
+    let name = first.format("%b").to_string();
+    let year = first.format("%Y").to_string();
+    pixels.extend(&Text::new(&name, Point::new(4, 10)).into_styled(*TEXT_BOLD));
+    pixels.extend(&Text::new(&year, Point::new(4, 22)).into_styled(*TEXT_LIGHT));
+
+    // The grid: a header, then a row of 6px per week
+    let left = 44;
+    for (column, day) in ["Mo", "Tu", "We", "Th", "Fr", "Sa", "Su"]
+        .iter()
+        .enumerate()
+    {
+        let position = Point::new(left + 16 * column as i32, 0);
+        pixels.extend(&Text::new(day, position).into_styled(*TEXT_SMALL));
+    }
+    for (row, column, day) in month_cells(first) {
+        let position = Point::new(left + 16 * column, 7 + 6 * row);
+        let text = format!("{:>2}", day);
+        let date = first.with_day(day);
+        if date == Some(today) {
+            pixels.extend(&Text::new(&text, position).into_styled(inverted));
+        } else {
+            pixels.extend(&Text::new(&text, position).into_styled(*TEXT_SMALL));
+        }
+        if date.is_some_and(|date| busy.contains(&date)) {
+            pixels.extend(&egrectangle!(
+                top_left = position + Point::new(13, 3),
+                bottom_right = position + Point::new(14, 4),
+                style = *FILL_ON,
+            ));
+        }
+    }
+    pixels
+}
+
+/// Make the entries of the agenda: the upcoming events (started today or later)
+fn agenda_entries(events: &[Event], now: NaiveDateTime) -> Vec<String> {
+    let today = now.date().and_hms_opt(0, 0, 0).unwrap_or(now);
+    let entries: Vec<String> = events
+        .iter()
+        .filter(|event| event.start >= today)
+        .take(AGENDA_SIZE)
+        .map(|event| {
+            let time = if event.all_day {
+                "     ".to_owned()
+            } else {
+                event.start.format("%H:%M").to_string()
+            };
+            format!("{} {} {}", event.start.format("%a %d"), time, event.summary)
+        })
+        .collect();
+    if entries.is_empty() {
+        vec!["No upcoming event".to_owned()]
+    } else {
+        entries
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::app::calendar::ics::Event;
+    use crate::app::calendar::{agenda_entries, make_month, month_cells};
+    use chrono::NaiveDate;
+    use embedded_graphics::pixelcolor::BinaryColor;
+    use embedded_graphics::prelude::*;
+
+    fn date(month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2020, month, day).unwrap()
+    }
+
+    /// The weeks start on monday.
+    #[test]
+    fn month_grid() {
+        // July 2020 starts on a wednesday, and lasts 5 weeks
+        let cells = month_cells(date(7, 1));
+        assert_eq!(cells.len(), 31);
+        assert_eq!(cells[0], (0, 2, 1));
+        assert_eq!(cells[5], (1, 0, 6));
+        assert_eq!(cells[30], (4, 4, 31));
+        // A month can need 6 weeks
+        assert_eq!(month_cells(date(8, 1)).last(), Some(&(5, 0, 31)));
+    }
+
+    /// Today is inverted, within the screen.
+    #[test]
+    fn month_layout() {
+        let pixels = make_month(date(8, 1), date(8, 31), &[]);
+        assert!(pixels
+            .iter()
+            .all(|pixel| pixel.0.x >= 0 && pixel.0.x < 160 && pixel.0.y >= 0 && pixel.0.y < 43));
+        // The 31st, on the last row of monday
+        let cell = |pixel: &&Pixel<BinaryColor>| {
+            (44..56).contains(&pixel.0.x) && (37..43).contains(&pixel.0.y)
+        };
+        let on = pixels
+            .iter()
+            .filter(cell)
+            .filter(|pixel| pixel.1 == BinaryColor::On)
+            .count();
+        let off = pixels.iter().filter(cell).count() - on;
+        assert!(on > off);
+    }
+
+    /// The agenda lists the upcoming events only.
+    #[test]
+    fn agenda() {
+        let event = |day: u32, hour: u32, all_day: bool, summary: &str| Event {
+            start: date(7, day).and_hms_opt(hour, 0, 0).unwrap(),
+            all_day,
+            summary: summary.to_owned(),
+        };
+        let events = vec![
+            event(13, 9, false, "Past"),
+            event(14, 0, true, "Bastille Day"),
+            event(15, 10, false, "Standup"),
+        ];
+        let now = date(7, 14).and_hms_opt(12, 0, 0).unwrap();
+        assert_eq!(
+            agenda_entries(&events, now),
+            vec!["Tue 14       Bastille Day", "Wed 15 10:00 Standup"]
+        );
+        assert_eq!(
+            agenda_entries(&[], now),
+            vec!["No upcoming event".to_owned()]
+        );
+    }
+}
@@ -0,0 +1,262 @@
+use crate::app::clock::world::Zone;
+use crate::settings::{self, SettingsError};
+use crate::xdg;
+use chrono::format::{Item, StrftimeItems};
+use chrono::{DateTime, Locale, TimeZone};
+use chrono_tz::Tz;
+use log::warn;
+use serde::{Deserialize, Serialize};
+use std::convert::TryFrom;
+use std::fmt::Display;
+use std::path::PathBuf;
+
+/// A configured time zone, as written in the clock file
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct ZoneConfig {
+    /// The name shown for the time zone, usually a city
+    pub label: String,
+    /// The IANA name of the time zone (e.g. "Europe/Paris")
+    pub zone: String,
+}
+
+/// The content of the clock file
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(default)]
+pub struct ClockConfig {
+    /// The locale of the month and day names (e.g. "fr_FR")
+    pub locale: String,
+    /// Whether the time is shown with 12 hours (AM/PM)
+    pub hour12: bool,
+    /// The format of the time (strftime), by default given by `hour12`
+    pub time_format: Option<String>,
+    /// The format of the date (strftime)
+    pub date_format: String,
+    pub zones: Vec<ZoneConfig>,
+}
+
+impl Default for ClockConfig {
+    fn default() -> Self {
+        let zone = |label: &str, zone: &str| ZoneConfig {
+            label: label.to_owned(),
+            zone: zone.to_owned(),
+        };
+        Self {
+            locale: "en_US".to_owned(),
+            hour12: false,
+            time_format: None,
+            date_format: "%d %B %Y".to_owned(),
+            zones: vec![
+                zone("UTC", "UTC"),
+                zone("New York", "America/New_York"),
+                zone("London", "Europe/London"),
+                zone("Tokyo", "Asia/Tokyo"),
+            ],
+        }
+    }
+}
+
+impl ClockConfig {
+    /// Load the clock configuration from the given file (or the default one)
+    pub fn load(path: PathBuf) -> Self {
+        settings::read_toml(&path, "clock")
+    }
+
+    /// Save the clock configuration (in `$XDG_CONFIG_HOME/g13-apps/clock.toml`)
+    pub fn save(&self) -> Result<(), SettingsError> {
+        match xdg::config_file("clock.toml") {
+            Some(path) => settings::write_toml(&path, self),
+            None => Ok(()),
+        }
+    }
+
+    /// Get the time zones, the unknown ones are skipped
+    pub fn get_zones(&self) -> Vec<Zone> {
+        self.zones
+            .iter()
+            .filter_map(|config| match config.zone.parse::<Tz>() {
+                Ok(tz) => Some(Zone {
+                    label: config.label.clone(),
+                    tz,
+                }),
+                Err(err) => {
+                    warn!("Unknown time zone {:?}: {}", config.zone, err);
+                    None
+                }
+            })
+            .collect()
+    }
+
+    /// Get the formats of the time and date.
+    /// An unknown locale or an invalid format is replaced by the default one.
+    pub fn get_formats(&self) -> Formats {
+        let locale = Locale::try_from(self.locale.as_str()).unwrap_or_else(|_| {
+            warn!("Unknown locale {:?}", self.locale);
+            Locale::POSIX
+        });
+        let (time, short_time, zone_time, short_date) = if self.hour12 {
+            ("%I:%M:%S %p", "%I:%M", "%I:%M%p", "%a %d %b %p")
+        } else {
+            ("%H:%M:%S", "%H:%M", "%H:%M", "%a %d %b")
+        };
+        Formats {
+            locale,
+            time: checked(self.time_format.as_deref(), time),
+            short_time: short_time.to_owned(),
+            zone_time: zone_time.to_owned(),
+            date: checked(Some(&self.date_format), "%d %B %Y"),
+            short_date: short_date.to_owned(),
+        }
+    }
+}
+
+/// Get the given format if it is valid (formatting with an invalid one panics), else the default one
+fn checked(format: Option<&str>, default: &str) -> String {
+    match format {
+        Some(format) if StrftimeItems::new(format).any(|item| item == Item::Error) => {
+            warn!("Invalid time format {:?}", format);
+            default.to_owned()
+        }
+        Some(format) => format.to_owned(),
+        None => default.to_owned(),
+    }
+}
+
+/// Load the clock configuration (from `$XDG_CONFIG_HOME/g13-apps/clock.toml`)
+pub fn load() -> ClockConfig {
+    xdg::config_file("clock.toml").map_or_else(ClockConfig::default, ClockConfig::load)
+}
+
+/// The formats of the time and date, in a locale
+#[derive(Clone, Debug)]
+pub struct Formats {
+    locale: Locale,
+    time: String,
+    /// The time without seconds (nor AM/PM)
+    short_time: String,
+    /// The time without seconds, in the world clock
+    zone_time: String,
+    date: String,
+    /// The date with abbreviated names, then AM/PM for the 12 hours time
+    short_date: String,
+}
+
+impl Default for Formats {
+    fn default() -> Self {
+        ClockConfig::default().get_formats()
+    }
+}
+
+impl Formats {
+    /// Format a time in the given format and the locale
+    fn format<Z: TimeZone>(&self, time: &DateTime<Z>, format: &str) -> String
+    where
+        Z::Offset: Display,
+    {
+        time.format_localized(format, self.locale).to_string()
+    }
+
+    /// Format the time
+    pub fn time<Z: TimeZone>(&self, time: &DateTime<Z>) -> String
+    where
+        Z::Offset: Display,
+    {
+        self.format(time, &self.time)
+    }
+
+    /// Format the time without seconds
+    pub fn short_time<Z: TimeZone>(&self, time: &DateTime<Z>) -> String
+    where
+        Z::Offset: Display,
+    {
+        self.format(time, &self.short_time)
+    }
+
+    /// Format the time without seconds, for the world clock
+    pub fn zone_time<Z: TimeZone>(&self, time: &DateTime<Z>) -> String
+    where
+        Z::Offset: Display,
+    {
+        self.format(time, &self.zone_time)
+    }
+
+    /// Format the date
+    pub fn date<Z: TimeZone>(&self, time: &DateTime<Z>) -> String
+    where
+        Z::Offset: Display,
+    {
+        self.format(time, &self.date)
+    }
+
+    /// Format the date with abbreviated names
+    pub fn short_date<Z: TimeZone>(&self, time: &DateTime<Z>) -> String
+    where
+        Z::Offset: Display,
+    {
+        self.format(time, &self.short_date)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::app::clock::config::{ClockConfig, ZoneConfig};
+    use crate::app::clock::world::Zone;
+    use chrono::TimeZone;
+    use chrono_tz::Tz;
+
+    /// The unknown time zones are skipped.
+    #[test]
+    fn config_zones() {
+        let config = ClockConfig {
+            zones: vec![
+                ZoneConfig {
+                    label: "Paris".to_owned(),
+                    zone: "Europe/Paris".to_owned(),
+                },
+                ZoneConfig {
+                    label: "Atlantis".to_owned(),
+                    zone: "Ocean/Atlantis".to_owned(),
+                },
+            ],
+            ..Default::default()
+        };
+        let paris = Zone {
+            label: "Paris".to_owned(),
+            tz: Tz::Europe__Paris,
+        };
+        assert_eq!(config.get_zones(), vec![paris]);
+    }
+
+    /// The month and day names follow the locale, and the time the 12/24 hours choice.
+    #[test]
+    fn localized_formats() {
+        let time = Tz::Europe__Paris
+            .with_ymd_and_hms(2020, 7, 14, 22, 5, 9)
+            .unwrap();
+        let english = ClockConfig::default().get_formats();
+        assert_eq!(english.date(&time), "14 July 2020");
+        assert_eq!(english.time(&time), "22:05:09");
+
+        let french: ClockConfig = toml::from_str("locale = \"fr_FR\"").unwrap();
+        let french = french.get_formats();
+        assert_eq!(french.date(&time), "14 juillet 2020");
+        assert_eq!(french.short_date(&time), "mar. 14 juil.");
+
+        let german: ClockConfig =
+            toml::from_str("locale = \"de_DE\"\nhour12 = true\ndate_format = \"%A, %d. %B\"")
+                .unwrap();
+        let german = german.get_formats();
+        assert_eq!(german.date(&time), "Dienstag, 14. Juli");
+        assert_eq!(german.short_time(&time), "10:05");
+    }
+
+    /// An invalid format or locale is replaced by the default one.
+    #[test]
+    fn invalid_formats() {
+        let time = Tz::UTC.with_ymd_and_hms(2020, 7, 14, 22, 5, 9).unwrap();
+        let config: ClockConfig =
+            toml::from_str("locale = \"xx_XX\"\ntime_format = \"%Q\"\nhour12 = true").unwrap();
+        let formats = config.get_formats();
+        assert_eq!(formats.time(&time), "10:05:09 PM");
+        assert_eq!(formats.date(&time), "14 July 2020");
+    }
+}
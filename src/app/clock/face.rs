@@ -0,0 +1,343 @@
+use crate::app::clock::config::Formats;
+use crate::component::{BigText, TextBlock};
+use crate::style::{BORDER_1, FILL_ON, TEXT_BOLD, TEXT_LIGHT, TEXT_SMALL};
+use chrono::{DateTime, Local, Timelike};
+use embedded_graphics::egrectangle;
+use embedded_graphics::fonts::Text;
+use embedded_graphics::geometry::{Point, Size};
+use embedded_graphics::pixelcolor::BinaryColor;
+use embedded_graphics::prelude::*;
+use embedded_graphics::primitives::{Circle, Line};
+use ibm437::Ibm437Font8x8Bold;
+
+/// The faces of the local clock
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum Face {
+    /// The analog clock, with the numeric time and date
+    #[default]
+    Classic,
+    /// The analog clock only
+    Analog,
+    /// The numeric time in large digits
+    Digital,
+    /// The digits of the time in binary (BCD)
+    Binary,
+    /// The time in words, to the nearest five minutes
+    Word,
+}
+
+impl Face {
+    /// Get the next face (or the first after the last)
+    pub fn next(self) -> Self {
+        match self {
+            Face::Classic => Face::Analog,
+            Face::Analog => Face::Digital,
+            Face::Digital => Face::Binary,
+            Face::Binary => Face::Word,
+            Face::Word => Face::Classic,
+        }
+    }
+
+    /// Make an drawable iterator of the face at the given time, with a seconds hand or not
+    pub fn pixels(
+        self,
+        time: &DateTime<Local>,
+        seconds: bool,
+        formats: &Formats,
+    ) -> Vec<Pixel<BinaryColor>> {
+        match self {
+            Face::Classic => make_classic(time, seconds, formats),
+            Face::Analog => make_analog(time, Point::new(80, 21), seconds),
+            Face::Digital => make_digital(time, formats),
+            Face::Binary => make_binary(time),
+            Face::Word => make_word(time),
+        }
+    }
+}
+
+/// Get the angle of the hour hand (in degrees, clockwise from 12), moving with the minutes
+fn hour_angle<T: Timelike>(time: &T) -> f64 {
+    (time.hour() % 12) as f64 * 30.0 + time.minute() as f64 * 0.5
+}
+
+/// Get the angle of the minute hand (in degrees, clockwise from 12), moving with the seconds
+fn minute_angle<T: Timelike>(time: &T) -> f64 {
+    time.minute() as f64 * 6.0 + time.second() as f64 * 0.1
+}
+
+/// Get the angle of the seconds hand (in degrees, clockwise from 12)
+fn second_angle<T: Timelike>(time: &T) -> f64 {
+    time.second() as f64 * 6.0
+}
+
+/// Get the point at the given angle (in degrees, clockwise from 12) and distance of the center
+fn polar(center: Point, angle: f64, length: f64) -> Point {
+    let radians = (angle - 90.0).to_radians();
+    Point::new(
+        (radians.cos() * length + center.x as f64).round() as i32,
+        (radians.sin() * length + center.y as f64).round() as i32,
+    )
+}
+
+/// Make an drawable iterator of the analog clock, of radius 20 around the center
+pub fn make_analog<T: Timelike>(time: &T, center: Point, seconds: bool) -> Vec<Pixel<BinaryColor>> {
+    // Making the clock face
+    let circle = Circle::new(center, 20).into_styled(*BORDER_1).into_iter();
+    let numbers = [
+        ("12", -4, -16),
+        ("3", 14, -2),
+        ("6", -2, 14),
+        ("9", -17, -2),
+    ]
+    .iter()
+    .flat_map(|&(text, x, y)| {
+        Text::new(text, center + Point::new(x, y))
+            .into_styled(*TEXT_SMALL)
+            .into_iter()
+            .collect::<Vec<_>>()
+    });
+    // A tick for each hour without number
+    let ticks = (0..12).filter(|hour| hour % 3 != 0).flat_map(|hour| {
+        let angle = hour as f64 * 30.0;
+        Line::new(polar(center, angle, 17.0), polar(center, angle, 19.0))
+            .into_styled(*BORDER_1)
+            .into_iter()
+    });
+
+    // Making the hands
+    let hour_hand = Line::new(center, polar(center, hour_angle(time), 10.0))
+        .into_styled(*BORDER_1)
+        .into_iter();
+    let min_hand = Line::new(center, polar(center, minute_angle(time), 15.0))
+        .into_styled(*BORDER_1)
+        .into_iter();
+    let second_hand = Line::new(center, polar(center, second_angle(time), 17.0))
+        .into_styled(*BORDER_1)
+        .into_iter()
+        .filter(move |_| seconds);
+
+    circle
+        .chain(numbers)
+        .chain(ticks)
+        .chain(hour_hand)
+        .chain(min_hand)
+        .chain(second_hand)
+        .collect()
+}
+
+/// Make an drawable iterator of the analog clock, with the numeric time and date on its right
+fn make_classic(
+    time: &DateTime<Local>,
+    seconds: bool,
+    formats: &Formats,
+) -> Vec<Pixel<BinaryColor>> {
+    let numeric = formats.time(time);
+    let date = formats.date(time);
+    make_analog(time, Point::new(20, 20), seconds)
+        .into_iter()
+        .chain(&Text::new(&numeric, Point::new(50, 43 / 2 - 10)).into_styled(*TEXT_BOLD))
+        .chain(&Text::new(&date, Point::new(50, 43 / 2 + 2)).into_styled(*TEXT_LIGHT))
+        .collect()
+}
+
+/// Make an drawable iterator of the time in large digits, with the date below
+fn make_digital(time: &DateTime<Local>, formats: &Formats) -> Vec<Pixel<BinaryColor>> {
+    let date = formats.short_date(time);
+    let seconds = time.format(":%S").to_string();
+    // "HH:MM" (80px) and ":SS" (24px), centered
+    BigText::new(formats.short_time(time), Point::new(28, 6), 2)
+        .into_iter()
+        .chain(&Text::new(&seconds, Point::new(108, 14)).into_styled(*TEXT_BOLD))
+        .chain(
+            &Text::new(&date, Point::new(80 - 3 * date.chars().count() as i32, 28))
+                .into_styled(*TEXT_LIGHT),
+        )
+        .collect()
+}
+
+/// Get the six digits of the time (HHMMSS)
+fn binary_digits<T: Timelike>(time: &T) -> [u32; 6] {
+    let (hour, minute, second) = (time.hour(), time.minute(), time.second());
+    [
+        hour / 10,
+        hour % 10,
+        minute / 10,
+        minute % 10,
+        second / 10,
+        second % 10,
+    ]
+}
+
+/// Make an drawable iterator of the binary clock: a column of 4 bits per digit,
+/// the most significant bit on top. The bits set are filled.
+fn make_binary<T: Timelike>(time: &T) -> Vec<Pixel<BinaryColor>> {
+    binary_digits(time)
+        .iter()
+        .enumerate()
+        .flat_map(|(column, digit)| {
+            // Pairs of columns: hours, minutes and seconds
+            let x = 43 + 10 * column as i32 + 8 * (column as i32 / 2);
+            (0..4).flat_map(move |row| {
+                let top_left = Point::new(x, 3 + 10 * row);
+                let bottom_right = top_left + Size::new(6, 6);
+                let style = if digit & (8 >> row) != 0 {
+                    *FILL_ON
+                } else {
+                    *BORDER_1
+                };
+                egrectangle!(
+                    top_left = top_left,
+                    bottom_right = bottom_right,
+                    style = style,
+                )
+                .into_iter()
+            })
+        })
+        .collect()
+}
+
+/// Get the time in words, to the nearest five minutes (e.g. "It's quarter past ten")
+fn words<T: Timelike>(time: &T) -> String {
+    const HOURS: [&str; 12] = [
+        "twelve", "one", "two", "three", "four", "five", "six", "seven", "eight", "nine", "ten",
+        "eleven",
+    ];
+    let minutes = (time.minute() + 2) / 5 * 5;
+    let hour = |offset: u32| HOURS[((time.hour() + offset) % 12) as usize];
+    let phrase = match minutes {
+        0 => format!("{} o'clock", hour(0)),
+        5 => format!("five past {}", hour(0)),
+        10 => format!("ten past {}", hour(0)),
+        15 => format!("quarter past {}", hour(0)),
+        20 => format!("twenty past {}", hour(0)),
+        25 => format!("twenty-five past {}", hour(0)),
+        30 => format!("half past {}", hour(0)),
+        35 => format!("twenty-five to {}", hour(1)),
+        40 => format!("twenty to {}", hour(1)),
+        45 => format!("quarter to {}", hour(1)),
+        50 => format!("ten to {}", hour(1)),
+        55 => format!("five to {}", hour(1)),
+        // Rounded up to the next hour
+        _ => format!("{} o'clock", hour(1)),
+    };
+    format!("It's {}", phrase)
+}
+
+/// Make an drawable iterator of the time in words
+fn make_word<T: Timelike>(time: &T) -> Vec<Pixel<BinaryColor>> {
+    TextBlock::new(
+        words(time),
+        Point::new(0, 6),
+        Point::new(159, 42),
+        Ibm437Font8x8Bold,
+    )
+    .pixels(*TEXT_BOLD)
+}
+
+#[cfg(test)]
+mod test {
+    use crate::app::clock::config::Formats;
+    use crate::app::clock::face::{
+        binary_digits, hour_angle, make_analog, minute_angle, words, Face,
+    };
+    use chrono::{DateTime, Local, TimeZone};
+    use embedded_graphics::pixelcolor::BinaryColor;
+    use embedded_graphics::prelude::*;
+
+    fn at(hour: u32, minute: u32, second: u32) -> DateTime<Local> {
+        Local
+            .with_ymd_and_hms(2020, 7, 14, hour, minute, second)
+            .unwrap()
+    }
+
+    /// The hour hand moves with the minutes, and the minute hand with the seconds.
+    #[test]
+    fn analog_hands() {
+        assert_eq!(hour_angle(&at(15, 0, 0)), 90.0);
+        assert_eq!(hour_angle(&at(10, 30, 0)), 315.0);
+        assert_eq!(minute_angle(&at(10, 30, 30)), 183.0);
+        // The seconds hand is optional
+        let center = Point::new(80, 21);
+        let without = make_analog(&at(10, 30, 30), center, false);
+        let with = make_analog(&at(10, 30, 30), center, true);
+        assert!(with.len() > without.len());
+        assert!(with
+            .iter()
+            .all(|pixel| (pixel.0 - center).x.abs() <= 20 && (pixel.0 - center).y.abs() <= 20));
+    }
+
+    /// The classic face shows the analog clock, with the time above the date on its right.
+    #[test]
+    fn classic_face() {
+        let formats = Formats::default();
+        let pixels = Face::Classic.pixels(&at(10, 42, 7), true, &formats);
+        let clock: Vec<_> = pixels
+            .iter()
+            .filter(|pixel| pixel.0.x < 50)
+            .copied()
+            .collect();
+        assert_eq!(clock, make_analog(&at(10, 42, 7), Point::new(20, 20), true));
+        // The lit rows of the texts, on the right of the clock
+        let text = |pixels: &[Pixel<BinaryColor>], rows: std::ops::Range<i32>| -> Vec<Point> {
+            pixels
+                .iter()
+                .filter(|pixel| pixel.0.x >= 50 && rows.contains(&pixel.0.y))
+                .filter(|pixel| pixel.1 == BinaryColor::On)
+                .map(|pixel| pixel.0)
+                .collect()
+        };
+        assert!(!text(&pixels, 11..19).is_empty());
+        assert!(!text(&pixels, 23..31).is_empty());
+        assert_eq!(text(&pixels, 0..43).len(), text(&pixels, 11..31).len());
+        // A minute later, only the time changes
+        let later = Face::Classic.pixels(&at(10, 43, 7), true, &formats);
+        assert_ne!(text(&later, 11..19), text(&pixels, 11..19));
+        assert_eq!(text(&later, 23..31), text(&pixels, 23..31));
+        assert_eq!(Face::default(), Face::Classic);
+    }
+
+    /// The digital face shows the time in large digits.
+    #[test]
+    fn digital_face() {
+        let pixels = Face::Digital.pixels(&at(10, 42, 7), false, &Formats::default());
+        // Large digits: about 16px high
+        let mut rows: Vec<_> = pixels
+            .iter()
+            .filter(|pixel| pixel.0.x < 108 && pixel.0.y < 22 && pixel.1 == BinaryColor::On)
+            .map(|pixel| pixel.0.y)
+            .collect();
+        rows.sort_unstable();
+        rows.dedup();
+        assert!(rows.len() >= 12);
+        assert!(pixels
+            .iter()
+            .all(|pixel| pixel.0.x >= 0 && pixel.0.x < 160 && pixel.0.y < 43));
+    }
+
+    /// The binary face shows each digit of the time.
+    #[test]
+    fn binary_face() {
+        assert_eq!(binary_digits(&at(10, 42, 7)), [1, 0, 4, 2, 0, 7]);
+        let pixels = Face::Binary.pixels(&at(10, 42, 7), false, &Formats::default());
+        // 24 bits of 7x7 pixels
+        assert_eq!(pixels.len(), 24 * 7 * 7);
+        // 6 bits set: 1, 4, 2 and 7 (3 bits), filled
+        let filled = pixels
+            .iter()
+            .filter(|pixel| pixel.1 == BinaryColor::On)
+            .count();
+        assert_eq!(filled, 6 * (7 * 7) + 18 * (6 * 4));
+    }
+
+    /// The word face rounds to the nearest five minutes.
+    #[test]
+    fn word_face() {
+        assert_eq!(words(&at(10, 14, 0)), "It's quarter past ten");
+        assert_eq!(words(&at(10, 38, 0)), "It's twenty to eleven");
+        assert_eq!(words(&at(23, 58, 0)), "It's twelve o'clock");
+        assert_eq!(words(&at(0, 1, 0)), "It's twelve o'clock");
+        assert!(!Face::Word
+            .pixels(&at(10, 14, 0), false, &Formats::default())
+            .is_empty());
+    }
+}
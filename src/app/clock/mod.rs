@@ -0,0 +1,131 @@
+use crate::app::App;
+use crate::app::Application;
+use crate::display::G13Display;
+use crate::error::AppError;
+use crate::style::FILL_OFF;
+use async_trait::async_trait;
+use chrono::Local;
+use embedded_graphics::egrectangle;
+use embedded_graphics::geometry::Point;
+use embedded_graphics::prelude::*;
+use face::Face;
+use std::marker::Unpin;
+use std::str::FromStr;
+use std::time::Duration;
+use tokio::io::AsyncWrite;
+use tokio::time;
+use config::Formats;
+use world::Zone;
+
+pub(crate) mod config;
+mod face;
+mod world;
+
+/// The G13 Clock app
+/// Analog and numeric (with several faces), then the world clock pages
+#[derive(Clone, Debug, Default)]
+pub struct Clock {
+    /// Define if the app should end/return
+    end: bool,
+    /// The face of the local clock
+    face: Face,
+    /// Whether the analog faces show a seconds hand
+    seconds: bool,
+    /// The shown page: the local clock (0), then the pages of the world clock
+    page: usize,
+    /// The time zones of the world clock, loaded on first execution (with the formats)
+    zones: Option<Vec<Zone>>,
+    /// The formats of the time and date
+    formats: Formats,
+}
+
+#[async_trait(?Send)]
+impl Application for Clock {
+    async fn execute<W: Unpin + AsyncWrite>(&mut self, out: &mut W) -> Result<App, AppError>
+    where
+        W: AsyncWrite + Unpin,
+    {
+        let mut interval = time::interval(Duration::from_millis(500));
+        let mut display = G13Display::new(out);
+        if self.zones.is_none() {
+            let config = config::load();
+            self.zones = Some(config.get_zones());
+            self.formats = config.get_formats();
+        }
+
+        // Wait until user ask for the menu app
+        #[warn(clippy::while_immutable_condition)]
+        while !self.end && self.page > 0 {
+            let zones = self.zones.as_deref().unwrap_or_default();
+            let start = (self.page - 1) * world::ZONES_PER_PAGE;
+            let end = zones.len().min(start + world::ZONES_PER_PAGE);
+            world::make_world(&zones[start..end], &Local::now(), &self.formats)
+                .into_iter()
+                .draw(&mut display)?;
+
+            // Flush and await
+            display.flush().await?;
+            interval.tick().await;
+        }
+        #[warn(clippy::while_immutable_condition)]
+        while !self.end {
+            // Draw the clock face
+            egrectangle!(
+                top_left = Point::zero(),
+                bottom_right = Point::new(159, 42),
+                style = *FILL_OFF,
+            )
+            .draw(&mut display)?;
+            self.face
+                .pixels(&Local::now(), self.seconds, &self.formats)
+                .into_iter()
+                .draw(&mut display)?;
+
+            // Flush and await
+            display.flush().await?;
+            interval.tick().await;
+        }
+
+        // Return to menu app
+        Ok(App::Menu(Default::default()))
+    }
+
+    /// Represents the next face button
+    async fn button_l1(&mut self) -> Result<(), AppError> {
+        self.face = self.face.next();
+        Ok(())
+    }
+
+    /// Represents the seconds hand button (show or hide it)
+    async fn button_l2(&mut self) -> Result<(), AppError> {
+        self.seconds = !self.seconds;
+        Ok(())
+    }
+
+    /// Represents the previous page button (or the last page after the first)
+    async fn button_l3(&mut self) -> Result<(), AppError> {
+        let pages = self.pages();
+        self.page = (self.page + pages - 1) % pages;
+        Ok(())
+    }
+
+    /// Represents the next page button (or the first page after the last)
+    async fn button_l4(&mut self) -> Result<(), AppError> {
+        self.page = (self.page + 1) % self.pages();
+        Ok(())
+    }
+
+    /// Represents the return button (go to menu)
+    async fn button_bd(&mut self) -> Result<(), AppError> {
+        self.end = true;
+        Ok(())
+    }
+}
+
+impl Clock {
+    /// Get the number of pages: the local clock, then the world clock (at least one page)
+    fn pages(&self) -> usize {
+        let zones = self.zones.as_ref().map_or(0, Vec::len);
+        1 + zones.div_ceil(world::ZONES_PER_PAGE).max(1)
+    }
+}
@@ -0,0 +1,114 @@
+use crate::app::clock::config::Formats;
+use crate::style::TEXT_LIGHT;
+use chrono::{DateTime, TimeZone};
+use chrono_tz::Tz;
+use embedded_graphics::drawable::Pixel;
+use embedded_graphics::fonts::Text;
+use embedded_graphics::geometry::Point;
+use embedded_graphics::pixelcolor::BinaryColor;
+
+/// The number of time zones shown on a page
+pub const ZONES_PER_PAGE: usize = 5;
+
+/// A time zone of the world clock
+#[derive(Clone, Debug, PartialEq)]
+pub struct Zone {
+    pub label: String,
+    pub tz: Tz,
+}
+
+/// Make the row of a time zone: its label, its time and its day offset
+/// ("+1" if it is already tomorrow there, "-1" if it is still yesterday).
+pub fn make_row<Z: TimeZone>(zone: &Zone, now: &DateTime<Z>, formats: &Formats) -> String {
+    let there = now.with_timezone(&zone.tz);
+    let time = formats.zone_time(&there);
+    let offset = (there.date_naive() - now.date_naive()).num_days();
+    let marker = match offset {
+        0 => String::new(),
+        offset => format!("{:+}", offset),
+    };
+    // 26 columns of 6px: the label, the time and the marker
+    let width = 26usize.saturating_sub(time.chars().count() + 4);
+    let label: String = zone.label.chars().take(width).collect();
+    format!("{:width$} {} {:>2}", label, time, marker, width = width)
+}
+
+/// Make an drawable iterator of a page of the world clock
+pub fn make_world<Z: TimeZone>(
+    zones: &[Zone],
+    now: &DateTime<Z>,
+    formats: &Formats,
+) -> Vec<Pixel<BinaryColor>> {
+    if zones.is_empty() {
+        return Text::new("No time zone", Point::new(1, 17))
+            .into_styled(*TEXT_LIGHT)
+            .into_iter()
+            .collect();
+    }
+    zones
+        .iter()
+        .enumerate()
+        .flat_map(|(i, zone)| {
+            Text::new(
+                &make_row(zone, now, formats),
+                Point::new(1, 1 + 8 * i as i32),
+            )
+            .into_styled(*TEXT_LIGHT)
+            .into_iter()
+            .collect::<Vec<_>>()
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use crate::app::clock::config::{ClockConfig, Formats};
+    use crate::app::clock::world::{make_row, Zone};
+    use chrono::TimeZone;
+    use chrono_tz::Tz;
+
+    fn zone(label: &str, tz: Tz) -> Zone {
+        Zone {
+            label: label.to_owned(),
+            tz,
+        }
+    }
+
+    /// The rows show the time there, and the day offset from here.
+    #[test]
+    fn day_offset() {
+        let formats = Formats::default();
+        let now = Tz::Europe__Paris
+            .with_ymd_and_hms(2020, 7, 14, 23, 30, 0)
+            .unwrap();
+        assert_eq!(
+            make_row(&zone("Tokyo", Tz::Asia__Tokyo), &now, &formats),
+            "Tokyo             06:30 +1"
+        );
+        assert_eq!(
+            make_row(&zone("Paris", Tz::Europe__Paris), &now, &formats),
+            "Paris             23:30   "
+        );
+        let morning = Tz::Europe__Paris
+            .with_ymd_and_hms(2020, 7, 14, 1, 0, 0)
+            .unwrap();
+        assert_eq!(
+            make_row(
+                &zone("San Francisco", Tz::America__Los_Angeles),
+                &morning,
+                &formats
+            ),
+            "San Francisco     16:00 -1"
+        );
+        // The labels are shortened for the 12 hours time
+        let hour12: ClockConfig = toml::from_str("hour12 = true").unwrap();
+        assert_eq!(
+            make_row(
+                &zone("San Francisco", Tz::America__Los_Angeles),
+                &morning,
+                &hour12.get_formats()
+            ),
+            "San Francisco   04:00PM -1"
+        );
+    }
+}
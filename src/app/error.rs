@@ -1,14 +1,15 @@
 use crate::app::App;
 use crate::app::Application;
-use crate::component::AppBar;
+use crate::component::{AppBar, Button, ButtonBar, TextBlock};
 use crate::display::G13Display;
 use crate::error::AppError;
-use crate::style::{TEXT_LIGHT, TEXT_SMALL};
+use crate::settings;
+use crate::style::TEXT_LIGHT;
 use async_trait::async_trait;
-use embedded_graphics::{fonts::Text, prelude::*};
+use chrono::{DateTime, Local};
+use embedded_graphics::{fonts::Font6x8, prelude::*};
 use std::marker::Unpin;
 use std::str::FromStr;
-use std::time::Duration;
 use tokio::io::AsyncWrite;
 use tokio::time;
 
@@ -19,39 +20,89 @@ use tokio::time;
 pub struct Error {
     /// Define if the app should end/return
     end: bool,
-    error: String,
+    /// The error message followed by the messages of its sources
+    chain: Vec<String>,
+    /// When the error occured
+    time: DateTime<Local>,
+    /// The name of the app which failed
+    origin: Option<String>,
+    /// A new instance of the failed app, to retry it
+    retry: Option<Box<App>>,
+    /// The app chosen by the user (None to go to the return app)
+    next: Option<Box<App>>,
+    /// The error text, word-wrapped
+    text: TextBlock,
     return_to: String,
 }
 
 impl Default for Error {
     // By default, no error has occured, so it's an error to open this app.
     fn default() -> Self {
-        Self {
-            end: true,
-            error: "Oops\nNo error has occured\nSorry.".to_owned(),
-            return_to: "menu".to_owned(),
-        }
+        let mut error = Self::new("Oops\nNo error has occured\nSorry.");
+        error.end = true;
+        error
     }
 }
 
 impl Error {
     /// Create an Error app with the given error
     pub fn new<S: std::fmt::Display>(error: S) -> Self {
-        Self {
-            end: false,
-            error: error.to_string(),
-            return_to: "menu".to_owned(),
+        Self::with_return(error, "menu")
+    }
+
+    /// Create an Error app showing the given error and its sources
+    pub fn from_error<E: std::error::Error + ?Sized>(error: &E) -> Self {
+        let mut app = Self::new(error);
+        let mut source = error.source();
+        while let Some(err) = source {
+            app.chain.push(err.to_string());
+            source = err.source();
         }
+        app.update_text();
+        app
     }
 
     /// Create an Error app with the given error and return app.
     /// The return app should be a valid string in the crate::app::App enum.
     pub fn with_return<E: std::fmt::Display, S: Into<String>>(error: E, return_to: S) -> Self {
-        Self {
+        let mut app = Self {
             end: false,
-            error: error.to_string(),
+            chain: vec![error.to_string()],
+            time: Local::now(),
+            origin: None,
+            retry: None,
+            next: None,
+            text: text_block(""),
             return_to: return_to.into(),
+        };
+        app.update_text();
+        app
+    }
+
+    /// Set the app which failed, so the user can retry it
+    pub fn with_origin(mut self, app: &App) -> Self {
+        self.origin = Some(app.name().to_owned());
+        self.retry = Some(Box::new(app.restart()));
+        self.update_text();
+        self
+    }
+
+    /// Get the error message followed by its sources
+    pub fn get_chain(&self) -> &[String] {
+        &self.chain
+    }
+
+    /// Build the text shown: when and where the error occured, then the error chain
+    fn update_text(&mut self) {
+        let mut text = self.time.format("At %H:%M:%S").to_string();
+        if let Some(origin) = &self.origin {
+            text.push_str(&format!(" in {}", origin));
         }
+        for (i, message) in self.chain.iter().enumerate() {
+            let prefix = if i == 0 { "" } else { "Caused by: " };
+            text.push_str(&format!("\n{}{}", prefix, message));
+        }
+        self.text = text_block(text);
     }
 }
 
@@ -61,72 +112,130 @@ impl Application for Error {
     where
         W: AsyncWrite + Unpin,
     {
-        let mut interval = time::interval(Duration::from_millis(500));
+        let mut interval = time::interval(settings::refresh());
         let mut display = G13Display::new(out);
+        let pages = self.text.pages();
 
-        // Setup the appbar
-        AppBar::new("An error occured:", Point::zero(), Point::new(160, 10)).draw(&mut display)?;
-
-        // Print the lines 1-3 to the app
-        let mut offset = 12;
-        let mut lines = self.error.clone();
-        for _ in 1..=3 {
-            // Get the number of chars to print
-            let chars = if lines.len() < 26 { lines.len() } else { 26 };
-            // Get the chars and print
-            let line: String = lines.drain(..chars).collect();
-            Text::new(&line, Point::new(0, offset))
-                .into_styled(*TEXT_LIGHT)
-                .draw(&mut display)?;
-
-            offset += 8;
+        // Setup the button bar: retry, menu and the pages scrolling
+        let mut buttonbar: ButtonBar = Default::default();
+        if self.retry.is_some() {
+            buttonbar.set_button1(Some(Button::from_str("Retry")));
         }
+        buttonbar.set_button2(Some(Button::from_str("Menu")));
+        if pages > 1 {
+            buttonbar.set_button3(Some(Button::from_str("\u{25B2}")));
+            buttonbar.set_button4(Some(Button::from_str("\u{25BC}")));
+        }
+        buttonbar.draw(&mut display)?;
 
-        // Added a more info in the buttom line
-        Text::new(
-            "More info in logs... Use BD to continue...",
-            Point::new(0, 38),
-        )
-        .into_styled(*TEXT_SMALL)
-        .draw(&mut display)?;
-
-        display.flush().await?;
-
-        // Wait until user ask to continue
+        // Wait until user ask to continue, and show the page asked
+        let mut last_page = usize::MAX;
         #[warn(clippy::while_immutable_condition)]
         while !self.end {
+            if last_page != self.text.get_page() {
+                last_page = self.text.get_page();
+                // Setup the appbar, with the page number
+                let title = if pages > 1 {
+                    format!("An error occured ({}/{}):", last_page + 1, pages)
+                } else {
+                    "An error occured:".to_owned()
+                };
+                AppBar::new(title, Point::zero(), Point::new(160, 10)).draw(&mut display)?;
+                self.text
+                    .pixels(*TEXT_LIGHT)
+                    .into_iter()
+                    .draw(&mut display)?;
+                display.flush().await?;
+            }
             interval.tick().await;
         }
 
+        if let Some(app) = self.next.take() {
+            return Ok(*app);
+        }
         match App::from_str(&self.return_to) {
             Ok(app) => Ok(app),
             Err(err) => Err(AppError::UnknownApp{name: self.return_to.clone(), source: err})
         }
     }
 
-    /// Not used
+    /// Represents the retry button (restart the failed app)
     async fn button_l1(&mut self) -> Result<(), AppError> {
+        if let Some(app) = self.retry.take() {
+            self.next = Some(app);
+            self.end = true;
+        }
         Ok(())
     }
 
-    /// Not used
+    /// Represents the menu button
     async fn button_l2(&mut self) -> Result<(), AppError> {
+        self.next = Some(Box::new(App::Menu(Default::default())));
+        self.end = true;
         Ok(())
     }
 
-    /// Not used
+    /// Represents the previous page button
     async fn button_l3(&mut self) -> Result<(), AppError> {
+        self.text.previous_page();
         Ok(())
     }
 
-    /// Not used
+    /// Represents the next page button
     async fn button_l4(&mut self) -> Result<(), AppError> {
+        self.text.next_page();
         Ok(())
     }
 
-    /// Represents the return button (go to menu)
+    /// Represents the return button (go to the return app)
     async fn button_bd(&mut self) -> Result<(), AppError> {
         self.end = true;
         Ok(())
     }
+
+    /// The backlight turns red while an error is shown
+    fn backlight(&self) -> Option<[u8; 3]> {
+        Some([255, 0, 0])
+    }
+}
+
+/// Create the text block of the error, between the app bar and the button bar
+fn text_block<T: Into<String>>(text: T) -> TextBlock {
+    TextBlock::new(text, Point::new(0, 11), Point::new(159, 34), Font6x8)
+}
+
+#[cfg(test)]
+mod test {
+    use crate::app::error::Error;
+    use crate::app::App;
+    use crate::error::AppError;
+    use std::str::FromStr;
+
+    /// The whole source chain is kept.
+    #[test]
+    fn source_chain() {
+        let source = App::from_str("unknown").unwrap_err();
+        let error = AppError::UnknownApp {
+            name: "unknown".to_owned(),
+            source,
+        };
+        let app = Error::from_error(&error);
+        assert_eq!(
+            app.get_chain(),
+            &[
+                "app named unknown does not exist".to_owned(),
+                "Matching variant not found".to_owned()
+            ]
+        );
+        assert!(app.retry.is_none());
+    }
+
+    /// The failed app can be retried.
+    #[test]
+    fn retry_origin() {
+        let menu = App::from_str("menu").unwrap();
+        let app = Error::new("Oops").with_origin(&menu);
+        assert_eq!(app.origin.as_deref(), Some("menu"));
+        assert!(matches!(app.retry.as_deref(), Some(App::Menu(_))));
+    }
 }
@@ -0,0 +1,154 @@
+use crate::app::App;
+use crate::app::Application;
+use crate::component::AppBar;
+use crate::display::G13Display;
+use crate::error::AppError;
+use crate::style::{FILL_OFF, TEXT_LIGHT};
+use async_trait::async_trait;
+use embedded_graphics::egrectangle;
+use embedded_graphics::{fonts::Text, prelude::*};
+use std::marker::Unpin;
+use std::time::Duration;
+use tokio::io::AsyncWrite;
+use tokio::time;
+
+/// The warning that no key is received from the driver, covering the app opened at startup.
+///
+/// The driver is probably missing the binds of the g13 apps:
+/// the warning is shown until a key is finally received.
+#[derive(Debug)]
+pub struct KeyCheck {
+    /// Define if the app should end/return
+    end: bool,
+    /// The app covered, restored once a key is received
+    previous: Option<Box<App>>,
+}
+
+impl Default for KeyCheck {
+    // No warning: the keys are considered received, and the menu is opened at once
+    fn default() -> Self {
+        Self {
+            end: true,
+            previous: None,
+        }
+    }
+}
+
+impl KeyCheck {
+    /// The delay for a key press after the startup, before warning that the keys are not received
+    pub const TIMEOUT: Duration = Duration::from_secs(15);
+
+    /// Create the warning covering the given app, restored once a key is received
+    pub fn new(previous: App) -> Self {
+        Self {
+            end: false,
+            previous: Some(Box::new(previous)),
+        }
+    }
+
+    /// Get the app covered, restored afterwards
+    pub fn get_previous(&mut self) -> Option<&mut App> {
+        self.previous.as_deref_mut()
+    }
+
+    /// Any key is received: the check is over
+    fn received(&mut self) -> Result<(), AppError> {
+        self.end = true;
+        Ok(())
+    }
+}
+
+#[async_trait(?Send)]
+impl Application for KeyCheck {
+    async fn execute<W: Unpin + AsyncWrite>(&mut self, out: &mut W) -> Result<App, AppError>
+    where
+        W: AsyncWrite + Unpin,
+    {
+        if !self.end {
+            let mut display = G13Display::new(out);
+            egrectangle!(
+                top_left = Point::zero(),
+                bottom_right = Point::new(159, 42),
+                style = *FILL_OFF,
+            )
+            .draw(&mut display)?;
+            AppBar::new("No key received!", Point::zero(), Point::new(160, 8))
+                .draw(&mut display)?;
+            for (i, line) in [
+                "The driver should bind",
+                "L1-L4 and BD to >L1...",
+                "See g13-apps.bind.",
+            ]
+            .iter()
+            .enumerate()
+            {
+                Text::new(line, Point::new(4, 12 + 10 * i as i32))
+                    .into_styled(*TEXT_LIGHT)
+                    .draw(&mut display)?;
+            }
+            display.flush().await?;
+        }
+
+        let mut interval = time::interval(Duration::from_secs(1));
+        #[warn(clippy::while_immutable_condition)]
+        while !self.end {
+            interval.tick().await;
+        }
+
+        Ok(match self.previous.take() {
+            Some(previous) => *previous,
+            None => App::Menu(Default::default()),
+        })
+    }
+
+    /// Any key ends the warning
+    async fn button_l1(&mut self) -> Result<(), AppError> {
+        self.received()
+    }
+
+    /// Any key ends the warning
+    async fn button_l2(&mut self) -> Result<(), AppError> {
+        self.received()
+    }
+
+    /// Any key ends the warning
+    async fn button_l3(&mut self) -> Result<(), AppError> {
+        self.received()
+    }
+
+    /// Any key ends the warning
+    async fn button_l4(&mut self) -> Result<(), AppError> {
+        self.received()
+    }
+
+    /// Any key ends the warning
+    async fn button_bd(&mut self) -> Result<(), AppError> {
+        self.received()
+    }
+
+    /// Red while the keys are not received
+    fn backlight(&self) -> Option<[u8; 3]> {
+        Some([255, 0, 0])
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::app::keycheck::KeyCheck;
+    use crate::app::{App, Application};
+    use std::io::Cursor;
+    use tokio::runtime::Runtime;
+
+    /// Any key restores the covered app, without being passed to it.
+    #[test]
+    fn key_received() {
+        let mut rt = Runtime::new().unwrap();
+        let mut app = KeyCheck::new(App::Logs(Default::default()));
+        assert_eq!(app.backlight(), Some([255, 0, 0]));
+        rt.block_on(app.button_l3()).unwrap();
+        let mut writer = Cursor::new(Vec::<u8>::new());
+        let next = rt.block_on(app.execute(&mut writer)).unwrap();
+        assert_eq!(next.name(), "logs");
+        assert!(writer.into_inner().is_empty());
+    }
+}
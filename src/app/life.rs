@@ -0,0 +1,220 @@
+use crate::app::App;
+use crate::app::Application;
+use crate::display::G13Display;
+use crate::error::AppError;
+use crate::life::{Life, Pattern};
+use crate::random::Rng;
+use crate::style::{FILL_OFF, TEXT_SMALL};
+use async_trait::async_trait;
+use embedded_graphics::egrectangle;
+use embedded_graphics::fonts::Text;
+use embedded_graphics::pixelcolor::BinaryColor;
+use embedded_graphics::prelude::*;
+use std::marker::Unpin;
+use std::time::{Duration, Instant};
+use tokio::io::AsyncWrite;
+use tokio::time;
+
+/// The size of the world: the whole LCD, one cell by pixel
+const WIDTH: i32 = 160;
+const HEIGHT: i32 = 43;
+/// The delays between two generations (in milliseconds), slowest first
+const PERIODS: [u64; 6] = [1000, 500, 200, 100, 50, 20];
+/// The speed at start (an index of the periods)
+const DEFAULT_SPEED: usize = 3;
+/// The interval between two checks of the clock
+const TICK: Duration = Duration::from_millis(10);
+/// How long the status is shown after a key
+const STATUS_DELAY: Duration = Duration::from_secs(2);
+
+/// Conway's game of life on the whole screen, wrapping around its edges.
+///
+/// L1 pauses, L2 starts the next pattern, L3 and L4 slow down and speed up.
+#[derive(Debug)]
+pub struct GameOfLife {
+    /// Define if the app should end/return
+    end: bool,
+    /// The running world (None until started)
+    life: Option<Life>,
+    /// The pattern of the running world
+    pattern: Pattern,
+    /// The speed, an index of the periods
+    speed: usize,
+    paused: bool,
+    /// The time of the last generation
+    last_step: Option<Instant>,
+    /// The time of the last key, to show the status for a while
+    last_key: Option<Instant>,
+}
+
+impl Default for GameOfLife {
+    fn default() -> Self {
+        Self {
+            end: false,
+            life: None,
+            pattern: Pattern::Soup,
+            speed: DEFAULT_SPEED,
+            paused: false,
+            last_step: None,
+            last_key: None,
+        }
+    }
+}
+
+impl GameOfLife {
+    /// Start the world of the current pattern
+    fn restart(&mut self) {
+        let mut rng = Rng::from_time();
+        self.life = Some(Life::with_pattern(WIDTH, HEIGHT, self.pattern, &mut rng));
+        self.last_step = None;
+    }
+
+    /// Get the delay between two generations
+    fn period(&self) -> Duration {
+        Duration::from_millis(PERIODS[self.speed])
+    }
+
+    /// Show the status after a key
+    fn key(&mut self) -> Result<(), AppError> {
+        self.last_key = Some(Instant::now());
+        Ok(())
+    }
+
+    /// Make the status line: the pattern, the generation and the speed
+    fn status(&self) -> String {
+        let generation = self.life.as_ref().map_or(0, Life::get_generation);
+        let speed = if self.paused {
+            "paused".to_owned()
+        } else {
+            format!("{}/s", 1000 / PERIODS[self.speed])
+        };
+        format!("{} #{} {}", self.pattern.name(), generation, speed)
+    }
+}
+
+#[async_trait(?Send)]
+impl Application for GameOfLife {
+    async fn execute<W: Unpin + AsyncWrite>(&mut self, out: &mut W) -> Result<App, AppError>
+    where
+        W: AsyncWrite + Unpin,
+    {
+        let mut interval = time::interval(TICK);
+        let mut display = G13Display::new(out);
+        if self.life.is_none() {
+            self.restart();
+        }
+
+        // Draw at once (the key pressed may have changed the world)
+        let mut changed = true;
+        let mut shown = false;
+        #[warn(clippy::while_immutable_condition)]
+        while !self.end {
+            let period = self.period();
+            let life = match self.life.as_mut() {
+                Some(life) => life,
+                None => break,
+            };
+            // No catching up: a slow display only slows the world down
+            let now = Instant::now();
+            let last_step = self.last_step.get_or_insert(now);
+            if !self.paused && now.duration_since(*last_step) >= period {
+                *last_step = now;
+                life.step();
+                changed = true;
+            }
+
+            let status = self
+                .last_key
+                .is_some_and(|last_key| now.duration_since(last_key) < STATUS_DELAY);
+            if changed || status != shown {
+                changed = false;
+                shown = status;
+                egrectangle!(
+                    top_left = Point::zero(),
+                    bottom_right = Point::new(WIDTH - 1, HEIGHT - 1),
+                    style = *FILL_OFF,
+                )
+                .draw(&mut display)?;
+                life.alive()
+                    .map(|(x, y)| Pixel(Point::new(x, y), BinaryColor::On))
+                    .draw(&mut display)?;
+                if status {
+                    egrectangle!(
+                        top_left = Point::new(0, HEIGHT - 8),
+                        bottom_right = Point::new(WIDTH - 1, HEIGHT - 1),
+                        style = *FILL_OFF,
+                    )
+                    .draw(&mut display)?;
+                    Text::new(&self.status(), Point::new(1, HEIGHT - 7))
+                        .into_styled(*TEXT_SMALL)
+                        .draw(&mut display)?;
+                }
+                display.flush().await?;
+            }
+            interval.tick().await;
+        }
+
+        Ok(App::Menu(Default::default()))
+    }
+
+    /// Represents the pause button
+    async fn button_l1(&mut self) -> Result<(), AppError> {
+        self.paused = !self.paused;
+        self.key()
+    }
+
+    /// Represents the next pattern button
+    async fn button_l2(&mut self) -> Result<(), AppError> {
+        self.pattern = self.pattern.next();
+        self.restart();
+        self.key()
+    }
+
+    /// Represents the slower button
+    async fn button_l3(&mut self) -> Result<(), AppError> {
+        self.speed = self.speed.saturating_sub(1);
+        self.key()
+    }
+
+    /// Represents the faster button
+    async fn button_l4(&mut self) -> Result<(), AppError> {
+        self.speed = (self.speed + 1).min(PERIODS.len() - 1);
+        self.key()
+    }
+
+    /// Represents the return button (go to menu)
+    async fn button_bd(&mut self) -> Result<(), AppError> {
+        self.end = true;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::app::life::{GameOfLife, PERIODS};
+    use crate::app::Application;
+    use crate::life::Pattern;
+    use tokio::runtime::Runtime;
+
+    /// The keys pause, change the pattern and the speed (within its bounds).
+    #[test]
+    fn keys() {
+        let mut rt = Runtime::new().unwrap();
+        let mut app = GameOfLife::default();
+        assert_eq!(app.status(), "Random #0 10/s");
+        rt.block_on(app.button_l1()).unwrap();
+        assert_eq!(app.status(), "Random #0 paused");
+        rt.block_on(app.button_l1()).unwrap();
+        for _ in 0..PERIODS.len() {
+            rt.block_on(app.button_l4()).unwrap();
+        }
+        assert_eq!(app.status(), "Random #0 50/s");
+        for _ in 0..PERIODS.len() {
+            rt.block_on(app.button_l3()).unwrap();
+        }
+        assert_eq!(app.status(), "Random #0 1/s");
+        rt.block_on(app.button_l2()).unwrap();
+        assert_eq!(app.pattern, Pattern::GliderGun);
+        assert_eq!(app.life.as_ref().map(|life| life.population()), Some(36));
+    }
+}
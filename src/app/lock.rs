@@ -0,0 +1,335 @@
+use crate::app::App;
+use crate::app::Application;
+use crate::display::G13Display;
+use crate::driver::{G13Driver, Led, LedStates};
+use crate::error::AppError;
+use crate::lock::LockConfig;
+use crate::profile::{self, ProfileConfig};
+use crate::protocol::{Key, Message};
+use crate::style::{FILL_OFF, TEXT_LIGHT};
+use async_trait::async_trait;
+use chrono::Local;
+use embedded_graphics::egrectangle;
+use embedded_graphics::fonts::{Font12x16, Text};
+use embedded_graphics::pixelcolor::BinaryColor;
+use embedded_graphics::prelude::*;
+use embedded_graphics::style::TextStyleBuilder;
+use log::{error, info, warn};
+use std::marker::Unpin;
+use std::time::Duration;
+use tokio::io::AsyncWrite;
+use tokio::time;
+
+/// The number of G keys
+const G_KEYS: u8 = 22;
+
+/// The lock screen, showing only a clock until it is unlocked
+/// by typing the PIN on the G keys, or by the unlock of the session.
+///
+/// It takes over the foreground app, which is restored afterwards.
+/// While locked, the G keys are bound to the g13 apps (so they don't type anything),
+/// then the profile is loaded again.
+#[derive(Debug)]
+pub struct Lock {
+    /// Define if the app should end/return
+    end: bool,
+    /// The app in foreground when locked
+    previous: Option<Box<App>>,
+    /// The G keys to type to unlock
+    pin: Vec<Key>,
+    /// The G keys typed so far
+    typed: Vec<Key>,
+    /// Define if the G keys are bound to the g13 apps
+    bound: bool,
+}
+
+impl Default for Lock {
+    // Already unlocked, ending at once on the menu (as left in place by App::take_lock)
+    fn default() -> Self {
+        Self {
+            end: true,
+            previous: None,
+            pin: Vec::new(),
+            typed: Vec::new(),
+            bound: false,
+        }
+    }
+}
+
+impl Lock {
+    /// Create the lock screen with the given PIN, over the given app
+    pub fn new(previous: App, pin: Vec<Key>) -> Self {
+        Self {
+            end: false,
+            previous: Some(Box::new(previous)),
+            pin,
+            typed: Vec::new(),
+            bound: false,
+        }
+    }
+
+    /// Create the lock screen again, still locked (over the menu, as the app can't be copied)
+    pub fn restart(&self) -> Self {
+        Self::new(App::Menu(Default::default()), self.pin.clone())
+    }
+
+    /// Unlock (the session was unlocked)
+    pub fn unlock(&mut self) {
+        self.end = true;
+    }
+
+    /// Bind the G keys to the g13 apps, to type the PIN
+    async fn bind_keys<W: Unpin + AsyncWrite>(&mut self, out: &mut W) -> Result<(), AppError> {
+        // Restored even if only some keys are bound
+        self.bound = true;
+        let mut driver = G13Driver::new(out);
+        for number in 1..=G_KEYS {
+            driver.send(&Message::bind_output(Key::G(number))).await?;
+        }
+        Ok(())
+    }
+
+    /// Restore the G keys, loading the profile again
+    async fn restore_keys<W: Unpin + AsyncWrite>(&self, out: &mut W) -> Result<(), AppError> {
+        match LockConfig::load().get_profile() {
+            Some(name) => {
+                let directory = ProfileConfig::load().get_directory().unwrap_or_default();
+                profile::load(out, &directory, &name).await?;
+            }
+            None => warn!("No profile to load, the G keys stay bound to the g13 apps"),
+        }
+        Ok(())
+    }
+
+    /// Draw the clock, and the stars of the typed keys
+    async fn show<W: Unpin + AsyncWrite>(
+        &self,
+        display: &mut G13Display<'_, W>,
+    ) -> Result<(), AppError> {
+        let clock_style = TextStyleBuilder::new(Font12x16)
+            .text_color(BinaryColor::On)
+            .background_color(BinaryColor::Off)
+            .build();
+        let now = Local::now();
+        egrectangle!(
+            top_left = Point::zero(),
+            bottom_right = Point::new(159, 42),
+            style = *FILL_OFF,
+        )
+        .draw(display)?;
+        Text::new(&now.format("%H:%M").to_string(), Point::new(50, 6))
+            .into_styled(clock_style)
+            .draw(display)?;
+        let date = now.format("%a %d %b").to_string();
+        let x = 80 - 3 * date.chars().count() as i32;
+        Text::new(&date, Point::new(x, 26))
+            .into_styled(*TEXT_LIGHT)
+            .draw(display)?;
+        if !self.typed.is_empty() {
+            let typed = "*".repeat(self.typed.len());
+            let x = 80 - 3 * typed.len() as i32;
+            Text::new(&typed, Point::new(x, 35))
+                .into_styled(*TEXT_LIGHT)
+                .draw(display)?;
+        }
+        display.flush().await?;
+        Ok(())
+    }
+
+    /// Any key but a G key clears the typed keys
+    fn clear(&mut self) -> Result<(), AppError> {
+        self.typed.clear();
+        Ok(())
+    }
+}
+
+#[async_trait(?Send)]
+impl Application for Lock {
+    async fn execute<W: Unpin + AsyncWrite>(&mut self, out: &mut W) -> Result<App, AppError>
+    where
+        W: AsyncWrite + Unpin,
+    {
+        // The errors are only logged: another app would bypass the lock.
+        // The binding is tried again after a key.
+        if !self.end && !self.bound && !self.pin.is_empty() {
+            if let Err(err) = self.bind_keys(out).await {
+                error!("Can't bind the G keys to type the PIN: {}", err);
+            }
+        }
+        let mut interval = time::interval(Duration::from_secs(1));
+        let mut display = G13Display::new(out);
+
+        #[warn(clippy::while_immutable_condition)]
+        while !self.end {
+            if let Err(err) = self.show(&mut display).await {
+                error!("Can't show the lock screen: {}", err);
+            }
+            interval.tick().await;
+        }
+
+        // Same once unlocked, the previous app is restored anyway
+        if self.bound {
+            self.bound = false;
+            if let Err(err) = self.restore_keys(out).await {
+                error!("Can't restore the G keys after the lock: {}", err);
+            }
+        }
+        Ok(self
+            .previous
+            .take()
+            .map_or_else(|| App::Menu(Default::default()), |app| *app))
+    }
+
+    /// Clear the typed keys
+    async fn button_l1(&mut self) -> Result<(), AppError> {
+        self.clear()
+    }
+
+    /// Clear the typed keys
+    async fn button_l2(&mut self) -> Result<(), AppError> {
+        self.clear()
+    }
+
+    /// Clear the typed keys
+    async fn button_l3(&mut self) -> Result<(), AppError> {
+        self.clear()
+    }
+
+    /// Clear the typed keys
+    async fn button_l4(&mut self) -> Result<(), AppError> {
+        self.clear()
+    }
+
+    /// Clear the typed keys
+    async fn button_bd(&mut self) -> Result<(), AppError> {
+        self.clear()
+    }
+
+    /// Type a key of the PIN, unlocking once complete (or starting again if wrong)
+    async fn button_g(&mut self, number: u8) -> Result<(), AppError> {
+        if self.pin.is_empty() {
+            return Ok(());
+        }
+        self.typed.push(Key::G(number));
+        if self.typed.len() >= self.pin.len() {
+            if self.typed == self.pin {
+                info!("Unlocked by PIN");
+                self.end = true;
+            } else {
+                warn!("Wrong PIN typed on the lock screen");
+            }
+            self.typed.clear();
+        }
+        Ok(())
+    }
+
+    /// The mode LEDs don't show the background state while locked
+    fn leds(&self) -> LedStates {
+        LedStates {
+            m1: Some(Led::Off),
+            m2: Some(Led::Off),
+            m3: Some(Led::Off),
+            mr: Some(Led::Off),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::app::lock::Lock;
+    use crate::app::{AlarmRinging, App, Application, Saver, Screensaver};
+    use crate::protocol::Key;
+    use std::io::{self, Cursor};
+    use std::pin::Pin;
+    use std::task::{Context, Poll};
+    use std::time::Duration;
+    use tokio::io::AsyncWrite;
+    use tokio::runtime::Runtime;
+    use tokio::time;
+
+    /// A writer failing as a closed pipe
+    struct Broken;
+
+    impl AsyncWrite for Broken {
+        fn poll_write(self: Pin<&mut Self>, _: &mut Context, _: &[u8]) -> Poll<io::Result<usize>> {
+            Poll::Ready(Err(io::ErrorKind::BrokenPipe.into()))
+        }
+
+        fn poll_flush(self: Pin<&mut Self>, _: &mut Context) -> Poll<io::Result<()>> {
+            Poll::Ready(Err(io::ErrorKind::BrokenPipe.into()))
+        }
+
+        fn poll_shutdown(self: Pin<&mut Self>, _: &mut Context) -> Poll<io::Result<()>> {
+            Poll::Ready(Ok(()))
+        }
+    }
+
+    /// The PIN unlocks, a wrong one starts again.
+    #[test]
+    fn pin() {
+        let mut rt = Runtime::new().unwrap();
+        let mut lock = Lock::new(App::Logs(Default::default()), vec![Key::G(1), Key::G(7)]);
+        rt.block_on(lock.button_g(7)).unwrap();
+        rt.block_on(lock.button_g(1)).unwrap();
+        assert!(!lock.end);
+        rt.block_on(lock.button_g(1)).unwrap();
+        rt.block_on(lock.button_bd()).unwrap();
+        assert!(lock.typed.is_empty());
+        rt.block_on(lock.button_g(1)).unwrap();
+        rt.block_on(lock.button_g(7)).unwrap();
+        assert!(lock.end);
+        let mut writer = Cursor::new(Vec::<u8>::new());
+        let previous = rt.block_on(lock.execute(&mut writer)).unwrap();
+        assert_eq!(previous.name(), "logs");
+    }
+
+    /// Without PIN, only the session unlocks.
+    #[test]
+    fn session_unlock() {
+        let mut rt = Runtime::new().unwrap();
+        let mut lock = Lock::new(App::Logs(Default::default()), Vec::new());
+        rt.block_on(lock.button_g(1)).unwrap();
+        assert!(!lock.end);
+        lock.unlock();
+        assert!(lock.end);
+        // Even covered by an alarm
+        let lock = Lock::new(App::Logs(Default::default()), Vec::new());
+        let mut app = App::AlarmRinging(AlarmRinging::new(App::Lock(lock)));
+        app.get_lock().unwrap().unlock();
+        assert!(app.get_lock().is_some_and(|lock| lock.end));
+        assert!(App::Logs(Default::default()).get_lock().is_none());
+    }
+
+    /// The errors of the driver don't unlock, nor a retry, and the previous app is restored anyway.
+    #[test]
+    fn driver_errors() {
+        let mut rt = Runtime::new().unwrap();
+        let mut lock = Lock::new(App::Logs(Default::default()), vec![Key::G(1)]);
+        let delay = Duration::from_millis(50);
+        let locked = rt.block_on(async { time::timeout(delay, lock.execute(&mut Broken)).await });
+        assert!(locked.is_err());
+        assert!(lock.bound && !lock.end);
+        lock.unlock();
+        let previous = rt.block_on(lock.execute(&mut Broken)).unwrap();
+        assert_eq!(previous.name(), "logs");
+        // A retry locks again
+        assert!(matches!(App::Lock(lock).restart(), App::Lock(retry) if !retry.end));
+    }
+
+    /// The error of an app covering the lock shows the lock again, still over the previous app.
+    #[test]
+    fn covered_errors() {
+        let mut rt = Runtime::new().unwrap();
+        let lock = Lock::new(App::Logs(Default::default()), vec![Key::G(1)]);
+        let mut app = App::Screensaver(Screensaver::new(App::Lock(lock), Saver::Stars));
+        assert!(rt.block_on(app.execute(&mut Broken)).is_err());
+        match app.take_lock() {
+            Some(App::Lock(mut lock)) => {
+                assert!(!lock.end);
+                assert_eq!(lock.previous.take().map(|app| app.name()), Some("logs"));
+            }
+            _ => panic!("The lock should be shown again"),
+        }
+        assert!(App::Logs(Default::default()).take_lock().is_none());
+    }
+}
@@ -0,0 +1,220 @@
+use crate::app::App;
+use crate::app::Application;
+use crate::component::{AppBar, Button, ButtonBar, List, TextBlock};
+use crate::display::G13Display;
+use crate::driver::{Led, LedStates};
+use crate::error::AppError;
+use crate::settings;
+use crate::logger::{self, Entry};
+use crate::style::TEXT_LIGHT;
+use async_trait::async_trait;
+use embedded_graphics::{
+    fonts::{Font6x8, Text},
+    prelude::*,
+};
+use std::marker::Unpin;
+use tokio::io::AsyncWrite;
+use tokio::time;
+
+/// The logs app lists the recent log entries and shows their details
+#[derive(Clone, Debug, Default)]
+pub struct Logs {
+    /// Define if the app should end/return
+    end: bool,
+    /// The entries listed, from the newest to the oldest
+    entries: Vec<Entry>,
+    /// The list of the entries (None if not loaded or empty)
+    list: Option<List>,
+    /// The details of the selected entry, when opened
+    details: Option<TextBlock>,
+}
+
+#[async_trait(?Send)]
+impl Application for Logs {
+    async fn execute<W: Unpin + AsyncWrite>(&mut self, out: &mut W) -> Result<App, AppError>
+    where
+        W: AsyncWrite + Unpin,
+    {
+        let mut interval = time::interval(settings::refresh());
+        let mut display = G13Display::new(out);
+        if self.list.is_none() {
+            self.load();
+        }
+
+        // Draw the details of the selected entry
+        if let Some(details) = &self.details {
+            let title = format!(
+                "Log entry ({}/{}):",
+                details.get_page() + 1,
+                details.pages()
+            );
+            AppBar::new(title, Point::zero(), Point::new(160, 10)).draw(&mut display)?;
+            details.pixels(*TEXT_LIGHT).into_iter().draw(&mut display)?;
+            let mut buttonbar: ButtonBar = Default::default();
+            if details.pages() > 1 {
+                buttonbar.set_button3(Some(Button::from_str("\u{25B2}")));
+                buttonbar.set_button4(Some(Button::from_str("\u{25BC}")));
+            }
+            buttonbar.draw(&mut display)?;
+            display.flush().await?;
+        } else {
+            AppBar::new("Logs:", Point::zero(), Point::new(160, 8)).draw(&mut display)?;
+            let mut buttonbar: ButtonBar = Default::default();
+            if self.list.is_some() {
+                buttonbar.set_button1(Some(Button::from_str("OK")));
+                buttonbar.set_button3(Some(Button::from_str("\u{25B2}")));
+                buttonbar.set_button4(Some(Button::from_str("\u{25BC}")));
+            } else {
+                Text::new("No log entry.", Point::new(4, 18))
+                    .into_styled(*TEXT_LIGHT)
+                    .draw(&mut display)?;
+            }
+            buttonbar.draw(&mut display)?;
+            display.flush().await?;
+        }
+
+        let mut last_cursor: usize = usize::MAX;
+        while !self.end {
+            // Tick and wait for new cursor (or a scrolling selection)
+            interval.tick().await;
+            let list = match (self.details.as_ref(), self.list.as_mut()) {
+                (None, Some(list)) => list,
+                _ => continue,
+            };
+            if last_cursor == list.get_cursor() && !list.tick() {
+                continue;
+            }
+            last_cursor = list.get_cursor();
+
+            // Draw and flush
+            list.draw_default(&mut display)?;
+            display.flush().await?;
+        }
+
+        Ok(App::Menu(Default::default()))
+    }
+
+    /// Represents the details button
+    async fn button_l1(&mut self) -> Result<(), AppError> {
+        if self.details.is_none() {
+            self.details = self.current().map(details);
+        }
+        Ok(())
+    }
+
+    /// Not used
+    async fn button_l2(&mut self) -> Result<(), AppError> {
+        Ok(())
+    }
+
+    /// Represents the previous button (or previous page of the details)
+    async fn button_l3(&mut self) -> Result<(), AppError> {
+        match (self.details.as_mut(), self.list.as_mut()) {
+            (Some(details), _) => {
+                details.previous_page();
+            }
+            (None, Some(list)) => list.previous(),
+            (None, None) => {}
+        }
+        Ok(())
+    }
+
+    /// Represents the next button (or next page of the details)
+    async fn button_l4(&mut self) -> Result<(), AppError> {
+        match (self.details.as_mut(), self.list.as_mut()) {
+            (Some(details), _) => {
+                details.next_page();
+            }
+            (None, Some(list)) => list.next(),
+            (None, None) => {}
+        }
+        Ok(())
+    }
+
+    /// Represents the return button (close the details or go to menu)
+    async fn button_bd(&mut self) -> Result<(), AppError> {
+        if self.details.take().is_none() {
+            self.end = true;
+        }
+        Ok(())
+    }
+
+    /// The new errors are not signaled while the logs are read
+    fn leds(&self) -> LedStates {
+        LedStates {
+            mr: Some(Led::Off),
+            ..Default::default()
+        }
+    }
+}
+
+impl Logs {
+    /// Load the recent entries and their list
+    fn load(&mut self) {
+        logger::mark_read();
+        self.entries = logger::recent();
+        self.list = if self.entries.is_empty() {
+            None
+        } else {
+            Some(List::new(self.entries.iter().map(summary).collect()))
+        };
+    }
+
+    /// Get the selected entry
+    fn current(&self) -> Option<&Entry> {
+        self.entries.get(self.list.as_ref()?.get_cursor())
+    }
+}
+
+/// Summarize an entry on one line: its time, the first letter of its level
+/// and the first line of its message
+fn summary(entry: &Entry) -> String {
+    format!(
+        "{} {} {}",
+        entry.time.format("%H:%M"),
+        &entry.level.to_string()[..1],
+        entry.message.lines().next().unwrap_or_default()
+    )
+}
+
+/// Create the text block with the details of an entry
+fn details(entry: &Entry) -> TextBlock {
+    let text = format!(
+        "{} {}\n{}\n{}",
+        entry.time.format("%Y-%m-%d %H:%M:%S"),
+        entry.level,
+        entry.target,
+        entry.message
+    );
+    TextBlock::new(text, Point::new(0, 11), Point::new(159, 34), Font6x8)
+}
+
+#[cfg(test)]
+mod test {
+    use super::{details, summary};
+    use crate::logger::Entry;
+    use chrono::{Local, TimeZone};
+    use log::Level;
+
+    /// An entry is summarized on one line, and detailed in a text block.
+    #[test]
+    fn entry_views() {
+        let entry = Entry {
+            time: Local.with_ymd_and_hms(2020, 7, 14, 12, 34, 56).unwrap(),
+            level: Level::Error,
+            target: "g13_apps".to_owned(),
+            message: "music_player failed: DBus error\nCaused by: timeout".to_owned(),
+        };
+        assert_eq!(summary(&entry), "12:34 E music_player failed: DBus error");
+        let block = details(&entry);
+        assert_eq!(
+            block.get_lines(),
+            &[
+                "2020-07-14 12:34:56 ERROR",
+                "g13_apps",
+                "music_player failed: DBus"
+            ]
+        );
+        assert_eq!(block.pages(), 2);
+    }
+}
@@ -6,6 +6,7 @@ use crate::component::List;
 use crate::component::{Button, ButtonBar};
 use crate::display::G13Display;
 use crate::error::AppError;
+use crate::settings;
 use async_trait::async_trait;
 use embedded_graphics::{
     pixelcolor::BinaryColor, prelude::*, primitives::Rectangle, style::PrimitiveStyleBuilder,
@@ -14,7 +15,6 @@ use once_cell::sync::Lazy;
 use std::iter::IntoIterator;
 use std::marker::Unpin;
 use std::str::FromStr;
-use std::time::Duration;
 use strum::VariantNames;
 use tokio::io::AsyncWrite;
 use tokio::time;
@@ -31,6 +31,7 @@ pub struct Menu {
 impl Default for Menu {
     fn default() -> Self {
         let apps: &[&'static str] = App::VARIANTS;
+        let settings = settings::settings();
         Self {
             end: false,
             // Build the app list
@@ -38,6 +39,7 @@ impl Default for Menu {
                 apps.iter()
                     .map(|name| name.to_string())
                     .filter(|name| !HIDDEN_APPS.contains(&name.as_str()))
+                    .filter(|name| !settings.is_hidden(name))
                     .collect(),
             ),
         }
@@ -50,7 +52,7 @@ impl Application for Menu {
     where
         W: AsyncWrite + Unpin,
     {
-        let mut interval = time::interval(Duration::from_millis(100));
+        let mut interval = time::interval(settings::refresh());
         let mut display = G13Display::new(out);
         let mut last_cursor: usize = usize::MAX;
 
@@ -59,10 +61,10 @@ impl Application for Menu {
 
         #[warn(clippy::while_immutable_condition)]
         while !self.end {
-            // If the cursor does not change between iteration, just wait
-            let list: &List = &self.list;
+            // If the cursor does not change between iteration (and nothing scrolls), just wait
+            let list: &mut List = &mut self.list;
             let cursor = list.get_cursor();
-            if last_cursor == cursor {
+            if last_cursor == cursor && !list.tick() {
                 interval.tick().await;
                 continue;
             }
@@ -1,43 +1,90 @@
+use crate::countdown;
+use crate::driver::{Led, LedStates};
 use crate::error::AppError;
+use crate::logger;
+use alarm::AlarmEditor;
 use async_trait::async_trait;
+use backlight::Backlight;
+#[cfg(feature = "calendar")]
+use calendar::Calendar;
 #[cfg(feature = "clock")]
 use clock::Clock;
 use enum_dispatch::enum_dispatch;
+use once_cell::sync::Lazy;
 #[cfg(feature = "hello")]
 use hello::Hello;
+use logs::Logs;
 use menu::Menu;
 #[cfg(feature = "music")]
+use music::MusicBrowser;
+#[cfg(feature = "music")]
 use music::MusicPlayer;
 #[cfg(feature = "music")]
 use music::MusicSelector;
+use profiles::ProfileSwitcher;
+use settings::SettingsEditor;
+#[cfg(feature = "life")]
+use life::GameOfLife;
+#[cfg(feature = "snake")]
+use snake::Snake;
+#[cfg(feature = "stopwatch")]
+use stopwatch::LapTimer;
+#[cfg(feature = "tetris")]
+use tetris::Tetris;
 use std::marker::Unpin;
-use strum_macros::{EnumCount, EnumString, EnumVariantNames};
+use std::str::FromStr;
+use std::sync::{Mutex, PoisonError};
+use strum_macros::{EnumCount, EnumString, EnumVariantNames, IntoStaticStr};
 use tokio::io::AsyncWrite;
 
+pub use alarm::AlarmRinging;
+pub use keycheck::KeyCheck;
+pub use lock::Lock;
+pub use screensaver::{Saver, Screensaver};
+pub use timer::Timer;
 #[cfg(feature = "music")]
 pub use music::MusicError;
 
+#[cfg(feature = "calendar")]
+mod calendar;
 #[cfg(feature = "clock")]
 mod clock;
 #[cfg(feature = "hello")]
 mod hello;
+#[cfg(feature = "life")]
+mod life;
 #[cfg(feature = "music")]
 mod music;
+#[cfg(feature = "snake")]
+mod snake;
+#[cfg(feature = "stopwatch")]
+mod stopwatch;
+#[cfg(feature = "tetris")]
+mod tetris;
 
+mod alarm;
+mod backlight;
 pub mod error;
+mod keycheck;
+mod lock;
+mod logs;
 mod menu;
+mod profiles;
+mod screensaver;
+mod settings;
+mod timer;
 
 /// List of apps hidden from the menu.
 ///
 /// Needed by error app
-pub const HIDDEN_APPS: &[&str] = &["error_app", "menu", "music_player"];
+pub const HIDDEN_APPS: &[&str] = &["error_app", "menu", "music_player", "music_browser", "alarm_ringing", "key_check", "screensaver", "lock"];
 
 /// Listing of all implemented applications.
 ///
 /// Probably some of them will be activated only with certain features.
 /// All the applications listed here must have in field, their structure which inherits the App trait.
 #[enum_dispatch(Application)]
-#[derive(EnumString, EnumVariantNames, EnumCount, Debug)]
+#[derive(EnumString, EnumVariantNames, EnumCount, IntoStaticStr, Debug)]
 #[strum(serialize_all = "snake_case")]
 #[non_exhaustive]
 pub enum App {
@@ -47,10 +94,123 @@ pub enum App {
     Hello,
     #[cfg(feature = "clock")]
     Clock,
+    #[cfg(feature = "calendar")]
+    Calendar,
+    #[cfg(feature = "stopwatch")]
+    Stopwatch(LapTimer),
+    Timer,
+    Alarms(AlarmEditor),
+    AlarmRinging,
+    KeyCheck,
+    Screensaver,
+    Lock,
     #[cfg(feature = "music")]
     Music(MusicSelector),
+    Logs,
+    Settings(SettingsEditor),
+    Backlight,
+    Profiles(ProfileSwitcher),
+    #[cfg(feature = "snake")]
+    Snake,
+    #[cfg(feature = "tetris")]
+    Tetris,
+    #[cfg(feature = "life")]
+    GameOfLife,
     #[cfg(feature = "music")]
     MusicPlayer,
+    #[cfg(feature = "music")]
+    MusicBrowser,
+}
+
+impl App {
+    /// Get the name of the app, as used by the menu
+    pub fn name(&self) -> &'static str {
+        self.into()
+    }
+
+    /// Create a new instance of the app, as if it was just opened
+    pub fn restart(&self) -> App {
+        match self {
+            #[cfg(feature = "music")]
+            App::MusicPlayer(player) => App::MusicPlayer(player.restart()),
+            #[cfg(feature = "music")]
+            App::MusicBrowser(browser) => App::MusicBrowser(browser.restart()),
+            // Never unlock by a retry
+            App::Lock(lock) => App::Lock(lock.restart()),
+            // Never retry an error, go back to the menu
+            App::ErrorApp(_) => App::Menu(Default::default()),
+            _ => App::from_str(self.name()).unwrap_or_else(|_| App::Menu(Default::default())),
+        }
+    }
+
+    /// Get the lock screen, in foreground or covered by an alarm, the end of a countdown,
+    /// the screensaver or the warning of the keys check (None if not locked)
+    pub fn get_lock(&mut self) -> Option<&mut Lock> {
+        match self {
+            App::Lock(lock) => Some(lock),
+            App::AlarmRinging(alarm) => alarm.get_previous()?.get_lock(),
+            App::Timer(timer) => timer.get_previous()?.get_lock(),
+            App::Screensaver(screensaver) => screensaver.get_previous()?.get_lock(),
+            App::KeyCheck(check) => check.get_previous()?.get_lock(),
+            _ => None,
+        }
+    }
+
+    /// Take the lock screen out of the app, to show it again after an error (None if not locked).
+    /// The error app would open the menu without the PIN.
+    pub fn take_lock(&mut self) -> Option<App> {
+        self.get_lock().map(|lock| App::Lock(std::mem::take(lock)))
+    }
+
+    /// Get the app hidden by the screensaver (or the app itself)
+    pub fn wake(self) -> App {
+        match self {
+            App::Screensaver(mut screensaver) => screensaver.wake(),
+            app => app,
+        }
+    }
+}
+
+/// The backlight color and the mode LEDs wanted by the foreground app (see [show_indicators])
+static INDICATORS: Lazy<Mutex<(Option<[u8; 3]>, LedStates)>> = Lazy::new(Default::default);
+
+/// Publish the backlight color and the mode LEDs wanted by the foreground app.
+/// The app can't be read while it runs: it publishes them itself if they change meanwhile.
+pub fn show_indicators<A: Application>(app: &A) {
+    *INDICATORS.lock().unwrap_or_else(PoisonError::into_inner) = (app.backlight(), app.leds());
+}
+
+/// Get the backlight color and the mode LEDs last published by the foreground app
+pub fn indicators() -> (Option<[u8; 3]>, LedStates) {
+    *INDICATORS.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+/// Get the states of the mode LEDs showing the background state:
+/// a running countdown (M2, blinking once over), a running stopwatch (M3)
+/// and the unread warnings and errors of the logs (MR, blinking).
+pub fn background_leds() -> LedStates {
+    let countdown = countdown::countdown();
+    let m2 = if countdown.is_finished() {
+        Some(Led::Blink)
+    } else if countdown.is_running() {
+        Some(Led::On)
+    } else {
+        None
+    };
+    #[cfg(feature = "stopwatch")]
+    let m3 = if stopwatch::stopwatch().is_running() {
+        Some(Led::On)
+    } else {
+        None
+    };
+    #[cfg(not(feature = "stopwatch"))]
+    let m3 = None;
+    let mr = if logger::unread() > 0 {
+        Some(Led::Blink)
+    } else {
+        None
+    };
+    LedStates { m1: None, m2, m3, mr }
 }
 
 /// Trait with all interactions between the AppManager and the App itself
@@ -81,4 +241,22 @@ pub trait Application {
 
     /// Function called as soon as the BD key is pressed (or a matching key defined in the configuration).
     async fn button_bd(&mut self) -> Result<(), AppError>;
+
+    /// Function called as soon as a G key is pressed, if bound to the g13 apps (as while locked).
+    async fn button_g(&mut self, _number: u8) -> Result<(), AppError> {
+        Ok(())
+    }
+
+    /// The color of the keyboard backlight wanted by the application (red, green, blue).
+    /// By default (None), the color configured for the application is used.
+    /// It is read when the app opens and after each key (see [show_indicators]).
+    fn backlight(&self) -> Option<[u8; 3]> {
+        None
+    }
+
+    /// The states of the mode LEDs wanted by the application.
+    /// The undeclared LEDs show the background state (see [background_leds]).
+    fn leds(&self) -> LedStates {
+        LedStates::default()
+    }
 }
@@ -0,0 +1,532 @@
+use super::find_player;
+use super::Bus;
+use super::Command;
+use super::MusicError;
+use super::MusicPlayer;
+use super::Song;
+use crate::app::App;
+use crate::app::Application;
+use crate::component::{AppBar, Button, ButtonBar, List};
+use crate::display::G13Display;
+use crate::error::AppError;
+use crate::settings;
+use crate::style::TEXT_LIGHT;
+use async_trait::async_trait;
+use dbus::ffidisp::stdintf::org_freedesktop_dbus::{Introspectable, Properties};
+use dbus::Path;
+use embedded_graphics::fonts::Text;
+use embedded_graphics::{pixelcolor::BinaryColor, prelude::*};
+use log::info;
+use mpris::{Player, TrackID};
+use tokio::io::AsyncWrite;
+use tokio::time;
+
+/// The object path of the MPRIS players
+const MPRIS_PATH: &str = "/org/mpris/MediaPlayer2";
+/// The MPRIS playlists interface (not covered by the mpris crate)
+const PLAYLISTS_INTERFACE: &str = "org.mpris.MediaPlayer2.Playlists";
+/// The maximum number of playlists fetched from the player
+const MAX_PLAYLISTS: u32 = 50;
+/// The D-Bus timeout (in milliseconds)
+const TIMEOUT_MS: i32 = 500;
+
+/// The sources of entries which can be browsed
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Source {
+    /// The tracks of the TrackList interface
+    Tracks,
+    /// The playlists of the Playlists interface
+    Playlists,
+}
+
+/// A track or a playlist, with its D-Bus id (track id or object path)
+#[derive(Clone, Debug, PartialEq)]
+struct Entry {
+    id: String,
+    label: String,
+}
+
+/// Music Browser lists the tracks or the playlists of a player,
+/// to jump to a track or to activate a playlist.
+/// It is opened from the player selector, and goes on with the player.
+#[derive(Clone, Debug, Default)]
+pub struct MusicBrowser {
+    end: bool,
+    activate: bool,
+    switch: bool,
+    player_name: String,
+    /// The available sources, the first one is the browsed one
+    sources: Vec<Source>,
+    /// The entries of the browsed source (None if not loaded yet)
+    entries: Option<Vec<Entry>>,
+    /// The list of the entries (None if there is no entry)
+    list: Option<List>,
+}
+
+#[async_trait(?Send)]
+impl Application for MusicBrowser {
+    async fn execute<W: Unpin + AsyncWrite>(&mut self, out: &mut W) -> Result<App, AppError>
+    where
+        W: AsyncWrite + Unpin,
+    {
+        let player = find_player(&self.player_name)?;
+        if self.entries.is_none() {
+            self.sources = available_sources(&Bus::Session, &player)?;
+            self.load(&player)?;
+        }
+        // Browse the other source
+        if self.switch {
+            self.switch = false;
+            self.sources.rotate_left(1);
+            self.load(&player)?;
+        }
+        let source = match self.sources.first() {
+            Some(source) => *source,
+            // Nothing to browse, go back to the player
+            None => {
+                return Ok(App::MusicPlayer(MusicPlayer::new(
+                    self.player_name.as_str(),
+                )))
+            }
+        };
+
+        let mut interval = time::interval(settings::refresh());
+        let mut display = G13Display::new(out);
+        let mut last_cursor: usize = usize::MAX;
+
+        // Draw the interface
+        self.interface(source).into_iter().draw(&mut display)?;
+        if self.list.is_none() {
+            Text::new("Nothing to show.", Point::new(4, 18))
+                .into_styled(*TEXT_LIGHT)
+                .draw(&mut display)?;
+            display.flush().await?;
+        }
+
+        while !self.end {
+            // Tick and wait for new cursor (or a scrolling selection)
+            interval.tick().await;
+            let list = match self.list.as_mut() {
+                Some(list) => list,
+                None => continue,
+            };
+            if last_cursor == list.get_cursor() && !list.tick() {
+                continue;
+            }
+            last_cursor = list.get_cursor();
+
+            // Draw and flush
+            list.draw_default(&mut display)?;
+            display.flush().await?;
+        }
+
+        if self.activate {
+            if let Some(entry) = self.current() {
+                activate(&Bus::Session, &player, source, entry)?;
+            }
+        }
+        Ok(App::MusicPlayer(MusicPlayer::new(
+            self.player_name.as_str(),
+        )))
+    }
+
+    // Selection button
+    async fn button_l1(&mut self) -> Result<(), AppError> {
+        self.activate = self.list.is_some();
+        self.end = self.activate;
+        Ok(())
+    }
+
+    // Switch between tracks and playlists
+    async fn button_l2(&mut self) -> Result<(), AppError> {
+        self.switch = self.sources.len() > 1;
+        Ok(())
+    }
+
+    // Previous button
+    async fn button_l3(&mut self) -> Result<(), AppError> {
+        if let Some(list) = self.list.as_mut() {
+            list.previous();
+        }
+        Ok(())
+    }
+
+    // Next button
+    async fn button_l4(&mut self) -> Result<(), AppError> {
+        if let Some(list) = self.list.as_mut() {
+            list.next();
+        }
+        Ok(())
+    }
+
+    // Return to the player
+    async fn button_bd(&mut self) -> Result<(), AppError> {
+        self.end = true;
+        Ok(())
+    }
+}
+
+impl MusicBrowser {
+    /// Create a MusicBrowser for the given MPRIS Player name
+    pub fn new<S: Into<String>>(player_name: S) -> Self {
+        Self {
+            player_name: player_name.into(),
+            ..Default::default()
+        }
+    }
+
+    /// Create a new MusicBrowser for the same player
+    pub fn restart(&self) -> Self {
+        Self::new(self.player_name.as_str())
+    }
+
+    /// Load the entries of the browsed source
+    fn load(&mut self, player: &Player) -> Result<(), AppError> {
+        let entries = match self.sources.first() {
+            Some(Source::Tracks) => load_tracks(player)?,
+            Some(Source::Playlists) => load_playlists(&Bus::Session, player)?,
+            None => Vec::new(),
+        };
+        self.list = if entries.is_empty() {
+            None
+        } else {
+            Some(List::new(
+                entries.iter().map(|entry| entry.label.clone()).collect(),
+            ))
+        };
+        self.entries = Some(entries);
+        Ok(())
+    }
+
+    /// Get the selected entry
+    fn current(&self) -> Option<&Entry> {
+        let cursor = self.list.as_ref()?.get_cursor();
+        self.entries.as_ref()?.get(cursor)
+    }
+
+    /// Get the interface of the browser for the given source
+    fn interface(&self, source: Source) -> Vec<Pixel<BinaryColor>> {
+        let title = match source {
+            Source::Tracks => "Tracks:",
+            Source::Playlists => "Playlists:",
+        };
+        let appbar = AppBar::new(title, Point::zero(), Point::new(160, 8)).into_iter();
+
+        let mut buttonbar: ButtonBar = Default::default();
+        if self.list.is_some() {
+            buttonbar.set_button1(Some(Button::from_str("OK")));
+            buttonbar.set_button3(Some(Button::from_str("\u{25B2}")));
+            buttonbar.set_button4(Some(Button::from_str("\u{25BC}")));
+        }
+        if self.sources.len() > 1 {
+            buttonbar.set_button2(Some(Button::from_str("\u{2194}")));
+        }
+        appbar.chain(buttonbar).collect()
+    }
+}
+
+/// Get the sources the player can be browsed with
+fn available_sources(bus: &Bus, player: &Player) -> Result<Vec<Source>, MusicError> {
+    let mut sources = Vec::new();
+    if player.supports_track_lists() {
+        sources.push(Source::Tracks);
+    }
+    // A new connection, for the interfaces not covered by the mpris crate
+    let conn = bus.connect()?;
+    let path = conn.with_path(player.bus_name().clone(), MPRIS_PATH, TIMEOUT_MS);
+    if path.introspect()?.contains(PLAYLISTS_INTERFACE) {
+        sources.push(Source::Playlists);
+    }
+    Ok(sources)
+}
+
+/// Load the tracks of the player track list
+fn load_tracks(player: &Player) -> Result<Vec<Entry>, MusicError> {
+    let tracks = player.get_track_list()?;
+    let metadata = player.get_tracks_metadata(tracks.ids())?;
+    Ok(tracks
+        .ids()
+        .iter()
+        .zip(metadata)
+        .map(|(id, meta)| {
+            let song = Song::from(meta);
+            Entry {
+                id: id.as_str().to_owned(),
+                label: format!("{} - {}", song.title, song.artist),
+            }
+        })
+        .collect())
+}
+
+/// Load the playlists of the player
+fn load_playlists(bus: &Bus, player: &Player) -> Result<Vec<Entry>, MusicError> {
+    let conn = bus.connect()?;
+    let path = conn.with_path(player.bus_name().clone(), MPRIS_PATH, TIMEOUT_MS);
+    // Use the preferred ordering of the player
+    let orderings: Vec<String> = path.get(PLAYLISTS_INTERFACE, "Orderings")?;
+    let ordering = orderings
+        .into_iter()
+        .next()
+        .unwrap_or_else(|| "Alphabetical".to_owned());
+    let (playlists,): (Vec<(Path<'static>, String, String)>,) = path.method_call(
+        PLAYLISTS_INTERFACE,
+        "GetPlaylists",
+        (0u32, MAX_PLAYLISTS, ordering, false),
+    )?;
+    Ok(playlists
+        .into_iter()
+        .map(|(id, name, _icon)| Entry {
+            id: id.to_string(),
+            label: name,
+        })
+        .collect())
+}
+
+/// Jump to the track or activate the playlist
+fn activate(bus: &Bus, player: &Player, source: Source, entry: &Entry) -> Result<(), MusicError> {
+    info!(
+        "{}: activating {:?} {}",
+        player.identity(),
+        source,
+        entry.id
+    );
+    match source {
+        Source::Tracks => {
+            let track = TrackID::new(entry.id.as_str()).map_err(MusicError::DBusError)?;
+            player
+                .go_to(&track)
+                .map_err(|err| MusicError::CommandError(Command::GoTo, Box::new(err.into())))
+        }
+        Source::Playlists => {
+            let conn = bus.connect()?;
+            let path = conn.with_path(player.bus_name().clone(), MPRIS_PATH, TIMEOUT_MS);
+            let playlist = Path::new(entry.id.as_str()).map_err(MusicError::DBusError)?;
+            path.method_call(PLAYLISTS_INTERFACE, "ActivatePlaylist", (playlist,))
+                .map_err(|err: dbus::Error| {
+                    MusicError::CommandError(Command::ActivatePlaylist, Box::new(err.into()))
+                })
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{activate, available_sources, load_playlists, load_tracks, Source};
+    use crate::app::music::{find_player_on, Bus};
+    use dbus::arg::{RefArg, Variant};
+    use dbus::blocking::LocalConnection;
+    use dbus::channel::Channel;
+    use dbus::tree::Factory;
+    use dbus::Path;
+    use std::collections::HashMap;
+    use std::io::{BufRead, BufReader};
+    use std::process::{Child, Command, Stdio};
+    use std::sync::atomic::{AtomicBool, Ordering};
+    use std::sync::{Arc, Mutex};
+    use std::thread;
+    use std::time::Duration;
+
+    /// A minimal configuration for a private session bus
+    const BUS_CONFIG: &str = r#"<!DOCTYPE busconfig PUBLIC "-//freedesktop//DTD D-Bus Bus Configuration 1.0//EN"
+ "http://www.freedesktop.org/standards/dbus/1.0/busconfig.dtd">
+<busconfig>
+  <type>session</type>
+  <listen>unix:tmpdir=/tmp</listen>
+  <auth>EXTERNAL</auth>
+  <policy context="default">
+    <allow send_destination="*" eavesdrop="true"/>
+    <allow eavesdrop="true"/>
+    <allow own="*"/>
+  </policy>
+</busconfig>"#;
+
+    /// Start a private session bus, returns the daemon and the bus address
+    fn private_bus() -> Option<(Child, String)> {
+        let config = std::env::temp_dir().join("g13-apps-test-bus.conf");
+        std::fs::write(&config, BUS_CONFIG).ok()?;
+        let mut daemon = Command::new("dbus-daemon")
+            .arg(format!("--config-file={}", config.display()))
+            .args(["--nofork", "--print-address"])
+            .stdout(Stdio::piped())
+            .spawn()
+            .ok()?;
+        let mut address = String::new();
+        BufReader::new(daemon.stdout.as_mut()?)
+            .read_line(&mut address)
+            .ok()?;
+        std::fs::remove_file(&config).ok();
+        Some((daemon, address.trim().to_owned()))
+    }
+
+    /// Serve a mock MPRIS player with a track list and playlists on the given bus, until stopped.
+    /// The calls to GoTo and ActivatePlaylist are recorded.
+    fn mock_player(address: &str, calls: Arc<Mutex<Vec<String>>>, stop: Arc<AtomicBool>) {
+        let mut channel = Channel::open_private(address).expect("Should connect the mock");
+        channel.register().expect("Should register the mock");
+        let conn = LocalConnection::from(channel);
+        conn.request_name("org.mpris.MediaPlayer2.mock", false, true, false)
+            .expect("Should own the mock name");
+        let f = Factory::new_fn::<()>();
+        let (goto_calls, activate_calls) = (calls.clone(), calls);
+        let root = f
+            .interface("org.mpris.MediaPlayer2", ())
+            .add_p(f.property::<&str, _>("Identity", ()).on_get(|iter, _| {
+                iter.append("Mock");
+                Ok(())
+            }))
+            .add_p(f.property::<bool, _>("HasTrackList", ()).on_get(|iter, _| {
+                iter.append(true);
+                Ok(())
+            }));
+        let track_list = f
+            .interface("org.mpris.MediaPlayer2.TrackList", ())
+            .add_p(
+                f.property::<Vec<Path<'static>>, _>("Tracks", ())
+                    .on_get(|iter, _| {
+                        iter.append(vec![
+                            Path::from("/org/mock/track/1"),
+                            Path::from("/org/mock/track/2"),
+                        ]);
+                        Ok(())
+                    }),
+            )
+            .add_m(
+                f.method("GetTracksMetadata", (), |m| {
+                    let ids: Vec<Path> = m.msg.read1()?;
+                    let metadata: Vec<HashMap<String, Variant<Box<dyn RefArg>>>> = ids
+                        .iter()
+                        .map(|id| {
+                            let number = id.rsplit('/').next().unwrap_or_default().to_owned();
+                            let mut meta: HashMap<String, Variant<Box<dyn RefArg>>> =
+                                HashMap::new();
+                            meta.insert(
+                                "mpris:trackid".to_owned(),
+                                Variant(Box::new(id.clone().into_static())),
+                            );
+                            meta.insert(
+                                "xesam:title".to_owned(),
+                                Variant(Box::new(format!("Song {}", number))),
+                            );
+                            meta.insert(
+                                "xesam:artist".to_owned(),
+                                Variant(Box::new(vec![format!("Artist {}", number)])),
+                            );
+                            meta
+                        })
+                        .collect();
+                    Ok(vec![m.msg.method_return().append1(metadata)])
+                })
+                .inarg::<Vec<Path>, _>("TrackIds")
+                .outarg::<Vec<HashMap<String, Variant<Box<dyn RefArg>>>>, _>("Metadata"),
+            )
+            .add_m(
+                f.method("GoTo", (), move |m| {
+                    let id: Path = m.msg.read1()?;
+                    goto_calls.lock().unwrap().push(format!("GoTo {}", id));
+                    Ok(vec![m.msg.method_return()])
+                })
+                .inarg::<Path, _>("TrackId"),
+            );
+        let playlists = f
+            .interface("org.mpris.MediaPlayer2.Playlists", ())
+            .add_p(f.property::<u32, _>("PlaylistCount", ()).on_get(|iter, _| {
+                iter.append(1u32);
+                Ok(())
+            }))
+            .add_p(
+                f.property::<Vec<&str>, _>("Orderings", ())
+                    .on_get(|iter, _| {
+                        iter.append(vec!["UserDefined"]);
+                        Ok(())
+                    }),
+            )
+            .add_m(
+                f.method("GetPlaylists", (), |m| {
+                    let (_, _, ordering, _): (u32, u32, String, bool) = m.msg.read4()?;
+                    assert_eq!(ordering, "UserDefined");
+                    let playlists = vec![(
+                        Path::from("/org/mock/playlist/1"),
+                        "Chill".to_owned(),
+                        String::new(),
+                    )];
+                    Ok(vec![m.msg.method_return().append1(playlists)])
+                })
+                .inarg::<u32, _>("Index")
+                .inarg::<u32, _>("MaxCount")
+                .inarg::<&str, _>("Order")
+                .inarg::<bool, _>("ReverseOrder")
+                .outarg::<Vec<(Path, &str, &str)>, _>("Playlists"),
+            )
+            .add_m(
+                f.method("ActivatePlaylist", (), move |m| {
+                    let id: Path = m.msg.read1()?;
+                    activate_calls
+                        .lock()
+                        .unwrap()
+                        .push(format!("ActivatePlaylist {}", id));
+                    Ok(vec![m.msg.method_return()])
+                })
+                .inarg::<Path, _>("PlaylistId"),
+            );
+        let tree = f.tree(()).add(
+            f.object_path("/org/mpris/MediaPlayer2", ())
+                .introspectable()
+                .add(root)
+                .add(track_list)
+                .add(playlists),
+        );
+        tree.start_receive(&conn);
+        while !stop.load(Ordering::SeqCst) {
+            conn.process(Duration::from_millis(50))
+                .expect("Should process the mock calls");
+        }
+    }
+
+    /// Browse and activate the tracks and the playlists of a mock player.
+    #[test]
+    #[ignore = "needs dbus-daemon, run with --ignored"]
+    fn browse_mock_player() {
+        let (mut daemon, address) = private_bus().expect("Should start dbus-daemon");
+        let bus = Bus::Address(address.clone());
+        let calls = Arc::new(Mutex::new(Vec::new()));
+        let stop = Arc::new(AtomicBool::new(false));
+        let mock = {
+            let (calls, stop) = (calls.clone(), stop.clone());
+            thread::spawn(move || mock_player(&address, calls, stop))
+        };
+
+        // Wait for the mock to be on the bus
+        let player = (0..40)
+            .find_map(|_| {
+                thread::sleep(Duration::from_millis(50));
+                find_player_on(&bus, "Mock").ok()
+            })
+            .expect("Should find the mock player");
+
+        assert_eq!(
+            available_sources(&bus, &player).unwrap(),
+            vec![Source::Tracks, Source::Playlists]
+        );
+        let tracks = load_tracks(&player).unwrap();
+        let labels: Vec<&str> = tracks.iter().map(|entry| entry.label.as_str()).collect();
+        assert_eq!(labels, vec!["Song 1 - Artist 1", "Song 2 - Artist 2"]);
+        let playlists = load_playlists(&bus, &player).unwrap();
+        assert_eq!(playlists.len(), 1);
+        assert_eq!(playlists[0].label, "Chill");
+
+        activate(&bus, &player, Source::Tracks, &tracks[1]).unwrap();
+        activate(&bus, &player, Source::Playlists, &playlists[0]).unwrap();
+        assert_eq!(
+            *calls.lock().unwrap(),
+            vec![
+                "GoTo /org/mock/track/2".to_owned(),
+                "ActivatePlaylist /org/mock/playlist/1".to_owned()
+            ]
+        );
+
+        stop.store(true, Ordering::SeqCst);
+        mock.join().unwrap();
+        daemon.kill().ok();
+        daemon.wait().ok();
+    }
+}
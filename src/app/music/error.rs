@@ -34,6 +34,12 @@ impl From<DBusError> for MusicError {
     }
 }
 
+impl From<dbus::Error> for MusicError {
+    fn from(err: dbus::Error) -> Self {
+        Self::DBusError(err.to_string())
+    }
+}
+
 impl From<MusicError> for crate::error::AppError {
     fn from(err: MusicError) -> Self {
         crate::error::AppError::MusicError(err)
@@ -1,12 +1,17 @@
+mod browser;
 mod player;
 mod selector;
 mod error;
 
+pub use browser::MusicBrowser;
 pub use player::MusicPlayer;
 pub use selector::MusicSelector;
 pub use error::MusicError;
 
-use mpris::{Metadata, MetadataValue};
+use crate::error::AppError;
+use dbus::ffidisp::{BusType, Connection};
+use mpris::{Metadata, MetadataValue, Player, PlayerFinder};
+use std::path::PathBuf;
 use std::string::ToString;
 use strum_macros::Display;
 
@@ -16,13 +21,59 @@ pub enum Command {
     Stop,
     Previous,
     Next,
+    GoTo,
+    ActivatePlaylist,
 }
 
-/// Represents a Song with only the basic info : title and artists.
+/// The D-Bus bus of the MPRIS players
+#[derive(Clone, Debug, PartialEq)]
+enum Bus {
+    /// The session bus of the user
+    Session,
+    /// A bus given by its address (the private bus of the tests)
+    #[cfg(test)]
+    Address(String),
+}
+
+impl Bus {
+    /// Open a new connection to the bus
+    fn connect(&self) -> Result<Connection, dbus::Error> {
+        match self {
+            Bus::Session => Connection::get_private(BusType::Session),
+            #[cfg(test)]
+            Bus::Address(address) => {
+                let conn = Connection::open_private(address)?;
+                conn.register()?;
+                Ok(conn)
+            }
+        }
+    }
+}
+
+/// Find the MPRIS player with the given name (its identity)
+fn find_player(player_name: &str) -> Result<Player<'static>, AppError> {
+    find_player_on(&Bus::Session, player_name)
+}
+
+/// Find the MPRIS player with the given name (its identity) on the given bus
+fn find_player_on(bus: &Bus, player_name: &str) -> Result<Player<'static>, AppError> {
+    // Get a player finder
+    let finder = PlayerFinder::for_connection(bus.connect().map_err(MusicError::from)?);
+    // get the players list
+    let players: Vec<Player<'_>> = finder.find_all().map_err(MusicError::from)?;
+    // get the wanted player
+    players
+        .into_iter()
+        .find(|player| player.identity() == player_name)
+        .ok_or(AppError::BadInitialization)
+}
+
+/// Represents a Song with only the basic info : title, artists and album art.
 #[derive(Default, Clone, Eq, PartialEq, Debug)]
 pub struct Song {
     title: String,
     artist: String,
+    art_url: Option<String>,
 }
 
 impl From<Metadata> for Song {
@@ -30,8 +81,33 @@ impl From<Metadata> for Song {
         Song {
             title: meta.title().unwrap_or("Unknown").to_owned(),
             artist: get_artists(&meta).unwrap_or("Unknown".to_string()),
+            art_url: meta.art_url().map(|url| url.to_owned()),
+        }
+    }
+}
+
+/// From an art url, get the path of the local file.
+/// Only `file://` urls are supported, remote arts are ignored.
+fn art_path(url: &str) -> Option<PathBuf> {
+    let encoded = url.strip_prefix("file://")?;
+    // Decode the percent-encoded bytes (%20 for spaces, etc...)
+    let mut bytes = Vec::with_capacity(encoded.len());
+    let mut iter = encoded.bytes();
+    while let Some(byte) = iter.next() {
+        if byte == b'%' {
+            let hex: Vec<u8> = iter.by_ref().take(2).collect();
+            if hex.len() != 2 {
+                return None;
+            }
+            let decoded = std::str::from_utf8(&hex)
+                .ok()
+                .and_then(|hex| u8::from_str_radix(hex, 16).ok())?;
+            bytes.push(decoded);
+        } else {
+            bytes.push(byte);
         }
     }
+    Some(PathBuf::from(String::from_utf8(bytes).ok()?))
 }
 
 /// From metadata, extracts the artists name
@@ -62,3 +138,20 @@ fn format_artists(artists: String) -> String {
         .replace("VEVO", "")
         .replace("; ", " & ")
 }
+
+#[cfg(test)]
+mod test {
+    use super::art_path;
+    use std::path::PathBuf;
+
+    /// Local art urls are decoded, remote ones are ignored.
+    #[test]
+    fn decode_art_path() {
+        assert_eq!(
+            art_path("file:///home/user/.cache/cover%20art%C3%A9.png"),
+            Some(PathBuf::from("/home/user/.cache/cover art\u{e9}.png"))
+        );
+        assert_eq!(art_path("https://example.com/cover.png"), None);
+        assert_eq!(art_path("file:///bad%2"), None);
+    }
+}
@@ -1,29 +1,50 @@
+use super::art_path;
+use super::find_player;
 use super::Song;
 use crate::app::App;
 use crate::app::Application;
-use crate::component::{AppBar, Button, ButtonBar};
+use crate::app::show_indicators;
+use crate::bitmap::{Bitmap, Dithering};
+use crate::component::{AppBar, Button, ButtonBar, Marquee};
 use crate::display::G13Display;
+use crate::driver::{Led, LedStates};
 use crate::error::AppError;
-use crate::style::{FILL_OFF, FILL_ON, TEXT_LIGHT, TEXT_SMALL};
+use crate::settings;
+use crate::style::{BORDER_1, FILL_OFF, FILL_ON, TEXT_LIGHT, TEXT_REGULAR, TEXT_SMALL};
 use async_trait::async_trait;
 use embedded_graphics::egrectangle;
 use embedded_graphics::geometry::Point;
-use embedded_graphics::{fonts::Text, pixelcolor::BinaryColor, prelude::*};
-use mpris::Player;
-use mpris::PlayerFinder;
+use embedded_graphics::primitives::Rectangle;
+use log::warn;
+use mpris::PlaybackStatus;
+use embedded_graphics::{
+    fonts::{Font6x8, Text},
+    pixelcolor::BinaryColor,
+    prelude::*,
+};
 use once_cell::sync::Lazy;
-use std::time::Duration;
 use tokio::io::AsyncWrite;
 use tokio::time;
 use super::Command;
 use super::MusicError;
 
+/// The size of the album art thumbnail (in pixels)
+const ART_SIZE: u32 = 32;
+/// The left offset of the song texts, next to the album art
+const TEXT_OFFSET: i32 = ART_SIZE as i32 + 2;
+/// The width available for the song texts (in pixels)
+const TEXT_WIDTH: u32 = 160 - TEXT_OFFSET as u32;
+
 /// Music Player show the music asscoiated with a player
 #[derive(Clone, Debug, Default)]
 pub struct MusicPlayer {
     end: bool,
     player_name: String,
     commands: Vec<Command>,
+    /// The last album art loaded, with its url (None if it can't be loaded)
+    art: Option<(String, Option<Bitmap>)>,
+    /// Whether the player is playing (the backlight is then green)
+    playing: bool,
 }
 
 #[async_trait(?Send)]
@@ -32,24 +53,24 @@ impl Application for MusicPlayer {
     where
         W: AsyncWrite + Unpin,
     {
-        let mut interval = time::interval(Duration::from_millis(100));
+        let mut interval = time::interval(settings::refresh());
         let mut display = G13Display::new(out);
-        // Get a player finder
-        let finder = PlayerFinder::new().map_err(MusicError::from)?;
-        // get the players list
-        let players: Vec<Player<'_>> = finder
-            .find_all()
-            .map_err(MusicError::from)?;
-        // get the wanted player
-        let player: Player = players
-            .into_iter()
-            .find(|player| player.identity() == &self.player_name)
-            .ok_or(AppError::BadInitialization)?;
+        let player = find_player(&self.player_name)?;
 
         // Draw the base interface
         (*PLAYER_INTERFACE).clone().into_iter().draw(&mut display)?;
 
+        // Draw the app bar, with the player name
+        let mut appbar = AppBar::new(
+            format!("\u{266B} {}", self.player_name),
+            Point::new(ART_SIZE as i32 + 1, 0),
+            Point::new(160, 8),
+        );
+        appbar.clone().draw(&mut display)?;
+
         let mut last_song: Option<Song> = None;
+        let mut title = Marquee::new("", TEXT_WIDTH, Font6x8);
+        let mut artist = Marquee::new("", TEXT_WIDTH - 16, Font6x8);
         while !self.end {
             // Tick
             interval.tick().await;
@@ -65,48 +86,64 @@ impl Application for MusicPlayer {
                         player.checked_previous().map_err(|err| MusicError::CommandError(cmd.clone(), Box::new(err.into())))?
                     }
                     Command::Next => player.checked_next().map_err(|err| MusicError::CommandError(cmd.clone(), Box::new(err.into())))?,
+                    // Handled by the browser
+                    Command::GoTo | Command::ActivatePlaylist => false,
                 };
             }
 
+            // The status can't be always read, the backlight is then kept
+            if let Ok(status) = player.get_playback_status() {
+                let playing = status == PlaybackStatus::Playing;
+                if playing != self.playing {
+                    self.playing = playing;
+                    show_indicators(self);
+                }
+            }
+
             // get the current song
             let song = Song::from(player.get_metadata().map_err(MusicError::from)?);
 
-            // continue until song changed
-            if Some(&song) == last_song.as_ref() {
-                continue;
+            // Scroll the too long texts
+            if appbar.tick() {
+                appbar.clone().draw(&mut display)?;
             }
-            last_song = Some(song.clone());
-
-            // Clear the last song
-            egrectangle!(
-                top_left = Point::new(0, 10),
-                bottom_right = Point::new(160, 34),
-                style = *FILL_OFF,
-            )
-            .draw(&mut display)?;
-
-            // Print the song title
-            let mut offset = 10;
-            let mut lines = song.title.clone();
-            for _ in 1..=2 {
-                if lines.is_empty() { continue; }
-                // Get the number of chars to print
-                let chars = if lines.len() < 26 { lines.len() } else { 26 };
-                // Get the chars and print
-                let line: String = lines.drain(..chars).collect();
-                Text::new(&line, Point::new(0, offset))
-                    .into_styled(*TEXT_LIGHT)
+            let mut scrolled = title.tick();
+            scrolled |= artist.tick();
+
+            if Some(&song) != last_song.as_ref() {
+                last_song = Some(song.clone());
+
+                // Draw the album art (or its placeholder)
+                match self.get_art(&song) {
+                    Some(art) => art.pixels(Point::zero()).into_iter().draw(&mut display)?,
+                    None => (*ART_PLACEHOLDER).clone().into_iter().draw(&mut display)?,
+                }
+
+                // Clear the last song
+                egrectangle!(
+                    top_left = Point::new(TEXT_OFFSET, 10),
+                    bottom_right = Point::new(160, 34),
+                    style = *FILL_OFF,
+                )
+                .draw(&mut display)?;
+
+                // Print the artist prefix
+                Text::new("by", Point::new(TEXT_OFFSET, 26))
+                    .into_styled(*TEXT_SMALL)
                     .draw(&mut display)?;
 
-                offset += 8;
+                title = Marquee::new(song.title.as_str(), TEXT_WIDTH, Font6x8);
+                artist = Marquee::new(song.artist.as_str(), TEXT_WIDTH - 16, Font6x8);
+            } else if !scrolled {
+                // continue until song changed or texts scrolled
+                continue;
             }
 
-            // Print the artist name
-            Text::new("by", Point::new(0, offset + 2))
-                .into_styled(*TEXT_SMALL)
-                .draw(&mut display)?;
-            Text::new(&song.artist, Point::new(16, offset))
-                .into_styled(*TEXT_LIGHT)
+            // Print the song title and the artist name
+            title
+                .pixels(Point::new(TEXT_OFFSET, 12), *TEXT_LIGHT)
+                .into_iter()
+                .chain(artist.pixels(Point::new(TEXT_OFFSET + 16, 24), *TEXT_LIGHT))
                 .draw(&mut display)?;
 
             display.flush().await?;
@@ -144,6 +181,23 @@ impl Application for MusicPlayer {
         self.end = true;
         Ok(())
     }
+
+    // The backlight turns green while the music plays
+    fn backlight(&self) -> Option<[u8; 3]> {
+        if self.playing {
+            Some([0, 255, 0])
+        } else {
+            None
+        }
+    }
+
+    // M1 is lit while the music plays
+    fn leds(&self) -> LedStates {
+        LedStates {
+            m1: Some(if self.playing { Led::On } else { Led::Off }),
+            ..Default::default()
+        }
+    }
 }
 
 impl MusicPlayer {
@@ -153,15 +207,53 @@ impl MusicPlayer {
             end: false,
             player_name: player_name.into(),
             commands: Vec::new(),
+            art: None,
+            playing: false,
         }
     }
+
+    /// Create a new MusicPlayer for the same player
+    pub fn restart(&self) -> Self {
+        Self::new(self.player_name.as_str())
+    }
+
+    /// Get the album art of the song as a thumbnail.
+    /// The thumbnail is cached, so it is loaded only once per track.
+    fn get_art(&mut self, song: &Song) -> Option<&Bitmap> {
+        let url = song.art_url.as_ref()?;
+        let cached = matches!(&self.art, Some((last, _)) if last == url);
+        if !cached {
+            let dithering = settings::settings()
+                .dithering
+                .parse()
+                .unwrap_or(Dithering::FloydSteinberg);
+            let art = art_path(url).and_then(|path| {
+                Bitmap::open(&path, ART_SIZE, ART_SIZE, dithering)
+                    .map_err(|err| warn!("Can't load album art {:?}: {}", path, err))
+                    .ok()
+            });
+            self.art = Some((url.clone(), art));
+        }
+        self.art.as_ref().and_then(|(_, art)| art.as_ref())
+    }
 }
 
+// The album art placeholder, used when the song has no (local) art
+static ART_PLACEHOLDER: Lazy<Vec<Pixel<BinaryColor>>> = Lazy::new(|| {
+    let border = Rectangle::new(
+        Point::zero(),
+        Point::new(ART_SIZE as i32 - 1, ART_SIZE as i32 - 1),
+    )
+    .into_styled(*BORDER_1)
+    .into_iter();
+    let note = Text::new("\u{266B}", Point::new(12, 12))
+        .into_styled(*TEXT_REGULAR)
+        .into_iter();
+    border.chain(note).collect()
+});
+
 // The static part of the selector interface
 static PLAYER_INTERFACE: Lazy<Vec<Pixel<BinaryColor>>> = Lazy::new(|| {
-    // Draw the app bar
-    let appbar = AppBar::new("\u{266B} Playing:", Point::zero(), Point::new(160, 8)).into_iter();
-
     // Draw the button info
     let mut buttonbar: ButtonBar = Default::default();
     buttonbar.set_button1(Some(Button::from_str("\u{25BA}")));
@@ -179,5 +271,5 @@ static PLAYER_INTERFACE: Lazy<Vec<Pixel<BinaryColor>>> = Lazy::new(|| {
     buttonbar.set_button3(Some(Button::from_str("\u{25C4}\u{25C4}")));
     buttonbar.set_button4(Some(Button::from_str("\u{25BA}\u{25BA}")));
 
-    appbar.chain(buttonbar.into_iter()).collect()
+    buttonbar.into_iter().collect()
 });
@@ -1,15 +1,17 @@
+use super::MusicBrowser;
 use super::MusicPlayer;
 use crate::app::App;
 use crate::app::Application;
 use crate::component::{AppBar, Button, ButtonBar, List};
 use crate::display::G13Display;
 use crate::error::AppError;
+use crate::settings;
 use async_trait::async_trait;
 use embedded_graphics::{pixelcolor::BinaryColor, prelude::*};
+use log::debug;
 use mpris::Player;
 use mpris::PlayerFinder;
 use once_cell::sync::Lazy;
-use std::time::Duration;
 use tokio::io::AsyncWrite;
 use tokio::time;
 use super::MusicError;
@@ -19,6 +21,8 @@ use super::MusicError;
 pub struct MusicSelector {
     end: bool,
     return_menu: bool,
+    /// Open the browser of tracks and playlists of the selected player
+    browse: bool,
     list: Option<List>,
 }
 
@@ -32,10 +36,10 @@ impl Application for MusicSelector {
         if self.list.is_none() {
             self.init()?;
         }
-        let mut interval = time::interval(Duration::from_millis(100));
+        let mut interval = time::interval(settings::refresh());
         let mut display = G13Display::new(out);
         let mut last_cursor: usize = usize::MAX;
-        let list = self.list.as_ref().unwrap();
+        let list = self.list.as_mut().unwrap();
 
         // Draw the base interface
         (*SELECTOR_INTERFACE)
@@ -44,9 +48,9 @@ impl Application for MusicSelector {
             .draw(&mut display)?;
 
         while !self.end {
-            // Tick and wait for new cursor
+            // Tick and wait for new cursor (or a scrolling selection)
             interval.tick().await;
-            if last_cursor == list.get_cursor() {
+            if last_cursor == list.get_cursor() && !list.tick() {
                 continue;
             }
             last_cursor = list.get_cursor();
@@ -58,6 +62,8 @@ impl Application for MusicSelector {
 
         if self.return_menu {
             Ok(App::Menu(Default::default()))
+        } else if self.browse {
+            Ok(App::MusicBrowser(MusicBrowser::new(list.get_current())))
         } else {
             Ok(App::MusicPlayer(MusicPlayer::new(list.get_current())))
         }
@@ -69,8 +75,10 @@ impl Application for MusicSelector {
         Ok(())
     }
 
-    // Not used
+    // Browse the tracks and playlists of the selected player
     async fn button_l2(&mut self) -> Result<(), AppError> {
+        self.end = true;
+        self.browse = true;
         Ok(())
     }
 
@@ -111,6 +119,7 @@ impl MusicSelector {
             .map_err(MusicError::from)?;
         // get their names
         let names: Vec<String> = players.iter().map(|p| p.identity().to_owned()).collect();
+        debug!("Found {} players: {:?}", names.len(), names);
         // create the list
         self.list = Some(List::new(names));
         Ok(())
@@ -125,6 +134,7 @@ static SELECTOR_INTERFACE: Lazy<Vec<Pixel<BinaryColor>>> = Lazy::new(|| {
     // Draw the button info
     let mut buttonbar: ButtonBar = Default::default();
     buttonbar.set_button1(Some(Button::from_str("OK")));
+    buttonbar.set_button2(Some(Button::from_str("\u{2261}")));
     buttonbar.set_button3(Some(Button::from_str("\u{25B2}")));
     buttonbar.set_button4(Some(Button::from_str("\u{25BC}")));
 
@@ -0,0 +1,145 @@
+use crate::app::App;
+use crate::app::Application;
+use crate::component::{AppBar, Button, ButtonBar, List};
+use crate::display::G13Display;
+use crate::error::AppError;
+use crate::profile::{self, ProfileConfig};
+use crate::settings;
+use crate::style::TEXT_LIGHT;
+use async_trait::async_trait;
+use embedded_graphics::{fonts::Text, prelude::*};
+use std::marker::Unpin;
+use std::path::PathBuf;
+use tokio::io::AsyncWrite;
+use tokio::time;
+
+/// The profiles app lists the bind files of the profiles directory, and loads the selected one
+#[derive(Clone, Debug, Default)]
+pub struct ProfileSwitcher {
+    /// Define if the app should end/return
+    end: bool,
+    /// The directory of the bind files
+    directory: Option<PathBuf>,
+    /// The names of the profiles
+    names: Vec<String>,
+    /// The list of the profiles (None if not loaded or empty)
+    list: Option<List>,
+    /// The profile to load on the next execution
+    load: Option<String>,
+}
+
+#[async_trait(?Send)]
+impl Application for ProfileSwitcher {
+    async fn execute<W: Unpin + AsyncWrite>(&mut self, out: &mut W) -> Result<App, AppError>
+    where
+        W: AsyncWrite + Unpin,
+    {
+        let mut interval = time::interval(settings::refresh());
+        if self.directory.is_none() {
+            self.directory = ProfileConfig::load().get_directory();
+            self.reload();
+        }
+        // The driver pipe is only reachable from here
+        if let (Some(name), Some(directory)) = (self.load.take(), self.directory.as_ref()) {
+            profile::load(out, directory, &name).await?;
+            self.reload();
+        }
+        let mut display = G13Display::new(out);
+
+        AppBar::new("Profiles:", Point::zero(), Point::new(160, 8)).draw(&mut display)?;
+        let mut buttonbar: ButtonBar = Default::default();
+        if self.list.is_some() {
+            buttonbar.set_button1(Some(Button::from_str("Load")));
+            buttonbar.set_button3(Some(Button::from_str("\u{25B2}")));
+            buttonbar.set_button4(Some(Button::from_str("\u{25BC}")));
+        } else {
+            Text::new("No profile.", Point::new(4, 18))
+                .into_styled(*TEXT_LIGHT)
+                .draw(&mut display)?;
+        }
+        buttonbar.draw(&mut display)?;
+        display.flush().await?;
+
+        let mut last_cursor: usize = usize::MAX;
+        while !self.end {
+            // Tick and wait for new cursor (or a scrolling selection)
+            interval.tick().await;
+            let list = match self.list.as_mut() {
+                Some(list) => list,
+                None => continue,
+            };
+            if last_cursor == list.get_cursor() && !list.tick() {
+                continue;
+            }
+            last_cursor = list.get_cursor();
+
+            // Draw and flush
+            list.draw_default(&mut display)?;
+            display.flush().await?;
+        }
+
+        Ok(App::Menu(Default::default()))
+    }
+
+    /// Represents the load button
+    async fn button_l1(&mut self) -> Result<(), AppError> {
+        let cursor = self.list.as_ref().map(List::get_cursor);
+        self.load = cursor.and_then(|cursor| self.names.get(cursor).cloned());
+        Ok(())
+    }
+
+    /// Not used
+    async fn button_l2(&mut self) -> Result<(), AppError> {
+        Ok(())
+    }
+
+    /// Represents the previous button
+    async fn button_l3(&mut self) -> Result<(), AppError> {
+        if let Some(list) = self.list.as_mut() {
+            list.previous();
+        }
+        Ok(())
+    }
+
+    /// Represents the next button
+    async fn button_l4(&mut self) -> Result<(), AppError> {
+        if let Some(list) = self.list.as_mut() {
+            list.next();
+        }
+        Ok(())
+    }
+
+    /// Represents the return button (go to menu)
+    async fn button_bd(&mut self) -> Result<(), AppError> {
+        self.end = true;
+        Ok(())
+    }
+}
+
+impl ProfileSwitcher {
+    /// Load the list of the profiles (keeping the cursor), the loaded one marked
+    fn reload(&mut self) {
+        let cursor = self.list.as_ref().map_or(0, List::get_cursor);
+        self.names = self
+            .directory
+            .as_deref()
+            .map(profile::list)
+            .unwrap_or_default();
+        let active = profile::profiles().get_active().map(str::to_owned);
+        let entries: Vec<String> = self
+            .names
+            .iter()
+            .map(|name| match active.as_deref() {
+                Some(active) if active == name => format!("\u{25BA} {}", name),
+                _ => format!("  {}", name),
+            })
+            .collect();
+        self.list = if entries.is_empty() {
+            None
+        } else {
+            let mut list = List::new(entries);
+            (0..cursor).for_each(|_| list.next());
+            Some(list)
+        };
+    }
+}
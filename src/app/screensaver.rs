@@ -0,0 +1,313 @@
+use crate::app::App;
+use crate::app::Application;
+use crate::display::G13Display;
+use crate::error::AppError;
+use crate::life::Life;
+use crate::random::Rng;
+use crate::style::{BORDER_1, FILL_OFF, TEXT_BOLD};
+use async_trait::async_trait;
+use embedded_graphics::egrectangle;
+use embedded_graphics::fonts::Text;
+use embedded_graphics::pixelcolor::BinaryColor;
+use embedded_graphics::prelude::*;
+use std::marker::Unpin;
+use std::time::Duration;
+use strum_macros::{EnumString, EnumVariantNames, IntoStaticStr};
+use tokio::io::AsyncWrite;
+use tokio::time;
+
+/// The size of the LCD (in pixels)
+const WIDTH: i32 = 160;
+const HEIGHT: i32 = 43;
+/// The interval between two frames of the animations
+const FRAME: Duration = Duration::from_millis(80);
+/// The text of the bouncing logo, and the size of its box
+const LOGO: &str = "G13 apps";
+const LOGO_WIDTH: i32 = 8 * 8 + 4;
+const LOGO_HEIGHT: i32 = 8 + 4;
+/// The number of stars of the starfield
+const STARS: usize = 40;
+/// The generations of a game of life before a new soup
+const LIFE_GENERATIONS: u32 = 600;
+/// The live cells of a game of life under which a new soup starts
+const LIFE_SPARSE: usize = 20;
+
+/// The animations shown by the screensaver
+#[derive(Clone, Copy, Debug, PartialEq, EnumString, EnumVariantNames, IntoStaticStr)]
+#[strum(serialize_all = "snake_case")]
+pub enum Saver {
+    /// The logo, bouncing on the edges of the screen
+    Logo,
+    /// Stars flying by, at various speeds
+    Stars,
+    /// A game of life, from a random soup (a new one once stable or almost empty)
+    Life,
+}
+
+/// A star of the starfield, moving to the left
+#[derive(Clone, Copy, Debug)]
+struct Star {
+    position: Point,
+    speed: i32,
+}
+
+impl Star {
+    /// Create a star at the given column, on a random row and with a random speed
+    fn new(x: i32, rng: &mut Rng) -> Self {
+        Star {
+            position: Point::new(x, rng.below(HEIGHT as u32) as i32),
+            speed: 1 + rng.below(3) as i32,
+        }
+    }
+}
+
+/// The state of an animation
+#[derive(Clone, Debug)]
+enum Scene {
+    Logo { position: Point, speed: Point },
+    Stars(Vec<Star>),
+    Life(Life),
+}
+
+impl Scene {
+    /// Create the first frame of an animation
+    fn new(saver: Saver, rng: &mut Rng) -> Self {
+        match saver {
+            Saver::Logo => Scene::Logo {
+                position: Point::new(
+                    rng.below((WIDTH - LOGO_WIDTH) as u32) as i32,
+                    rng.below((HEIGHT - LOGO_HEIGHT) as u32) as i32,
+                ),
+                speed: Point::new(1, 1),
+            },
+            Saver::Stars => Scene::Stars(
+                (0..STARS)
+                    .map(|_| Star::new(rng.below(WIDTH as u32) as i32, rng))
+                    .collect(),
+            ),
+            Saver::Life => Scene::Life(Life::soup(WIDTH, HEIGHT, rng)),
+        }
+    }
+
+    /// Move to the next frame
+    fn step(&mut self, rng: &mut Rng) {
+        match self {
+            Scene::Logo { position, speed } => {
+                let next = *position + *speed;
+                if next.x < 0 || next.x + LOGO_WIDTH > WIDTH {
+                    speed.x = -speed.x;
+                }
+                if next.y < 0 || next.y + LOGO_HEIGHT > HEIGHT {
+                    speed.y = -speed.y;
+                }
+                *position += *speed;
+            }
+            Scene::Stars(stars) => {
+                for star in stars.iter_mut() {
+                    star.position.x -= star.speed;
+                    if star.position.x < 0 {
+                        *star = Star::new(WIDTH - 1, rng);
+                    }
+                }
+            }
+            Scene::Life(life) => {
+                if !life.step()
+                    || life.get_generation() >= LIFE_GENERATIONS
+                    || life.population() < LIFE_SPARSE
+                {
+                    *life = Life::soup(WIDTH, HEIGHT, rng);
+                }
+            }
+        }
+    }
+
+    /// Make the pixels of the frame (over a blank screen)
+    fn pixels(&self) -> Vec<Pixel<BinaryColor>> {
+        let mut pixels: Vec<Pixel<BinaryColor>> = Vec::new();
+        match self {
+            Scene::Logo { position, .. } => {
+                pixels.extend(&egrectangle!(
+                    top_left = *position,
+                    bottom_right = *position + Point::new(LOGO_WIDTH - 1, LOGO_HEIGHT - 1),
+                    style = *BORDER_1,
+                ));
+                pixels
+                    .extend(&Text::new(LOGO, *position + Point::new(2, 2)).into_styled(*TEXT_BOLD));
+            }
+            Scene::Stars(stars) => {
+                for star in stars {
+                    pixels.push(Pixel(star.position, BinaryColor::On));
+                    // The fastest ones leave a trail
+                    if star.speed > 2 && star.position.x + 1 < WIDTH {
+                        pixels.push(Pixel(star.position + Point::new(1, 0), BinaryColor::On));
+                    }
+                }
+            }
+            Scene::Life(life) => {
+                pixels.extend(life.alive().map(|(x, y)| Pixel(Point::new(x, y), BinaryColor::On)));
+            }
+        }
+        pixels
+    }
+}
+
+/// The screensaver, shown after a while without any key pressed.
+///
+/// It takes over the foreground app and turns the backlight off,
+/// the app is restored by the next key (without the action of the key).
+#[derive(Debug)]
+pub struct Screensaver {
+    /// Define if the app should end/return
+    end: bool,
+    /// The app in foreground when the screensaver started
+    previous: Option<Box<App>>,
+    /// The animation shown
+    scene: Scene,
+    /// The generator of the animation
+    rng: Rng,
+}
+
+impl Default for Screensaver {
+    // Nothing is covered, so the logo ends at once and the menu is opened
+    fn default() -> Self {
+        let mut rng = Rng::from_time();
+        Self {
+            end: true,
+            previous: None,
+            scene: Scene::new(Saver::Logo, &mut rng),
+            rng,
+        }
+    }
+}
+
+impl Screensaver {
+    /// Create the screensaver with the given animation, over the given app
+    pub fn new(previous: App, saver: Saver) -> Self {
+        let mut rng = Rng::from_time();
+        Self {
+            end: false,
+            previous: Some(Box::new(previous)),
+            scene: Scene::new(saver, &mut rng),
+            rng,
+        }
+    }
+
+    /// Get the app covered, restored afterwards
+    pub fn get_previous(&mut self) -> Option<&mut App> {
+        self.previous.as_deref_mut()
+    }
+
+    /// Stop the screensaver, getting the app to restore
+    pub fn wake(&mut self) -> App {
+        self.end = true;
+        self.previous
+            .take()
+            .map_or_else(|| App::Menu(Default::default()), |app| *app)
+    }
+
+    /// Any key stops the screensaver
+    fn key(&mut self) -> Result<(), AppError> {
+        self.end = true;
+        Ok(())
+    }
+}
+
+#[async_trait(?Send)]
+impl Application for Screensaver {
+    async fn execute<W: Unpin + AsyncWrite>(&mut self, out: &mut W) -> Result<App, AppError>
+    where
+        W: AsyncWrite + Unpin,
+    {
+        let mut interval = time::interval(FRAME);
+        let mut display = G13Display::new(out);
+
+        #[warn(clippy::while_immutable_condition)]
+        while !self.end {
+            egrectangle!(
+                top_left = Point::zero(),
+                bottom_right = Point::new(WIDTH - 1, HEIGHT - 1),
+                style = *FILL_OFF,
+            )
+            .draw(&mut display)?;
+            self.scene.pixels().into_iter().draw(&mut display)?;
+            display.flush().await?;
+            interval.tick().await;
+            self.scene.step(&mut self.rng);
+        }
+        Ok(self.wake())
+    }
+
+    /// Any key stops the screensaver
+    async fn button_l1(&mut self) -> Result<(), AppError> {
+        self.key()
+    }
+
+    /// Any key stops the screensaver
+    async fn button_l2(&mut self) -> Result<(), AppError> {
+        self.key()
+    }
+
+    /// Any key stops the screensaver
+    async fn button_l3(&mut self) -> Result<(), AppError> {
+        self.key()
+    }
+
+    /// Any key stops the screensaver
+    async fn button_l4(&mut self) -> Result<(), AppError> {
+        self.key()
+    }
+
+    /// Any key stops the screensaver
+    async fn button_bd(&mut self) -> Result<(), AppError> {
+        self.key()
+    }
+
+    /// The backlight is off while the screensaver runs
+    fn backlight(&self) -> Option<[u8; 3]> {
+        if self.end {
+            None
+        } else {
+            Some([0, 0, 0])
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::app::screensaver::{Saver, Scene, Screensaver, HEIGHT, WIDTH};
+    use crate::app::{App, Application};
+    use crate::random::Rng;
+    use std::io::Cursor;
+    use tokio::runtime::Runtime;
+
+    /// The animations stay on the screen.
+    #[test]
+    fn animations() {
+        for saver in [Saver::Logo, Saver::Stars, Saver::Life].iter() {
+            let mut rng = Rng::new(7);
+            let mut scene = Scene::new(*saver, &mut rng);
+            for _ in 0..1000 {
+                scene.step(&mut rng);
+                let pixels = scene.pixels();
+                assert!(!pixels.is_empty());
+                assert!(pixels.iter().all(|pixel| {
+                    (0..WIDTH).contains(&pixel.0.x) && (0..HEIGHT).contains(&pixel.0.y)
+                }));
+            }
+        }
+    }
+
+    /// A key restores the app and its backlight, without any action.
+    #[test]
+    fn wake() {
+        let mut rt = Runtime::new().unwrap();
+        let mut app = Screensaver::new(App::Logs(Default::default()), Saver::Stars);
+        assert_eq!(app.backlight(), Some([0, 0, 0]));
+        rt.block_on(app.button_l2()).unwrap();
+        let mut writer = Cursor::new(Vec::<u8>::new());
+        let previous = rt.block_on(app.execute(&mut writer)).unwrap();
+        assert_eq!(previous.name(), "logs");
+        assert!(writer.into_inner().is_empty());
+        assert_eq!(app.backlight(), None);
+    }
+}
@@ -0,0 +1,497 @@
+use crate::app::App;
+use crate::app::Application;
+use crate::app::{Saver, HIDDEN_APPS};
+#[cfg(feature = "music")]
+use crate::bitmap::Dithering;
+use crate::component::{AppBar, Button, ButtonBar, List, Spinner};
+use crate::display::G13Display;
+use crate::error::AppError;
+use crate::settings::{self, Settings, COLORS, SETTINGS_APP};
+use crate::style::TEXT_BOLD;
+use async_trait::async_trait;
+use embedded_graphics::{fonts::Text, prelude::*};
+use std::marker::Unpin;
+use strum::VariantNames;
+use tokio::io::AsyncWrite;
+use tokio::time;
+
+/// The delays proposed before the screensaver (in minutes, 0 for never)
+const IDLE_DELAYS: &[u64] = &[0, 1, 2, 5, 10, 15, 30, 60];
+
+/// A setting shown by the app
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Setting {
+    /// The app opened at startup
+    Startup,
+    /// The 12/24 hours time of the clock
+    #[cfg(feature = "clock")]
+    Hour12,
+    /// The refresh interval of the list based apps
+    Refresh,
+    /// The color of the keyboard backlight
+    Backlight,
+    /// The apps hidden from the menu
+    Hidden,
+    /// The delay before the screensaver
+    Idle,
+    /// The animation of the screensaver
+    Screensaver,
+    /// The binding of the keys at startup
+    Keybinds,
+    /// The check of the keys at startup
+    KeyCheck,
+    /// The dithering of the album arts
+    #[cfg(feature = "music")]
+    Dithering,
+}
+
+impl Setting {
+    /// Get all the settings, in the order of the list
+    fn all() -> Vec<Setting> {
+        let mut all = vec![Setting::Startup];
+        #[cfg(feature = "clock")]
+        all.push(Setting::Hour12);
+        all.extend(&[
+            Setting::Refresh,
+            Setting::Backlight,
+            Setting::Hidden,
+            Setting::Idle,
+            Setting::Screensaver,
+            Setting::Keybinds,
+            Setting::KeyCheck,
+        ]);
+        #[cfg(feature = "music")]
+        all.push(Setting::Dithering);
+        all
+    }
+
+    /// Get the name of the setting
+    fn label(self) -> &'static str {
+        match self {
+            Setting::Startup => "Startup",
+            #[cfg(feature = "clock")]
+            Setting::Hour12 => "12h clock",
+            Setting::Refresh => "Refresh",
+            Setting::Backlight => "Backlight",
+            Setting::Hidden => "Hidden",
+            Setting::Idle => "Idle",
+            Setting::Screensaver => "Saver",
+            Setting::Keybinds => "Keybinds",
+            Setting::KeyCheck => "Key check",
+            #[cfg(feature = "music")]
+            Setting::Dithering => "Dithering",
+        }
+    }
+
+    /// Get the current value of the setting, as shown in the list
+    fn value(self, settings: &Settings) -> String {
+        match self {
+            Setting::Startup => settings.startup.clone(),
+            #[cfg(feature = "clock")]
+            Setting::Hour12 => on_off(crate::app::clock::config::load().hour12),
+            Setting::Refresh => format!("{} ms", settings.refresh),
+            Setting::Backlight => settings::color_name(settings.backlight),
+            Setting::Hidden => match settings.hidden.len() {
+                0 => "none".to_owned(),
+                1 => "1 app".to_owned(),
+                count => format!("{} apps", count),
+            },
+            Setting::Idle => idle_delay(settings.idle),
+            Setting::Screensaver => settings.screensaver.clone(),
+            Setting::Keybinds => on_off(settings.install_binds),
+            Setting::KeyCheck => on_off(settings.check_keys),
+            #[cfg(feature = "music")]
+            Setting::Dithering => settings.dithering.clone(),
+        }
+    }
+}
+
+/// A setting being edited
+#[derive(Clone, Debug)]
+enum Edit {
+    /// A choice among values: the startup app, the backlight color, the screensaver...
+    Choice {
+        setting: Setting,
+        choices: Vec<String>,
+        index: usize,
+    },
+    /// The refresh interval, in tens of milliseconds
+    Refresh(Spinner),
+    /// The apps, hidden or not
+    Hidden(List),
+}
+
+/// The settings app, to change the settings from the keyboard.
+/// The changes are saved and applied at once.
+#[derive(Clone, Debug, Default)]
+pub struct SettingsEditor {
+    /// Define if the app should end/return
+    end: bool,
+    /// The list of the settings and their values
+    list: Option<List>,
+    /// The setting being edited
+    edit: Option<Edit>,
+}
+
+#[async_trait(?Send)]
+impl Application for SettingsEditor {
+    async fn execute<W: Unpin + AsyncWrite>(&mut self, out: &mut W) -> Result<App, AppError>
+    where
+        W: AsyncWrite + Unpin,
+    {
+        let mut interval = time::interval(settings::refresh());
+        let mut display = G13Display::new(out);
+        if self.list.is_none() {
+            self.load();
+        }
+
+        let mut buttonbar: ButtonBar = Default::default();
+        buttonbar.set_button3(Some(Button::from_str("\u{25B2}")));
+        buttonbar.set_button4(Some(Button::from_str("\u{25BC}")));
+        match &self.edit {
+            Some(Edit::Choice { setting, .. }) => {
+                AppBar::new(setting.label(), Point::zero(), Point::new(160, 8))
+                    .draw(&mut display)?;
+                buttonbar.set_button1(Some(Button::from_str("OK")));
+            }
+            Some(Edit::Refresh(_)) => {
+                AppBar::new("Refresh (ms):", Point::zero(), Point::new(160, 8))
+                    .draw(&mut display)?;
+                buttonbar.set_button1(Some(Button::from_str("OK")));
+            }
+            Some(Edit::Hidden(_)) => {
+                AppBar::new("Hidden apps:", Point::zero(), Point::new(160, 8))
+                    .draw(&mut display)?;
+                buttonbar.set_button1(Some(Button::from_str("I/O")));
+            }
+            None => {
+                AppBar::new("Settings:", Point::zero(), Point::new(160, 8)).draw(&mut display)?;
+                buttonbar.set_button1(Some(Button::from_str("OK")));
+            }
+        }
+        buttonbar.draw(&mut display)?;
+
+        // Draw the edited value, centered
+        let value = match &self.edit {
+            Some(Edit::Choice { choices, index, .. }) => choices.get(*index).cloned(),
+            Some(Edit::Refresh(spinner)) => Some((spinner.get_value() * 10).to_string()),
+            _ => None,
+        };
+        if let Some(value) = value {
+            let text = format!("\u{25C4} {} \u{25BA}", value);
+            let x = 80 - 4 * text.chars().count() as i32;
+            Text::new(&text, Point::new(x, 18))
+                .into_styled(*TEXT_BOLD)
+                .draw(&mut display)?;
+            display.flush().await?;
+        }
+
+        let mut last_cursor: usize = usize::MAX;
+        while !self.end {
+            // Tick and wait for new cursor (or a scrolling selection)
+            interval.tick().await;
+            let list = match (self.edit.as_mut(), self.list.as_mut()) {
+                (Some(Edit::Hidden(list)), _) | (None, Some(list)) => list,
+                _ => continue,
+            };
+            if last_cursor == list.get_cursor() && !list.tick() {
+                continue;
+            }
+            last_cursor = list.get_cursor();
+
+            // Draw and flush
+            list.draw_default(&mut display)?;
+            display.flush().await?;
+        }
+
+        Ok(App::Menu(Default::default()))
+    }
+
+    /// Represents the edit button (or save the edited setting, or toggle the selected app)
+    async fn button_l1(&mut self) -> Result<(), AppError> {
+        match self.edit.take() {
+            Some(Edit::Choice {
+                setting,
+                choices,
+                index,
+            }) => {
+                let value = choices.get(index).cloned().unwrap_or_default();
+                self.update(|settings| match setting {
+                    Setting::Startup => settings.startup = value,
+                    Setting::Backlight => {
+                        if let Some((_, color)) = COLORS.get(index) {
+                            settings.backlight = *color;
+                        }
+                    }
+                    Setting::Idle => {
+                        if let Some(delay) = IDLE_DELAYS.get(index) {
+                            settings.idle = *delay;
+                        }
+                    }
+                    Setting::Screensaver => settings.screensaver = value,
+                    #[cfg(feature = "music")]
+                    Setting::Dithering => settings.dithering = value,
+                    // The other settings are not edited with choices
+                    _ => {}
+                })?;
+            }
+            Some(Edit::Refresh(spinner)) => {
+                self.update(|settings| settings.refresh = spinner.get_value() as u64 * 10)?;
+            }
+            Some(Edit::Hidden(list)) => {
+                let cursor = list.get_cursor();
+                if let Some(app) = apps(&[SETTINGS_APP]).get(cursor).cloned() {
+                    self.update(|settings| {
+                        if settings.is_hidden(&app) {
+                            settings.hidden.retain(|hidden| *hidden != app);
+                        } else {
+                            settings.hidden.push(app);
+                        }
+                    })?;
+                }
+                self.edit = Some(Edit::Hidden(hidden_list(&settings::settings(), cursor)));
+            }
+            None => match self.selected() {
+                #[cfg(feature = "clock")]
+                Some(Setting::Hour12) => {
+                    toggle_hour12()?;
+                    self.reload();
+                }
+                Some(Setting::Keybinds) => {
+                    self.update(|settings| settings.install_binds = !settings.install_binds)?
+                }
+                Some(Setting::KeyCheck) => {
+                    self.update(|settings| settings.check_keys = !settings.check_keys)?
+                }
+                Some(setting) => self.edit = start_edit(setting),
+                None => {}
+            },
+        }
+        Ok(())
+    }
+
+    /// Not used
+    async fn button_l2(&mut self) -> Result<(), AppError> {
+        Ok(())
+    }
+
+    /// Represents the previous button (or the previous value)
+    async fn button_l3(&mut self) -> Result<(), AppError> {
+        match (self.edit.as_mut(), self.list.as_mut()) {
+            (Some(Edit::Choice { choices, index, .. }), _) => {
+                *index = (*index + choices.len() - 1) % choices.len();
+            }
+            (Some(Edit::Refresh(spinner)), _) => spinner.increment(),
+            (Some(Edit::Hidden(list)), _) | (None, Some(list)) => list.previous(),
+            (None, None) => {}
+        }
+        Ok(())
+    }
+
+    /// Represents the next button (or the next value)
+    async fn button_l4(&mut self) -> Result<(), AppError> {
+        match (self.edit.as_mut(), self.list.as_mut()) {
+            (Some(Edit::Choice { choices, index, .. }), _) => {
+                *index = (*index + 1) % choices.len();
+            }
+            (Some(Edit::Refresh(spinner)), _) => spinner.decrement(),
+            (Some(Edit::Hidden(list)), _) | (None, Some(list)) => list.next(),
+            (None, None) => {}
+        }
+        Ok(())
+    }
+
+    /// Represents the return button (cancel the edition or go to menu)
+    async fn button_bd(&mut self) -> Result<(), AppError> {
+        if self.edit.take().is_none() {
+            self.end = true;
+        }
+        Ok(())
+    }
+}
+
+impl SettingsEditor {
+    /// Load the list of the settings
+    fn load(&mut self) {
+        let settings = settings::settings();
+        self.list = Some(List::new(entries(&settings)));
+    }
+
+    /// Reload the list of the settings, keeping the cursor
+    fn reload(&mut self) {
+        let cursor = self.list.as_ref().map_or(0, List::get_cursor);
+        self.load();
+        if let Some(list) = self.list.as_mut() {
+            (0..cursor).for_each(|_| list.next());
+        }
+    }
+
+    /// Get the selected setting
+    fn selected(&self) -> Option<Setting> {
+        let cursor = self.list.as_ref()?.get_cursor();
+        Setting::all().get(cursor).copied()
+    }
+
+    /// Change the settings, save them and reload the list
+    fn update<F: FnOnce(&mut Settings)>(&mut self, change: F) -> Result<(), AppError> {
+        {
+            let mut settings = settings::settings();
+            change(&mut settings);
+            settings.save()?;
+        }
+        self.reload();
+        Ok(())
+    }
+}
+
+/// Switch the clock between 12 and 24 hours (applied when the clock is opened)
+#[cfg(feature = "clock")]
+fn toggle_hour12() -> Result<(), AppError> {
+    let mut config = crate::app::clock::config::load();
+    config.hour12 = !config.hour12;
+    Ok(config.save()?)
+}
+
+/// Show a delay before the screensaver
+fn idle_delay(minutes: u64) -> String {
+    match minutes {
+        0 => "never".to_owned(),
+        minutes => format!("{} min", minutes),
+    }
+}
+
+/// Show a switch setting
+fn on_off(value: bool) -> String {
+    if value { "on" } else { "off" }.to_owned()
+}
+
+/// Get the names of the apps of the menu, but the excluded ones
+fn apps(excluded: &[&str]) -> Vec<String> {
+    App::VARIANTS
+        .iter()
+        .filter(|name| !HIDDEN_APPS.contains(name) && !excluded.contains(name))
+        .map(|name| name.to_string())
+        .collect()
+}
+
+/// Make the entries of the settings list: their name and value
+fn entries(settings: &Settings) -> Vec<String> {
+    Setting::all()
+        .into_iter()
+        .map(|setting| format!("{:<10}{}", setting.label(), setting.value(settings)))
+        .collect()
+}
+
+/// Make the list of the apps which can be hidden, with the given cursor
+fn hidden_list(settings: &Settings, cursor: usize) -> List {
+    let entries = apps(&[SETTINGS_APP])
+        .into_iter()
+        .map(|app| {
+            let mark = if settings.is_hidden(&app) { "x" } else { " " };
+            format!("[{}] {}", mark, app)
+        })
+        .collect();
+    let mut list = List::new(entries);
+    (0..cursor).for_each(|_| list.next());
+    list
+}
+
+/// Start the edition of a setting (None if it is only toggled)
+fn start_edit(setting: Setting) -> Option<Edit> {
+    let settings = settings::settings();
+    match setting {
+        Setting::Startup => {
+            let mut choices = apps(&[]);
+            choices.insert(0, "menu".to_owned());
+            let index = choices
+                .iter()
+                .position(|app| *app == settings.startup)
+                .unwrap_or(0);
+            Some(Edit::Choice {
+                setting,
+                choices,
+                index,
+            })
+        }
+        #[cfg(feature = "clock")]
+        Setting::Hour12 => None,
+        Setting::Keybinds | Setting::KeyCheck => None,
+        Setting::Refresh => Some(Edit::Refresh(Spinner::new(
+            (settings.refresh / 10) as u32,
+            2,
+            50,
+        ))),
+        Setting::Backlight => Some(Edit::Choice {
+            setting,
+            choices: COLORS.iter().map(|(name, _)| (*name).to_owned()).collect(),
+            index: COLORS
+                .iter()
+                .position(|(_, color)| *color == settings.backlight)
+                .unwrap_or(0),
+        }),
+        Setting::Hidden => Some(Edit::Hidden(hidden_list(&settings, 0))),
+        Setting::Idle => Some(Edit::Choice {
+            setting,
+            choices: IDLE_DELAYS.iter().map(|delay| idle_delay(*delay)).collect(),
+            index: IDLE_DELAYS
+                .iter()
+                .position(|delay| *delay == settings.idle)
+                .unwrap_or(0),
+        }),
+        Setting::Screensaver => Some(Edit::Choice {
+            setting,
+            choices: Saver::VARIANTS.iter().map(|name| name.to_string()).collect(),
+            index: Saver::VARIANTS
+                .iter()
+                .position(|name| *name == settings.screensaver)
+                .unwrap_or(0),
+        }),
+        #[cfg(feature = "music")]
+        Setting::Dithering => Some(Edit::Choice {
+            setting,
+            choices: Dithering::VARIANTS.iter().map(|name| name.to_string()).collect(),
+            index: Dithering::VARIANTS
+                .iter()
+                .position(|name| *name == settings.dithering)
+                .unwrap_or(0),
+        }),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::app::settings::{apps, entries, hidden_list};
+    use crate::settings::Settings;
+
+    /// The settings are listed with their value.
+    #[test]
+    fn settings_entries() {
+        let settings = Settings {
+            backlight: [255, 0, 0],
+            hidden: vec!["logs".to_owned()],
+            ..Default::default()
+        };
+        let entries = entries(&settings);
+        assert_eq!(entries[0], "Startup   menu");
+        assert!(entries.contains(&"Refresh   100 ms".to_owned()));
+        assert!(entries.contains(&"Backlight Red".to_owned()));
+        assert!(entries.contains(&"Hidden    1 app".to_owned()));
+        assert!(entries.contains(&"Idle      10 min".to_owned()));
+        assert!(entries.contains(&"Key check on".to_owned()));
+    }
+
+    /// The hidden apps are marked, the settings app can't be hidden.
+    #[test]
+    fn hidden_apps() {
+        let settings = Settings {
+            hidden: vec!["logs".to_owned()],
+            ..Default::default()
+        };
+        let apps = apps(&["settings"]);
+        assert!(!apps.iter().any(|app| app == "settings" || app == "menu"));
+        let logs = apps.iter().position(|app| app == "logs").unwrap();
+        let list = hidden_list(&settings, logs);
+        assert_eq!(list.get_cursor(), logs);
+        assert_eq!(list.get_current(), "[x] logs");
+    }
+}
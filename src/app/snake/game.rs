@@ -0,0 +1,231 @@
+use crate::random::Rng;
+use std::collections::VecDeque;
+use std::time::Duration;
+
+/// The size of the playfield (in cells)
+pub const COLUMNS: i32 = 40;
+pub const ROWS: i32 = 8;
+/// The delay between two moves at start, and at full speed
+const START_PERIOD: u64 = 250;
+const MIN_PERIOD: u64 = 80;
+/// The number of moves before speeding up
+const SPEEDUP_STEPS: u32 = 40;
+
+/// A cell of the playfield (column, row)
+pub type Cell = (i32, i32);
+
+/// The direction of the snake
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Direction {
+    Up,
+    Down,
+    Left,
+    Right,
+}
+
+impl Direction {
+    /// Get the move of the head in this direction
+    fn offset(self) -> Cell {
+        match self {
+            Direction::Up => (0, -1),
+            Direction::Down => (0, 1),
+            Direction::Left => (-1, 0),
+            Direction::Right => (1, 0),
+        }
+    }
+
+    /// Get the opposite direction
+    fn opposite(self) -> Self {
+        match self {
+            Direction::Up => Direction::Down,
+            Direction::Down => Direction::Up,
+            Direction::Left => Direction::Right,
+            Direction::Right => Direction::Left,
+        }
+    }
+}
+
+/// A game of snake: eat the food to grow, without hitting the walls nor the snake itself.
+///
+/// The game only moves on [Game::step], and its food is placed by a seeded generator,
+/// so a game can be replayed.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Game {
+    /// The cells of the snake, the head first
+    snake: VecDeque<Cell>,
+    /// The direction of the last move
+    direction: Direction,
+    /// The direction of the next move
+    next_direction: Direction,
+    food: Cell,
+    rng: Rng,
+    score: u32,
+    /// The number of moves
+    steps: u32,
+    over: bool,
+}
+
+impl Game {
+    /// Start a game, the food placed from the given seed
+    pub fn new(seed: u64) -> Self {
+        let row = ROWS / 2;
+        let mut game = Game {
+            snake: (0..3).map(|i| (COLUMNS / 4 - i, row)).collect(),
+            direction: Direction::Right,
+            next_direction: Direction::Right,
+            food: (0, 0),
+            rng: Rng::new(seed),
+            score: 0,
+            steps: 0,
+            over: false,
+        };
+        game.place_food();
+        game
+    }
+
+    /// Turn on the next move (the snake can't go back)
+    pub fn turn(&mut self, direction: Direction) {
+        if direction != self.direction.opposite() {
+            self.next_direction = direction;
+        }
+    }
+
+    /// Move the snake by one cell
+    pub fn step(&mut self) {
+        if self.over {
+            return;
+        }
+        self.direction = self.next_direction;
+        self.steps += 1;
+        let (x, y) = self.snake[0];
+        let (dx, dy) = self.direction.offset();
+        let head = (x + dx, y + dy);
+        let eating = head == self.food;
+        // Unless eating, the tail moves away at the same time
+        let body = self.snake.len() - if eating { 0 } else { 1 };
+        let inside = (0..COLUMNS).contains(&head.0) && (0..ROWS).contains(&head.1);
+        if !inside || self.snake.iter().take(body).any(|cell| *cell == head) {
+            self.over = true;
+            return;
+        }
+        if !eating {
+            self.snake.pop_back();
+        }
+        self.snake.push_front(head);
+        if eating {
+            self.score += 1;
+            self.place_food();
+        }
+    }
+
+    /// Get the delay between two moves: shorter and shorter
+    pub fn period(&self) -> Duration {
+        let speedup = 10 * (self.steps / SPEEDUP_STEPS) as u64;
+        Duration::from_millis(START_PERIOD.saturating_sub(speedup).max(MIN_PERIOD))
+    }
+
+    /// Get the cells of the snake, the head first
+    pub fn get_snake(&self) -> &VecDeque<Cell> {
+        &self.snake
+    }
+
+    pub fn get_food(&self) -> Cell {
+        self.food
+    }
+
+    pub fn get_score(&self) -> u32 {
+        self.score
+    }
+
+    pub fn is_over(&self) -> bool {
+        self.over
+    }
+
+    /// Place the food on a random free cell (the game is won if there is none)
+    fn place_food(&mut self) {
+        if self.snake.len() >= (COLUMNS * ROWS) as usize {
+            self.over = true;
+            return;
+        }
+        loop {
+            let cell = (
+                self.rng.below(COLUMNS as u32) as i32,
+                self.rng.below(ROWS as u32) as i32,
+            );
+            if !self.snake.contains(&cell) {
+                self.food = cell;
+                return;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::app::snake::game::{Direction, Game, COLUMNS};
+    use std::time::Duration;
+
+    /// The snake grows when eating, and dies on the walls.
+    #[test]
+    fn eat_and_die() {
+        let mut game = Game::new(1);
+        let (x, y) = game.get_snake()[0];
+        game.food = (x + 1, y);
+        game.step();
+        assert_eq!(game.get_score(), 1);
+        assert_eq!(game.get_snake().len(), 4);
+        assert_eq!(game.get_snake()[0], (x + 1, y));
+        assert!(!game.get_snake().contains(&game.get_food()));
+        // Can't go back
+        game.turn(Direction::Left);
+        game.step();
+        assert_eq!(game.get_snake()[0], (x + 2, y));
+        while !game.is_over() {
+            game.step();
+        }
+        assert_eq!(game.get_snake()[0].0, COLUMNS - 1);
+    }
+
+    /// The snake dies when biting itself.
+    #[test]
+    fn bite() {
+        let mut game = Game::new(1);
+        let (x, y) = game.get_snake()[0];
+        // Grow to 5 cells
+        for i in 1..=2 {
+            game.food = (x + i, y);
+            game.step();
+        }
+        for direction in [Direction::Down, Direction::Left, Direction::Up].iter() {
+            game.turn(*direction);
+            game.step();
+        }
+        assert!(game.is_over());
+    }
+
+    /// A game is replayed from its seed and moves, and speeds up over time.
+    #[test]
+    fn replay() {
+        let play = || {
+            let mut game = Game::new(42);
+            let moves = [
+                Direction::Down,
+                Direction::Right,
+                Direction::Up,
+                Direction::Right,
+            ];
+            for i in 0..200 {
+                game.turn(moves[i / 3 % moves.len()]);
+                game.step();
+            }
+            game
+        };
+        assert_eq!(play(), play());
+        let mut game = Game::new(42);
+        assert_eq!(game.period(), Duration::from_millis(250));
+        game.steps = 400;
+        assert_eq!(game.period(), Duration::from_millis(150));
+        game.steps = 10_000;
+        assert_eq!(game.period(), Duration::from_millis(80));
+    }
+}
@@ -0,0 +1,253 @@
+use crate::app::App;
+use crate::app::Application;
+use crate::component::{AppBar, Button, ButtonBar};
+use crate::display::G13Display;
+use crate::error::AppError;
+use crate::random::Rng;
+use crate::score::HighScores;
+use crate::style::{BORDER_1, FILL_OFF, FILL_ON, TEXT_SMALL, TITLE_BOLD};
+use async_trait::async_trait;
+use embedded_graphics::egrectangle;
+use embedded_graphics::fonts::Text;
+use embedded_graphics::pixelcolor::BinaryColor;
+use embedded_graphics::prelude::*;
+use game::{Cell, Direction, Game, COLUMNS, ROWS};
+use log::{error, info};
+use std::marker::Unpin;
+use std::time::{Duration, Instant};
+use tokio::io::AsyncWrite;
+use tokio::time;
+
+mod game;
+
+/// The name of the game, for its high scores
+const GAME: &str = "snake";
+/// The size of a cell (in pixels)
+const CELL: i32 = 4;
+/// The top of the playfield, under the score bar
+const TOP: i32 = 10;
+/// The interval between two checks of the game clock
+const TICK: Duration = Duration::from_millis(10);
+/// The most late moves caught up at once, the others are skipped
+/// (the game is stopped while covered by another screen)
+const CATCH_UP: u32 = 2;
+
+/// The snake game: L1 to L4 turn left, up, down and right.
+///
+/// The snake moves at a fixed rate (faster and faster), whatever the keys pressed.
+#[derive(Debug, Default)]
+pub struct Snake {
+    /// Define if the app should end/return
+    end: bool,
+    /// The running game (None until started)
+    game: Option<Game>,
+    /// The time of the last move of the snake
+    last_step: Option<Instant>,
+    /// The high scores (loaded on first execution)
+    scores: Option<HighScores>,
+    /// Define if the score of the ended game is recorded
+    recorded: bool,
+}
+
+impl Snake {
+    /// Turn the snake, or start a new game once over (with L1)
+    fn turn(&mut self, direction: Direction) -> Result<(), AppError> {
+        match self.game.as_mut() {
+            Some(game) if game.is_over() && direction == Direction::Left => self.new_game(),
+            Some(game) if game.is_over() => {}
+            Some(game) => game.turn(direction),
+            None => {}
+        }
+        Ok(())
+    }
+
+    /// Start a new game
+    fn new_game(&mut self) {
+        self.game = Some(Game::new(Rng::from_time().next_u64()));
+        self.last_step = None;
+        self.recorded = false;
+    }
+
+    /// Record the score of the ended game
+    fn record(&mut self) {
+        let (game, scores) = match (self.game.as_ref(), self.scores.as_mut()) {
+            (Some(game), Some(scores)) if game.is_over() && !self.recorded => (game, scores),
+            _ => return,
+        };
+        self.recorded = true;
+        if let Some(rank) = scores.add(game.get_score()) {
+            info!("New snake high score: {} (#{})", game.get_score(), rank + 1);
+            if let Err(err) = scores.save() {
+                error!("Can't save the snake scores: {}", err);
+            }
+        }
+    }
+}
+
+#[async_trait(?Send)]
+impl Application for Snake {
+    async fn execute<W: Unpin + AsyncWrite>(&mut self, out: &mut W) -> Result<App, AppError>
+    where
+        W: AsyncWrite + Unpin,
+    {
+        let mut interval = time::interval(TICK);
+        let mut display = G13Display::new(out);
+        if self.game.is_none() {
+            self.new_game();
+        }
+        let best = self
+            .scores
+            .get_or_insert_with(|| HighScores::load(GAME))
+            .best();
+
+        // Draw at once (the key pressed may have changed the game)
+        let mut changed = true;
+        #[warn(clippy::while_immutable_condition)]
+        while !self.end {
+            let game = match self.game.as_mut() {
+                Some(game) => game,
+                None => break,
+            };
+            // Move at the rate of the game, catching up the late moves
+            let now = Instant::now();
+            let last_step = self.last_step.get_or_insert(now);
+            if now.duration_since(*last_step) > game.period() * CATCH_UP {
+                *last_step = now - game.period() * CATCH_UP;
+            }
+            while !game.is_over() && now.duration_since(*last_step) >= game.period() {
+                *last_step += game.period();
+                game.step();
+                changed = true;
+            }
+
+            let over = game.is_over();
+            if changed {
+                changed = false;
+                let title = format!(
+                    "Snake {:>4}   Best {:>4}",
+                    game.get_score(),
+                    best.max(game.get_score())
+                );
+                AppBar::new(title, Point::zero(), Point::new(160, 8)).draw(&mut display)?;
+                make_playfield(game).into_iter().draw(&mut display)?;
+                if over {
+                    Text::new("GAME OVER", Point::new(44, 16))
+                        .into_styled(*TITLE_BOLD)
+                        .draw(&mut display)?;
+                    if let Some(table) = self.scores.as_ref().and_then(make_table) {
+                        let x = 80 - 3 * table.len() as i32;
+                        Text::new(&table, Point::new(x, 28))
+                            .into_styled(*TEXT_SMALL)
+                            .draw(&mut display)?;
+                    }
+                    let mut buttonbar: ButtonBar = Default::default();
+                    buttonbar.set_button1(Some(Button::from_str("Again")));
+                    buttonbar.draw(&mut display)?;
+                }
+                display.flush().await?;
+            }
+            if over && !self.recorded {
+                self.record();
+                // Draw again, with the new high scores
+                changed = true;
+            }
+            interval.tick().await;
+        }
+
+        Ok(App::Menu(Default::default()))
+    }
+
+    /// Represents the left button (or a new game, once over)
+    async fn button_l1(&mut self) -> Result<(), AppError> {
+        self.turn(Direction::Left)
+    }
+
+    /// Represents the up button
+    async fn button_l2(&mut self) -> Result<(), AppError> {
+        self.turn(Direction::Up)
+    }
+
+    /// Represents the down button
+    async fn button_l3(&mut self) -> Result<(), AppError> {
+        self.turn(Direction::Down)
+    }
+
+    /// Represents the right button
+    async fn button_l4(&mut self) -> Result<(), AppError> {
+        self.turn(Direction::Right)
+    }
+
+    /// Represents the return button (go to menu)
+    async fn button_bd(&mut self) -> Result<(), AppError> {
+        self.end = true;
+        Ok(())
+    }
+}
+
+/// Make the line of the high scores (None if not played yet)
+fn make_table(scores: &HighScores) -> Option<String> {
+    if scores.get_scores().is_empty() {
+        return None;
+    }
+    let scores: Vec<String> = scores.get_scores().iter().map(u32::to_string).collect();
+    Some(format!("Best {}", scores.join(" ")))
+}
+
+/// Get the top left pixel of a cell
+fn cell_point((x, y): Cell) -> Point {
+    Point::new(x * CELL, TOP + y * CELL)
+}
+
+/// Make a drawable iterator of the playfield: the snake as full cells, the food as a ring
+fn make_playfield(game: &Game) -> Vec<Pixel<BinaryColor>> {
+    let mut pixels: Vec<Pixel<BinaryColor>> = Vec::new();
+    pixels.extend(&egrectangle!(
+        top_left = Point::new(0, TOP),
+        bottom_right = Point::new(COLUMNS * CELL - 1, TOP + ROWS * CELL - 1),
+        style = *FILL_OFF,
+    ));
+    for cell in game.get_snake() {
+        let point = cell_point(*cell);
+        pixels.extend(&egrectangle!(
+            top_left = point,
+            bottom_right = point + Point::new(CELL - 2, CELL - 2),
+            style = *FILL_ON,
+        ));
+    }
+    let food = cell_point(game.get_food());
+    pixels.extend(&egrectangle!(
+        top_left = food,
+        bottom_right = food + Point::new(CELL - 2, CELL - 2),
+        style = *BORDER_1,
+    ));
+    pixels
+}
+
+#[cfg(test)]
+mod test {
+    use crate::app::snake::game::Game;
+    use crate::app::snake::{make_playfield, make_table};
+    use crate::score::HighScores;
+    use embedded_graphics::pixelcolor::BinaryColor;
+
+    /// The snake cells are full, the food is a ring.
+    #[test]
+    fn playfield() {
+        let game = Game::new(3);
+        let lit = make_playfield(&game)
+            .iter()
+            .filter(|pixel| pixel.1 == BinaryColor::On)
+            .count();
+        assert_eq!(lit, 3 * 9 + 8);
+    }
+
+    /// The high scores are listed once played.
+    #[test]
+    fn table() {
+        let mut scores = HighScores::default();
+        assert_eq!(make_table(&scores), None);
+        scores.add(12);
+        scores.add(40);
+        assert_eq!(make_table(&scores).as_deref(), Some("Best 40 12"));
+    }
+}
@@ -0,0 +1,326 @@
+use crate::app::App;
+use crate::app::Application;
+use crate::component::{BigText, Button, ButtonBar, List};
+use crate::display::G13Display;
+use crate::error::AppError;
+use crate::style::{FILL_OFF, TEXT_BOLD, TEXT_LIGHT};
+use crate::time::{MonotonicSource, SystemTime};
+use async_trait::async_trait;
+use embedded_graphics::egrectangle;
+use embedded_graphics::{fonts::Text, prelude::*};
+use once_cell::sync::Lazy;
+use std::marker::Unpin;
+use std::sync::{Mutex, MutexGuard, PoisonError};
+use std::time::{Duration, Instant};
+use tokio::io::AsyncWrite;
+use tokio::time;
+
+/// The stopwatch of the g13 apps, it keeps running when its app is closed
+static STOPWATCH: Lazy<Mutex<Stopwatch<SystemTime>>> =
+    Lazy::new(|| Mutex::new(Stopwatch::new(SystemTime)));
+
+/// A stopwatch, measuring the running time and its laps
+#[derive(Debug)]
+pub struct Stopwatch<T: MonotonicSource> {
+    time: T,
+    /// When the stopwatch was (re)started, None if it is stopped
+    started: Option<Instant>,
+    /// The time measured before the last start
+    accumulated: Duration,
+    /// The measured time at each lap, oldest first
+    laps: Vec<Duration>,
+}
+
+impl<T: MonotonicSource> Stopwatch<T> {
+    /// Create a stopped stopwatch, using the given clock
+    pub fn new(time: T) -> Self {
+        Self {
+            time,
+            started: None,
+            accumulated: Duration::default(),
+            laps: Vec::new(),
+        }
+    }
+
+    /// Check if the stopwatch is running
+    pub fn is_running(&self) -> bool {
+        self.started.is_some()
+    }
+
+    /// Get the measured time
+    pub fn elapsed(&self) -> Duration {
+        let running = self.started.map_or_else(Duration::default, |started| {
+            self.time.instant().saturating_duration_since(started)
+        });
+        self.accumulated + running
+    }
+
+    /// Start the stopwatch, or stop it if it is running
+    pub fn toggle(&mut self) {
+        match self.started.take() {
+            Some(started) => {
+                self.accumulated += self.time.instant().saturating_duration_since(started)
+            }
+            None => self.started = Some(self.time.instant()),
+        }
+    }
+
+    /// Record a lap, only if the stopwatch is running
+    pub fn lap(&mut self) {
+        if self.is_running() {
+            self.laps.push(self.elapsed());
+        }
+    }
+
+    /// Stop the stopwatch and forget the measured time and the laps
+    pub fn reset(&mut self) {
+        self.started = None;
+        self.accumulated = Duration::default();
+        self.laps.clear();
+    }
+
+    /// Get the measured time at each lap, oldest first
+    pub fn get_laps(&self) -> &[Duration] {
+        &self.laps
+    }
+}
+
+/// Get the stopwatch of the g13 apps
+pub fn stopwatch() -> MutexGuard<'static, Stopwatch<SystemTime>> {
+    STOPWATCH.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+/// Format a duration as "MM:SS" (or "H:MM:SS" after an hour) and its milliseconds ".mmm"
+fn format_duration(duration: Duration) -> (String, String) {
+    let seconds = duration.as_secs();
+    let (hours, minutes, seconds) = (seconds / 3600, seconds / 60 % 60, seconds % 60);
+    let time = if hours > 0 {
+        format!("{}:{:02}:{:02}", hours, minutes, seconds)
+    } else {
+        format!("{:02}:{:02}", minutes, seconds)
+    };
+    (time, format!(".{:03}", duration.subsec_millis()))
+}
+
+/// Make the label of a lap, with its time and its split (the time since the previous lap)
+fn lap_label(number: usize, time: Duration, previous: Duration) -> String {
+    let (total, total_ms) = format_duration(time);
+    let (split, split_ms) = format_duration(time.checked_sub(previous).unwrap_or_default());
+    format!("#{} {}{} +{}{}", number, total, total_ms, split, split_ms)
+}
+
+/// The stopwatch app, a lap timer with millisecond precision.
+///
+/// The stopwatch is shared by all its instances, so it keeps running in background.
+#[derive(Clone, Debug, Default)]
+pub struct LapTimer {
+    /// Define if the app should end/return
+    end: bool,
+    /// The list of the laps, newest first
+    list: Option<List>,
+    /// The number of laps in the list
+    laps: usize,
+}
+
+#[async_trait(?Send)]
+impl Application for LapTimer {
+    async fn execute<W: Unpin + AsyncWrite>(&mut self, out: &mut W) -> Result<App, AppError>
+    where
+        W: AsyncWrite + Unpin,
+    {
+        let mut interval = time::interval(Duration::from_millis(50));
+        let mut display = G13Display::new(out);
+
+        // Draw the button info
+        let running = stopwatch().is_running();
+        let mut buttonbar: ButtonBar = Default::default();
+        buttonbar.set_button1(Some(Button::from_str(if running {
+            "Stop"
+        } else {
+            "Start"
+        })));
+        if running {
+            buttonbar.set_button2(Some(Button::from_str("Lap")));
+        }
+        buttonbar.set_button3(Some(Button::from_str("Reset")));
+        buttonbar.set_button4(Some(Button::from_str("\u{25BC}")));
+        buttonbar.draw(&mut display)?;
+
+        let mut first = true;
+        while !self.end {
+            let (elapsed, running) = {
+                let stopwatch = stopwatch();
+                self.load(&stopwatch);
+                (stopwatch.elapsed(), stopwatch.is_running())
+            };
+            let scrolled = self.list.as_mut().is_some_and(List::tick);
+            if first || running || scrolled {
+                first = false;
+                egrectangle!(
+                    top_left = Point::zero(),
+                    bottom_right = Point::new(159, 34),
+                    style = *FILL_OFF,
+                )
+                .draw(&mut display)?;
+
+                // Draw the time in large digits, then its milliseconds
+                let (time, millis) = format_duration(elapsed);
+                let width = time.chars().count() as i32 * 16;
+                BigText::new(time, Point::new(2, 0), 2).draw(&mut display)?;
+                Text::new(&millis, Point::new(4 + width, 8))
+                    .into_styled(*TEXT_BOLD)
+                    .draw(&mut display)?;
+
+                // Draw the laps
+                match self.list.as_mut() {
+                    Some(list) => list.draw_within_border(
+                        &mut display,
+                        Point::new(0, 17),
+                        Point::new(159, 34),
+                    )?,
+                    None => Text::new("No lap", Point::new(1, 25))
+                        .into_styled(*TEXT_LIGHT)
+                        .draw(&mut display)?,
+                }
+                display.flush().await?;
+            }
+            interval.tick().await;
+        }
+
+        Ok(App::Menu(Default::default()))
+    }
+
+    /// Represents the start/stop button
+    async fn button_l1(&mut self) -> Result<(), AppError> {
+        stopwatch().toggle();
+        Ok(())
+    }
+
+    /// Represents the lap button
+    async fn button_l2(&mut self) -> Result<(), AppError> {
+        stopwatch().lap();
+        Ok(())
+    }
+
+    /// Represents the reset button
+    async fn button_l3(&mut self) -> Result<(), AppError> {
+        stopwatch().reset();
+        Ok(())
+    }
+
+    /// Represents the next lap button (back to the newest after the oldest)
+    async fn button_l4(&mut self) -> Result<(), AppError> {
+        if let Some(list) = self.list.as_mut() {
+            let cursor = list.get_cursor();
+            list.next();
+            if cursor == list.get_cursor() {
+                list.reset();
+            }
+        }
+        Ok(())
+    }
+
+    /// Represents the return button (go to menu, the stopwatch keeps running)
+    async fn button_bd(&mut self) -> Result<(), AppError> {
+        self.end = true;
+        Ok(())
+    }
+}
+
+impl LapTimer {
+    /// Load the list of the laps, if they changed
+    fn load<T: MonotonicSource>(&mut self, stopwatch: &Stopwatch<T>) {
+        let laps = stopwatch.get_laps();
+        if self.laps == laps.len() && (self.list.is_some() || laps.is_empty()) {
+            return;
+        }
+        self.laps = laps.len();
+        self.list = if laps.is_empty() {
+            None
+        } else {
+            let entries = laps
+                .iter()
+                .enumerate()
+                .rev()
+                .map(|(i, time)| {
+                    let previous = i.checked_sub(1).map_or_else(Duration::default, |i| laps[i]);
+                    lap_label(i + 1, *time, previous)
+                })
+                .collect();
+            Some(List::new(entries))
+        };
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::app::stopwatch::{format_duration, LapTimer, Stopwatch};
+    use crate::time::ManualTime;
+    use std::time::Duration;
+
+    /// The stopwatch only measures the time while running.
+    #[test]
+    fn start_stop_lap() {
+        let clock = ManualTime::new();
+        let mut stopwatch = Stopwatch::new(clock.clone());
+        clock.advance(Duration::from_secs(5));
+        assert_eq!(stopwatch.elapsed(), Duration::default());
+        // Not running, no lap
+        stopwatch.lap();
+        assert!(stopwatch.get_laps().is_empty());
+
+        stopwatch.toggle();
+        clock.advance(Duration::from_millis(1500));
+        stopwatch.lap();
+        clock.advance(Duration::from_millis(500));
+        stopwatch.toggle();
+        clock.advance(Duration::from_secs(10));
+        assert!(!stopwatch.is_running());
+        assert_eq!(stopwatch.elapsed(), Duration::from_secs(2));
+
+        stopwatch.toggle();
+        clock.advance(Duration::from_secs(1));
+        stopwatch.lap();
+        assert_eq!(
+            stopwatch.get_laps(),
+            &[Duration::from_millis(1500), Duration::from_secs(3)]
+        );
+
+        stopwatch.reset();
+        assert!(!stopwatch.is_running());
+        assert_eq!(stopwatch.elapsed(), Duration::default());
+        assert!(stopwatch.get_laps().is_empty());
+    }
+
+    /// The time shows the hours only when needed.
+    #[test]
+    fn formatting() {
+        assert_eq!(
+            format_duration(Duration::from_millis(62_345)),
+            ("01:02".to_owned(), ".345".to_owned())
+        );
+        assert_eq!(
+            format_duration(Duration::from_millis(3_723_004)),
+            ("1:02:03".to_owned(), ".004".to_owned())
+        );
+    }
+
+    /// The laps are listed newest first, with their split.
+    #[test]
+    fn lap_list() {
+        let clock = ManualTime::new();
+        let mut stopwatch = Stopwatch::new(clock.clone());
+        let mut app = LapTimer::default();
+        app.load(&stopwatch);
+        assert!(app.list.is_none());
+
+        stopwatch.toggle();
+        clock.advance(Duration::from_secs(10));
+        stopwatch.lap();
+        clock.advance(Duration::from_secs(5));
+        stopwatch.lap();
+        app.load(&stopwatch);
+        let list = app.list.as_ref().unwrap();
+        assert_eq!(list.get_current(), "#2 00:15.000 +00:05.000");
+    }
+}
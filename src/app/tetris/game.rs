@@ -0,0 +1,404 @@
+use crate::random::Rng;
+use std::time::Duration;
+
+/// The size of the well (in cells)
+pub const WIDTH: i32 = 10;
+pub const HEIGHT: i32 = 20;
+/// The delay of the fall at the first level, and at the fastest one (in milliseconds)
+const START_PERIOD: u64 = 800;
+const MIN_PERIOD: u64 = 100;
+/// The speedup of the fall for each level (in milliseconds)
+const LEVEL_SPEEDUP: u64 = 70;
+/// The duration of the animation of the cleared lines
+const CLEAR_DELAY: Duration = Duration::from_millis(300);
+/// The lines to clear for the next level
+const LEVEL_LINES: u32 = 10;
+/// The points of 1, 2, 3 and 4 lines cleared at once (for each level)
+const LINE_POINTS: [u32; 4] = [40, 100, 300, 1200];
+
+/// A cell of the well (column, row), the row 0 on top
+pub type Cell = (i32, i32);
+
+/// The kinds of pieces
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Kind {
+    I,
+    O,
+    T,
+    S,
+    Z,
+    J,
+    L,
+}
+
+impl Kind {
+    const ALL: [Kind; 7] = [
+        Kind::I,
+        Kind::O,
+        Kind::T,
+        Kind::S,
+        Kind::Z,
+        Kind::J,
+        Kind::L,
+    ];
+
+    /// Get the cells of the piece, in its box, before any rotation
+    fn cells(self) -> [Cell; 4] {
+        match self {
+            Kind::I => [(0, 1), (1, 1), (2, 1), (3, 1)],
+            Kind::O => [(0, 0), (1, 0), (0, 1), (1, 1)],
+            Kind::T => [(1, 0), (0, 1), (1, 1), (2, 1)],
+            Kind::S => [(1, 0), (2, 0), (0, 1), (1, 1)],
+            Kind::Z => [(0, 0), (1, 0), (1, 1), (2, 1)],
+            Kind::J => [(0, 0), (0, 1), (1, 1), (2, 1)],
+            Kind::L => [(2, 0), (0, 1), (1, 1), (2, 1)],
+        }
+    }
+
+    /// Get the size of the box of the piece, in which it rotates
+    fn size(self) -> i32 {
+        match self {
+            Kind::I => 4,
+            Kind::O => 2,
+            _ => 3,
+        }
+    }
+
+    /// Pick a random kind
+    fn random(rng: &mut Rng) -> Self {
+        Self::ALL[rng.below(Self::ALL.len() as u32) as usize]
+    }
+}
+
+/// A piece falling in the well
+#[derive(Clone, Debug, PartialEq)]
+pub struct Piece {
+    kind: Kind,
+    /// The cells of the piece in its box, rotated
+    cells: [Cell; 4],
+    /// The position of the box in the well
+    position: Cell,
+}
+
+impl Piece {
+    /// Create a piece on top of the well, centered
+    fn new(kind: Kind) -> Self {
+        Piece {
+            kind,
+            cells: kind.cells(),
+            position: ((WIDTH - kind.size()) / 2, 0),
+        }
+    }
+
+    /// Get the cells of the piece in the well
+    pub fn cells(&self) -> impl Iterator<Item = Cell> + '_ {
+        let (x, y) = self.position;
+        self.cells.iter().map(move |(dx, dy)| (x + dx, y + dy))
+    }
+
+    /// Get the cells of the piece in its box (for the preview of the next piece)
+    pub fn shape(kind: Kind) -> [Cell; 4] {
+        kind.cells()
+    }
+
+    /// Get the piece moved by the given offset
+    fn moved(&self, dx: i32, dy: i32) -> Self {
+        Piece {
+            position: (self.position.0 + dx, self.position.1 + dy),
+            ..self.clone()
+        }
+    }
+
+    /// Get the piece rotated clockwise, in its box
+    fn rotated(&self) -> Self {
+        let size = self.kind.size();
+        let mut cells = self.cells;
+        for cell in cells.iter_mut() {
+            *cell = (size - 1 - cell.1, cell.0);
+        }
+        Piece {
+            cells,
+            ..self.clone()
+        }
+    }
+}
+
+/// A game of falling blocks: complete the lines of the well to clear them.
+///
+/// The game only moves on [Game::step] and the player moves, its pieces are picked by a
+/// seeded generator, so a game can be replayed.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Game {
+    /// The cells of the well, by row (true if filled)
+    well: Vec<[bool; WIDTH as usize]>,
+    piece: Piece,
+    next: Kind,
+    rng: Rng,
+    score: u32,
+    lines: u32,
+    /// The rows being cleared (shown before they are removed)
+    clearing: Vec<i32>,
+    over: bool,
+}
+
+impl Game {
+    /// Start a game, the pieces picked from the given seed
+    pub fn new(seed: u64) -> Self {
+        let mut rng = Rng::new(seed);
+        let piece = Piece::new(Kind::random(&mut rng));
+        let next = Kind::random(&mut rng);
+        Game {
+            well: vec![[false; WIDTH as usize]; HEIGHT as usize],
+            piece,
+            next,
+            rng,
+            score: 0,
+            lines: 0,
+            clearing: Vec::new(),
+            over: false,
+        }
+    }
+
+    /// Move the piece to the left (if possible)
+    pub fn move_left(&mut self) {
+        self.try_move(self.piece.moved(-1, 0));
+    }
+
+    /// Move the piece to the right (if possible)
+    pub fn move_right(&mut self) {
+        self.try_move(self.piece.moved(1, 0));
+    }
+
+    /// Rotate the piece clockwise, pushing it away from the walls if needed
+    pub fn rotate(&mut self) {
+        let rotated = self.piece.rotated();
+        for dx in [0, -1, 1, -2, 2].iter() {
+            if self.try_move(rotated.moved(*dx, 0)) {
+                return;
+            }
+        }
+    }
+
+    /// Drop the piece at once (2 points by row)
+    pub fn drop(&mut self) {
+        if !self.is_playing() {
+            return;
+        }
+        while self.try_move(self.piece.moved(0, 1)) {
+            self.score += 2;
+        }
+        self.lock();
+    }
+
+    /// Make the piece fall by one row, or end the clearing of the lines
+    pub fn step(&mut self) {
+        if self.over {
+            return;
+        }
+        if !self.clearing.is_empty() {
+            for row in self.clearing.drain(..) {
+                self.well.remove(row as usize);
+                self.well.insert(0, [false; WIDTH as usize]);
+            }
+            self.spawn();
+        } else if !self.try_move(self.piece.moved(0, 1)) {
+            self.lock();
+        }
+    }
+
+    /// Get the delay before the next step: shorter at each level
+    pub fn period(&self) -> Duration {
+        if !self.clearing.is_empty() {
+            return CLEAR_DELAY;
+        }
+        let speedup = LEVEL_SPEEDUP * self.get_level() as u64;
+        Duration::from_millis(START_PERIOD.saturating_sub(speedup).max(MIN_PERIOD))
+    }
+
+    /// Check if a cell of the well is filled
+    pub fn is_filled(&self, (x, y): Cell) -> bool {
+        self.well[y as usize][x as usize]
+    }
+
+    /// Get the falling piece (None while clearing the lines or once over)
+    pub fn get_piece(&self) -> Option<&Piece> {
+        if self.is_playing() {
+            Some(&self.piece)
+        } else {
+            None
+        }
+    }
+
+    pub fn get_next(&self) -> Kind {
+        self.next
+    }
+
+    /// Get the rows being cleared
+    pub fn get_clearing(&self) -> &[i32] {
+        &self.clearing
+    }
+
+    pub fn get_score(&self) -> u32 {
+        self.score
+    }
+
+    pub fn get_lines(&self) -> u32 {
+        self.lines
+    }
+
+    /// Get the level, from 0 (one more each 10 lines)
+    pub fn get_level(&self) -> u32 {
+        self.lines / LEVEL_LINES
+    }
+
+    pub fn is_over(&self) -> bool {
+        self.over
+    }
+
+    /// Check if the piece can be moved
+    fn is_playing(&self) -> bool {
+        !self.over && self.clearing.is_empty()
+    }
+
+    /// Check if a piece fits in the well
+    fn fits(&self, piece: &Piece) -> bool {
+        piece.cells().all(|(x, y)| {
+            (0..WIDTH).contains(&x) && (0..HEIGHT).contains(&y) && !self.is_filled((x, y))
+        })
+    }
+
+    /// Move the piece if it fits, telling if moved
+    fn try_move(&mut self, piece: Piece) -> bool {
+        if self.is_playing() && self.fits(&piece) {
+            self.piece = piece;
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Lock the piece in the well, then clear the full lines (or spawn the next piece)
+    fn lock(&mut self) {
+        for (x, y) in self.piece.cells() {
+            self.well[y as usize][x as usize] = true;
+        }
+        self.clearing = (0..HEIGHT)
+            .filter(|row| self.well[*row as usize].iter().all(|filled| *filled))
+            .collect();
+        if self.clearing.is_empty() {
+            self.spawn();
+        } else {
+            let cleared = self.clearing.len();
+            self.score += LINE_POINTS[cleared - 1] * (self.get_level() + 1);
+            self.lines += cleared as u32;
+        }
+    }
+
+    /// Put the next piece on top of the well (the game is over if it does not fit)
+    fn spawn(&mut self) {
+        self.piece = Piece::new(self.next);
+        self.next = Kind::random(&mut self.rng);
+        if !self.fits(&self.piece) {
+            self.over = true;
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::app::tetris::game::{Game, Kind, Piece, HEIGHT, WIDTH};
+    use std::time::Duration;
+
+    /// Get a game with the given falling piece
+    fn with_piece(kind: Kind) -> Game {
+        let mut game = Game::new(1);
+        game.piece = Piece::new(kind);
+        game
+    }
+
+    /// The pieces rotate in their box, and stay in the well.
+    #[test]
+    fn rotate() {
+        let mut game = with_piece(Kind::I);
+        game.rotate();
+        let cells: Vec<_> = game.get_piece().unwrap().cells().collect();
+        assert_eq!(cells, vec![(5, 0), (5, 1), (5, 2), (5, 3)]);
+        // Pushed away from the wall
+        for _ in 0..WIDTH {
+            game.move_right();
+        }
+        game.rotate();
+        let cells: Vec<_> = game.get_piece().unwrap().cells().collect();
+        assert!(cells.iter().all(|(x, _)| *x < WIDTH));
+        assert_eq!(cells.len(), 4);
+        // A square never moves when rotated
+        let mut game = with_piece(Kind::O);
+        let mut before: Vec<_> = game.piece.cells().collect();
+        game.rotate();
+        let mut after: Vec<_> = game.piece.cells().collect();
+        before.sort_unstable();
+        after.sort_unstable();
+        assert_eq!(after, before);
+    }
+
+    /// The full lines are shown, then cleared and scored.
+    #[test]
+    fn clear_lines() {
+        let mut game = with_piece(Kind::I);
+        // Two bottom lines full but the last column
+        for row in [HEIGHT - 1, HEIGHT - 2].iter() {
+            for column in 0..WIDTH - 1 {
+                game.well[*row as usize][column as usize] = true;
+            }
+        }
+        game.rotate();
+        for _ in 0..WIDTH {
+            game.move_right();
+        }
+        game.drop();
+        assert_eq!(game.get_clearing(), &[HEIGHT - 2, HEIGHT - 1]);
+        assert!(game.get_piece().is_none());
+        assert_eq!(game.period(), Duration::from_millis(300));
+        assert_eq!(game.get_lines(), 2);
+        assert_eq!(game.get_score(), 2 * (HEIGHT as u32 - 4) + 100);
+        game.step();
+        assert!(game.get_clearing().is_empty());
+        // The rest of the piece fell down
+        assert!((0..WIDTH - 1).all(|column| !game.is_filled((column, HEIGHT - 1))));
+        assert!(game.is_filled((WIDTH - 1, HEIGHT - 1)));
+        assert_eq!(game.period(), Duration::from_millis(800));
+    }
+
+    /// The game is over when a piece can't enter the well.
+    #[test]
+    fn game_over() {
+        let mut game = Game::new(7);
+        let mut drops = 0;
+        while !game.is_over() {
+            game.drop();
+            drops += 1;
+        }
+        assert!(drops < HEIGHT);
+        game.step();
+        game.move_left();
+        assert!(game.is_over() && game.get_piece().is_none());
+    }
+
+    /// A game is replayed from its seed and moves.
+    #[test]
+    fn replay() {
+        let play = || {
+            let mut game = Game::new(42);
+            for i in 0..500 {
+                match i % 5 {
+                    0 => game.move_left(),
+                    1 => game.rotate(),
+                    2 => game.move_right(),
+                    _ => game.step(),
+                }
+            }
+            game
+        };
+        assert_eq!(play(), play());
+    }
+}
@@ -0,0 +1,336 @@
+use crate::app::App;
+use crate::app::Application;
+use crate::component::{Button, ButtonBar};
+use crate::display::G13Display;
+use crate::error::AppError;
+use crate::random::Rng;
+use crate::score::HighScores;
+use crate::style::{BORDER_1, FILL_OFF, FILL_ON, TEXT_LIGHT, TEXT_SMALL, TITLE_BOLD};
+use async_trait::async_trait;
+use embedded_graphics::egrectangle;
+use embedded_graphics::fonts::Text;
+use embedded_graphics::pixelcolor::BinaryColor;
+use embedded_graphics::prelude::*;
+use game::{Cell, Game, Piece, HEIGHT, WIDTH};
+use log::{error, info};
+use std::marker::Unpin;
+use std::time::{Duration, Instant};
+use tokio::io::AsyncWrite;
+use tokio::time;
+
+mod game;
+
+/// The name of the game, for its high scores
+const GAME: &str = "tetris";
+/// The size of a cell of the well (in pixels)
+const CELL: i32 = 4;
+/// The left of the panel, right of the well
+const PANEL: i32 = 2 + HEIGHT * CELL + 4;
+/// The interval between two checks of the game clock
+const TICK: Duration = Duration::from_millis(20);
+/// The interval between two flashes of the cleared lines
+const FLASH: u128 = 75;
+/// The most late falls caught up at once, the others are skipped
+/// (the game is stopped while covered by another screen)
+const CATCH_UP: u32 = 2;
+
+/// The falling blocks game, its well lying on the side to fit the screen:
+/// the pieces fall from the right to the left.
+///
+/// L1 and L2 move the piece up and down, L3 rotates it and L4 drops it.
+#[derive(Debug, Default)]
+pub struct Tetris {
+    /// Define if the app should end/return
+    end: bool,
+    /// The running game (None until started)
+    game: Option<Game>,
+    /// The time of the last fall of the piece
+    last_step: Option<Instant>,
+    /// The high scores (loaded on first execution)
+    scores: Option<HighScores>,
+    /// The rank of the score of the ended game, once recorded (None if not kept)
+    rank: Option<Option<usize>>,
+}
+
+impl Tetris {
+    /// Start a new game
+    fn new_game(&mut self) {
+        self.game = Some(Game::new(Rng::from_time().next_u64()));
+        self.last_step = None;
+        self.rank = None;
+    }
+
+    /// Play a move on the running game
+    fn play<F: FnOnce(&mut Game)>(&mut self, play: F) -> Result<(), AppError> {
+        if let Some(game) = self.game.as_mut() {
+            play(game);
+        }
+        Ok(())
+    }
+
+    /// Record the score of the ended game
+    fn record(&mut self) {
+        let (game, scores) = match (self.game.as_ref(), self.scores.as_mut()) {
+            (Some(game), Some(scores)) if game.is_over() && self.rank.is_none() => (game, scores),
+            _ => return,
+        };
+        let rank = scores.add(game.get_score());
+        self.rank = Some(rank);
+        if let Some(rank) = rank {
+            info!(
+                "New tetris high score: {} (#{})",
+                game.get_score(),
+                rank + 1
+            );
+            if let Err(err) = scores.save() {
+                error!("Can't save the tetris scores: {}", err);
+            }
+        }
+    }
+}
+
+#[async_trait(?Send)]
+impl Application for Tetris {
+    async fn execute<W: Unpin + AsyncWrite>(&mut self, out: &mut W) -> Result<App, AppError>
+    where
+        W: AsyncWrite + Unpin,
+    {
+        let mut interval = time::interval(TICK);
+        let mut display = G13Display::new(out);
+        if self.game.is_none() {
+            self.new_game();
+        }
+        let best = self
+            .scores
+            .get_or_insert_with(|| HighScores::load(GAME))
+            .best();
+
+        // Draw at once (the key pressed may have changed the game)
+        let mut changed = true;
+        let start = Instant::now();
+        #[warn(clippy::while_immutable_condition)]
+        while !self.end {
+            let game = match self.game.as_mut() {
+                Some(game) => game,
+                None => break,
+            };
+            // Fall at the rate of the level, catching up the late falls
+            let now = Instant::now();
+            let last_step = self.last_step.get_or_insert(now);
+            if now.duration_since(*last_step) > game.period() * CATCH_UP {
+                *last_step = now - game.period() * CATCH_UP;
+            }
+            while !game.is_over() && now.duration_since(*last_step) >= game.period() {
+                *last_step += game.period();
+                game.step();
+                changed = true;
+            }
+
+            let over = game.is_over();
+            let clearing = !game.get_clearing().is_empty();
+            if changed || clearing {
+                changed = false;
+                let flash = ((now - start).as_millis() / FLASH).is_multiple_of(2);
+                make_well(game, flash).into_iter().draw(&mut display)?;
+                make_panel(game, best).into_iter().draw(&mut display)?;
+                if over {
+                    // The high scores take the place of the panel
+                    let rank = self.rank.flatten();
+                    let table = self
+                        .scores
+                        .as_ref()
+                        .and_then(|scores| make_table(scores, rank));
+                    if let Some(table) = table {
+                        egrectangle!(
+                            top_left = Point::new(PANEL, 0),
+                            bottom_right = Point::new(159, 42),
+                            style = *FILL_OFF,
+                        )
+                        .draw(&mut display)?;
+                        for (i, row) in table.iter().enumerate() {
+                            Text::new(row, Point::new(PANEL, 1 + 7 * i as i32))
+                                .into_styled(*TEXT_SMALL)
+                                .draw(&mut display)?;
+                        }
+                    }
+                    Text::new("GAME OVER", Point::new(6, 12))
+                        .into_styled(*TITLE_BOLD)
+                        .draw(&mut display)?;
+                    if let Some(Some(rank)) = self.rank {
+                        let text = format!("High score #{}", rank + 1);
+                        Text::new(&text, Point::new(6, 22))
+                            .into_styled(*TEXT_LIGHT)
+                            .draw(&mut display)?;
+                    }
+                    let mut buttonbar: ButtonBar = Default::default();
+                    buttonbar.set_button1(Some(Button::from_str("Again")));
+                    buttonbar.draw(&mut display)?;
+                }
+                display.flush().await?;
+            }
+            if over && self.rank.is_none() {
+                self.record();
+                changed = true;
+            }
+            interval.tick().await;
+        }
+
+        Ok(App::Menu(Default::default()))
+    }
+
+    /// Represents the up button (or a new game, once over)
+    async fn button_l1(&mut self) -> Result<(), AppError> {
+        if self.game.as_ref().is_some_and(Game::is_over) {
+            self.new_game();
+            return Ok(());
+        }
+        self.play(Game::move_left)
+    }
+
+    /// Represents the down button
+    async fn button_l2(&mut self) -> Result<(), AppError> {
+        self.play(Game::move_right)
+    }
+
+    /// Represents the rotate button
+    async fn button_l3(&mut self) -> Result<(), AppError> {
+        self.play(Game::rotate)
+    }
+
+    /// Represents the drop button
+    async fn button_l4(&mut self) -> Result<(), AppError> {
+        self.play(Game::drop)
+    }
+
+    /// Represents the return button (go to menu)
+    async fn button_bd(&mut self) -> Result<(), AppError> {
+        self.end = true;
+        Ok(())
+    }
+}
+
+/// Get the top left pixel of a cell of the well: its top on the right, its columns from the top
+fn cell_point((column, row): Cell) -> Point {
+    Point::new(1 + (HEIGHT - 1 - row) * CELL, 1 + column * CELL)
+}
+
+/// Make a drawable iterator of the well: the filled cells and the falling piece.
+/// The cleared lines flash.
+fn make_well(game: &Game, flash: bool) -> Vec<Pixel<BinaryColor>> {
+    let mut pixels: Vec<Pixel<BinaryColor>> = Vec::new();
+    pixels.extend(&egrectangle!(
+        top_left = Point::zero(),
+        bottom_right = Point::new(1 + HEIGHT * CELL, 1 + WIDTH * CELL),
+        style = *BORDER_1,
+    ));
+    let piece: Vec<Cell> = game
+        .get_piece()
+        .map(|piece| piece.cells().collect())
+        .unwrap_or_default();
+    for row in 0..HEIGHT {
+        let cleared = game.get_clearing().contains(&row);
+        for column in 0..WIDTH {
+            let cell = (column, row);
+            let style = if cleared {
+                if flash {
+                    *FILL_ON
+                } else {
+                    *BORDER_1
+                }
+            } else if game.is_filled(cell) || piece.contains(&cell) {
+                *FILL_ON
+            } else {
+                continue;
+            };
+            let point = cell_point(cell);
+            pixels.extend(&egrectangle!(
+                top_left = point,
+                bottom_right = point + Point::new(CELL - 2, CELL - 2),
+                style = style,
+            ));
+        }
+    }
+    pixels
+}
+
+/// Make a drawable iterator of the panel: the score, the level, the lines and the next piece
+fn make_panel(game: &Game, best: u32) -> Vec<Pixel<BinaryColor>> {
+    let mut pixels: Vec<Pixel<BinaryColor>> = Vec::new();
+    pixels.extend(&egrectangle!(
+        top_left = Point::new(PANEL, 0),
+        bottom_right = Point::new(159, 42),
+        style = *FILL_OFF,
+    ));
+    let rows = [
+        format!("Score {:>5}", game.get_score()),
+        format!("Level {:>5}", game.get_level() + 1),
+        format!("Lines {:>5}", game.get_lines()),
+        format!("Best  {:>5}", best.max(game.get_score())),
+    ];
+    for (i, row) in rows.iter().enumerate() {
+        pixels
+            .extend(&Text::new(row, Point::new(PANEL, 1 + 8 * i as i32)).into_styled(*TEXT_SMALL));
+    }
+    pixels.extend(&Text::new("Next", Point::new(PANEL, 34)).into_styled(*TEXT_SMALL));
+    // The next piece, on its side too, in small cells
+    for (column, row) in Piece::shape(game.get_next()).iter() {
+        let point = Point::new(PANEL + 40 - 3 * row, 32 + 3 * column);
+        pixels.extend(&egrectangle!(
+            top_left = point,
+            bottom_right = point + Point::new(1, 1),
+            style = *FILL_ON,
+        ));
+    }
+    pixels
+}
+
+/// Make the rows of the high scores table, the score of the ended game marked by its rank
+/// (None if not played yet)
+fn make_table(scores: &HighScores, rank: Option<usize>) -> Option<Vec<String>> {
+    if scores.get_scores().is_empty() {
+        return None;
+    }
+    let mut rows = vec!["High scores".to_owned()];
+    rows.extend(scores.get_scores().iter().enumerate().map(|(i, score)| {
+        let mark = if rank == Some(i) { '>' } else { ' ' };
+        format!("{}{} {:>9}", mark, i + 1, score)
+    }));
+    Some(rows)
+}
+
+#[cfg(test)]
+mod test {
+    use crate::app::tetris::game::{Game, HEIGHT, WIDTH};
+    use crate::app::tetris::{cell_point, make_table, make_well};
+    use crate::score::HighScores;
+    use embedded_graphics::pixelcolor::BinaryColor;
+    use embedded_graphics::prelude::*;
+
+    /// The well lies on its side: its top on the right, its bottom on the left.
+    #[test]
+    fn well_on_side() {
+        assert_eq!(cell_point((0, HEIGHT - 1)), Point::new(1, 1));
+        assert_eq!(cell_point((WIDTH - 1, 0)), Point::new(77, 37));
+        let game = Game::new(5);
+        let well = make_well(&game, false);
+        assert!(well.iter().all(|pixel| pixel.0.x <= 81 && pixel.0.y <= 41));
+        // The border and the falling piece
+        let lit = well
+            .iter()
+            .filter(|pixel| pixel.1 == BinaryColor::On)
+            .count();
+        assert_eq!(lit, 2 * 82 + 2 * 40 + 4 * 9);
+    }
+
+    /// The high scores are listed once played, the new one marked.
+    #[test]
+    fn table() {
+        let mut scores = HighScores::default();
+        assert_eq!(make_table(&scores, None), None);
+        scores.add(1200);
+        let rank = scores.add(4000);
+        let table = make_table(&scores, rank).unwrap();
+        assert_eq!(table, vec!["High scores", ">1      4000", " 2      1200"]);
+        assert!(table.iter().all(|row| row.len() <= 12));
+    }
+}
@@ -0,0 +1,261 @@
+use crate::app::App;
+use crate::app::Application;
+use crate::component::{BigText, Button, ButtonBar};
+use crate::countdown::{self, Countdown, Phase};
+use crate::display::G13Display;
+use crate::error::AppError;
+use crate::style::{BORDER_1, FILL_OFF, FILL_ON, TEXT_LIGHT, TITLE_BOLD};
+use crate::time::{MonotonicSource, SystemTime, TimeSource};
+use async_trait::async_trait;
+use embedded_graphics::egrectangle;
+use embedded_graphics::{fonts::Text, prelude::*};
+use std::marker::Unpin;
+use std::time::Duration;
+use tokio::io::AsyncWrite;
+use tokio::time;
+
+/// The countdown app, for pomodoros and other timers.
+///
+/// The countdown keeps running in background: when it ends,
+/// this app takes over the foreground app (restored afterwards) and flashes.
+#[derive(Debug, Default)]
+pub struct Timer {
+    /// Define if the app should end/return
+    end: bool,
+    /// The app in foreground when the countdown ended
+    previous: Option<Box<App>>,
+}
+
+impl Timer {
+    /// Create the timer app, over the given app
+    pub fn over(previous: App) -> Self {
+        Self {
+            end: false,
+            previous: Some(Box::new(previous)),
+        }
+    }
+
+    /// Get the app covered, restored afterwards
+    pub fn get_previous(&mut self) -> Option<&mut App> {
+        self.previous.as_deref_mut()
+    }
+}
+
+/// Format a remaining time as "MM:SS" (or "H:MM:SS" after an hour), rounded up to the second
+fn format_remaining(remaining: Duration) -> String {
+    let mut seconds = remaining.as_secs();
+    if remaining.subsec_nanos() > 0 {
+        seconds += 1;
+    }
+    let (hours, minutes, seconds) = (seconds / 3600, seconds / 60 % 60, seconds % 60);
+    if hours > 0 {
+        format!("{}:{:02}:{:02}", hours, minutes, seconds)
+    } else {
+        format!("{:02}:{:02}", minutes, seconds)
+    }
+}
+
+/// Get the labels of the buttons, depending on the state of the countdown
+fn labels<T: MonotonicSource>(countdown: &Countdown<T>) -> [Option<&'static str>; 4] {
+    if countdown.is_finished() {
+        let next = match (countdown.get_phase(), countdown.get_preset().rest) {
+            (Phase::Work, Some(_)) => "Rest",
+            _ => "Work",
+        };
+        [Some(next), Some("Stop"), None, None]
+    } else {
+        let start = if countdown.is_running() {
+            "Pause"
+        } else {
+            "Start"
+        };
+        let stop = if countdown.is_idle() { "Next" } else { "Stop" };
+        [Some(start), Some(stop), Some("+1"), Some("-1")]
+    }
+}
+
+#[async_trait(?Send)]
+impl Application for Timer {
+    async fn execute<W: Unpin + AsyncWrite>(&mut self, out: &mut W) -> Result<App, AppError>
+    where
+        W: AsyncWrite + Unpin,
+    {
+        let mut interval = time::interval(Duration::from_millis(500));
+        let mut display = G13Display::new(out);
+
+        let mut shown_labels = None;
+        let mut inverted = false;
+        #[warn(clippy::while_immutable_condition)]
+        while !self.end {
+            countdown::check(&SystemTime);
+            let today = SystemTime.now().date_naive();
+            let (title, cycles, remaining, duration, finished, labels) = {
+                let countdown = countdown::countdown();
+                let preset = countdown.get_preset();
+                let title = match (preset.rest, countdown.get_phase()) {
+                    (None, _) => preset.name.clone(),
+                    (Some(_), Phase::Work) => format!("{}: work", preset.name),
+                    (Some(_), Phase::Rest) => format!("{}: rest", preset.name),
+                };
+                (
+                    title,
+                    countdown.get_cycles(today),
+                    countdown.remaining(),
+                    countdown.get_duration(),
+                    countdown.is_finished(),
+                    labels(&countdown),
+                )
+            };
+
+            // Draw the button info, when it changes
+            if shown_labels != Some(labels) {
+                shown_labels = Some(labels);
+                let [l1, l2, l3, l4] = labels;
+                egrectangle!(
+                    top_left = Point::new(0, 35),
+                    bottom_right = Point::new(159, 42),
+                    style = *FILL_OFF,
+                )
+                .draw(&mut display)?;
+                let mut buttonbar: ButtonBar = Default::default();
+                buttonbar.set_button1(l1.map(Button::from_str));
+                buttonbar.set_button2(l2.map(Button::from_str));
+                buttonbar.set_button3(l3.map(Button::from_str));
+                buttonbar.set_button4(l4.map(Button::from_str));
+                buttonbar.draw(&mut display)?;
+            }
+
+            // Flash when the countdown is over
+            inverted = finished && !inverted;
+            if inverted {
+                egrectangle!(
+                    top_left = Point::zero(),
+                    bottom_right = Point::new(159, 34),
+                    style = *FILL_ON,
+                )
+                .draw(&mut display)?;
+                Text::new("Time's up!", Point::new(40, 13))
+                    .into_styled(*TITLE_BOLD)
+                    .draw(&mut display)?;
+            } else {
+                egrectangle!(
+                    top_left = Point::zero(),
+                    bottom_right = Point::new(159, 34),
+                    style = *FILL_OFF,
+                )
+                .draw(&mut display)?;
+                let cycles = format!("{} today", cycles);
+                Text::new(&title, Point::new(1, 0))
+                    .into_styled(*TEXT_LIGHT)
+                    .draw(&mut display)?;
+                Text::new(&cycles, Point::new(160 - 6 * cycles.len() as i32, 0))
+                    .into_styled(*TEXT_LIGHT)
+                    .draw(&mut display)?;
+
+                // Draw the remaining time in large digits, centered
+                let text = format_remaining(remaining);
+                let width = text.len() as i32 * 16;
+                BigText::new(text, Point::new((160 - width) / 2, 9), 2).draw(&mut display)?;
+
+                // Draw the progress bar, shrinking with the remaining time
+                egrectangle!(
+                    top_left = Point::new(0, 27),
+                    bottom_right = Point::new(159, 32),
+                    style = *BORDER_1,
+                )
+                .draw(&mut display)?;
+                let full = 155.0 * remaining.as_secs_f64() / duration.as_secs_f64().max(1.0);
+                if full >= 1.0 {
+                    egrectangle!(
+                        top_left = Point::new(2, 29),
+                        bottom_right = Point::new(1 + full as i32, 30),
+                        style = *FILL_ON,
+                    )
+                    .draw(&mut display)?;
+                }
+            }
+
+            display.flush().await?;
+            interval.tick().await;
+        }
+
+        Ok(self
+            .previous
+            .take()
+            .map_or_else(|| App::Menu(Default::default()), |app| *app))
+    }
+
+    /// Represents the start/pause button (or start the next phase)
+    async fn button_l1(&mut self) -> Result<(), AppError> {
+        countdown::countdown().toggle();
+        Ok(())
+    }
+
+    /// Represents the next preset button (or stop the countdown)
+    async fn button_l2(&mut self) -> Result<(), AppError> {
+        let mut countdown = countdown::countdown();
+        if countdown.is_idle() {
+            countdown.next_preset();
+        } else {
+            countdown.stop();
+        }
+        Ok(())
+    }
+
+    /// Represents the add a minute button
+    async fn button_l3(&mut self) -> Result<(), AppError> {
+        let mut countdown = countdown::countdown();
+        if !countdown.is_finished() {
+            countdown.adjust(1);
+        }
+        Ok(())
+    }
+
+    /// Represents the remove a minute button
+    async fn button_l4(&mut self) -> Result<(), AppError> {
+        let mut countdown = countdown::countdown();
+        if !countdown.is_finished() {
+            countdown.adjust(-1);
+        }
+        Ok(())
+    }
+
+    /// Represents the return button (go back, the countdown keeps running)
+    async fn button_bd(&mut self) -> Result<(), AppError> {
+        self.end = true;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::app::timer::{format_remaining, labels};
+    use crate::countdown::{Countdown, TimerConfig};
+    use crate::time::ManualTime;
+    use std::time::Duration;
+
+    /// The remaining time is rounded up, it shows 00:00 only when over.
+    #[test]
+    fn remaining_format() {
+        assert_eq!(format_remaining(Duration::from_millis(1_500)), "00:02");
+        assert_eq!(format_remaining(Duration::from_secs(25 * 60)), "25:00");
+        assert_eq!(format_remaining(Duration::from_secs(3_600)), "1:00:00");
+        assert_eq!(format_remaining(Duration::default()), "00:00");
+    }
+
+    /// The buttons follow the state of the countdown.
+    #[test]
+    fn button_labels() {
+        let clock = ManualTime::new();
+        let mut countdown = Countdown::new(clock.clone(), TimerConfig::default().presets);
+        assert_eq!(
+            labels(&countdown),
+            [Some("Start"), Some("Next"), Some("+1"), Some("-1")]
+        );
+        countdown.toggle();
+        assert_eq!(labels(&countdown)[..2], [Some("Pause"), Some("Stop")]);
+        clock.advance(Duration::from_secs(25 * 60));
+        countdown.check(chrono::NaiveDate::from_ymd_opt(2020, 7, 14).unwrap());
+        assert_eq!(labels(&countdown), [Some("Rest"), Some("Stop"), None, None]);
+    }
+}
@@ -0,0 +1,219 @@
+use embedded_graphics::drawable::Pixel;
+use embedded_graphics::geometry::Point;
+use embedded_graphics::pixelcolor::BinaryColor;
+use image::imageops::FilterType;
+use image::{DynamicImage, GrayImage, ImageResult};
+use std::path::Path;
+use strum_macros::{EnumString, EnumVariantNames, IntoStaticStr};
+
+/// The 4x4 Bayer matrix used by the ordered dithering
+const BAYER_4X4: [[u8; 4]; 4] = [[0, 8, 2, 10], [12, 4, 14, 6], [3, 11, 1, 9], [15, 7, 13, 5]];
+
+/// The dithering algorithms available to convert a picture into a 1-bit bitmap
+#[derive(Clone, Copy, Debug, PartialEq, EnumString, EnumVariantNames, IntoStaticStr)]
+#[strum(serialize_all = "snake_case")]
+pub enum Dithering {
+    /// Error diffusion, best looking for photos (like album arts)
+    FloydSteinberg,
+    /// Bayer matrix, more regular patterns
+    Ordered,
+}
+
+/// A 1-bit picture, ready to be drawn on the G13 display.
+///
+/// Dark pixels of the source picture are On, light ones are Off.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Bitmap {
+    width: u32,
+    height: u32,
+    pixels: Vec<bool>,
+}
+
+impl Bitmap {
+    /// Load a local picture (PNG or JPEG) and convert it into a bitmap
+    /// fitting in a width x height box.
+    pub fn open<P: AsRef<Path>>(
+        path: P,
+        width: u32,
+        height: u32,
+        dithering: Dithering,
+    ) -> ImageResult<Self> {
+        let image = image::open(path)?;
+        Ok(Self::from_image(&image, width, height, dithering))
+    }
+
+    /// Convert a picture into a bitmap fitting in a width x height box.
+    ///
+    /// The aspect ratio is kept and the picture is centered in the box.
+    pub fn from_image(image: &DynamicImage, width: u32, height: u32, dithering: Dithering) -> Self {
+        let thumbnail = image.resize(width, height, FilterType::Triangle).to_luma8();
+        // Center the thumbnail in the box, the free space is left blank (white)
+        let (dx, dy) = (
+            (width - thumbnail.width()) / 2,
+            (height - thumbnail.height()) / 2,
+        );
+        let boxed = GrayImage::from_fn(width, height, |x, y| {
+            if x < dx || y < dy || x - dx >= thumbnail.width() || y - dy >= thumbnail.height() {
+                image::Luma([u8::MAX])
+            } else {
+                *thumbnail.get_pixel(x - dx, y - dy)
+            }
+        });
+        Self::from_luma(&boxed, dithering)
+    }
+
+    /// Convert a grayscale picture into a bitmap of the same size
+    pub fn from_luma(luma: &GrayImage, dithering: Dithering) -> Self {
+        let (width, height) = luma.dimensions();
+        let pixels = match dithering {
+            Dithering::FloydSteinberg => floyd_steinberg(luma),
+            Dithering::Ordered => ordered(luma),
+        };
+        Self {
+            width,
+            height,
+            pixels,
+        }
+    }
+
+    /// Get whether the pixel at (x, y) is On.
+    /// Pixels out of the bitmap are Off.
+    pub fn get(&self, x: u32, y: u32) -> bool {
+        x < self.width && y < self.height && self.pixels[(y * self.width + x) as usize]
+    }
+
+    /// Get the pixels of the bitmap, its top left corner placed at the given point
+    pub fn pixels(&self, top_left: Point) -> Vec<Pixel<BinaryColor>> {
+        (0..self.height)
+            .flat_map(|y| (0..self.width).map(move |x| (x, y)))
+            .map(|(x, y)| {
+                let color = if self.get(x, y) {
+                    BinaryColor::On
+                } else {
+                    BinaryColor::Off
+                };
+                Pixel(top_left + Point::new(x as i32, y as i32), color)
+            })
+            .collect()
+    }
+}
+
+/// Floyd-Steinberg dithering: the quantization error of each pixel is spread
+/// over its right and bottom neighbours.
+fn floyd_steinberg(luma: &GrayImage) -> Vec<bool> {
+    let (width, height) = (luma.width() as usize, luma.height() as usize);
+    let mut levels: Vec<f32> = luma.pixels().map(|p| p.0[0] as f32).collect();
+    let mut pixels = vec![false; width * height];
+    for y in 0..height {
+        for x in 0..width {
+            let index = y * width + x;
+            let old = levels[index];
+            let on = old < 128.0;
+            pixels[index] = on;
+            let error = old - if on { 0.0 } else { 255.0 };
+            // Spread the error: 7/16 right, 3/16 bottom left, 5/16 bottom, 1/16 bottom right
+            if x + 1 < width {
+                levels[index + 1] += error * 7.0 / 16.0;
+            }
+            if y + 1 < height {
+                if x > 0 {
+                    levels[index + width - 1] += error * 3.0 / 16.0;
+                }
+                levels[index + width] += error * 5.0 / 16.0;
+                if x + 1 < width {
+                    levels[index + width + 1] += error / 16.0;
+                }
+            }
+        }
+    }
+    pixels
+}
+
+/// Ordered dithering using a 4x4 Bayer matrix as threshold map
+fn ordered(luma: &GrayImage) -> Vec<bool> {
+    luma.enumerate_pixels()
+        .map(|(x, y, p)| {
+            let threshold = BAYER_4X4[(y % 4) as usize][(x % 4) as usize] as u32 * 16 + 8;
+            (p.0[0] as u32) < threshold
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use crate::bitmap::{Bitmap, Dithering};
+    use embedded_graphics::pixelcolor::BinaryColor;
+    use embedded_graphics::prelude::*;
+    use image::{GrayImage, Luma};
+    use strum::VariantNames;
+
+    /// Count the On pixels of the column x
+    fn column_count(bitmap: &Bitmap, x: u32) -> usize {
+        (0..bitmap.height).filter(|y| bitmap.get(x, *y)).count()
+    }
+
+    /// Plain black and white pictures must stay plain with all the algorithms of the settings.
+    #[test]
+    fn plain_colors() {
+        for name in Dithering::VARIANTS.iter() {
+            let dithering: Dithering = name.parse().expect("Should parse the setting");
+            let black = Bitmap::from_luma(&GrayImage::from_pixel(8, 8, Luma([0])), dithering);
+            let white = Bitmap::from_luma(&GrayImage::from_pixel(8, 8, Luma([255])), dithering);
+            assert!((0..8).all(|x| column_count(&black, x) == 8));
+            assert!((0..8).all(|x| column_count(&white, x) == 0));
+        }
+    }
+
+    /// A mid gray must light about half of the pixels.
+    #[test]
+    fn mid_gray() {
+        for dithering in [Dithering::FloydSteinberg, Dithering::Ordered].iter() {
+            let gray = Bitmap::from_luma(&GrayImage::from_pixel(16, 16, Luma([128])), *dithering);
+            let count: usize = (0..16).map(|x| column_count(&gray, x)).sum();
+            assert!(
+                (112..=144).contains(&count),
+                "{:?} gave {}",
+                dithering,
+                count
+            );
+        }
+    }
+
+    /// Load the gradient fixture: the left side is dark, the right side is light.
+    #[test]
+    fn load_png_gradient() {
+        let bitmap = Bitmap::open(
+            "tests/fixtures/gradient.png",
+            32,
+            32,
+            Dithering::FloydSteinberg,
+        )
+        .expect("Should load the fixture");
+        assert_eq!((bitmap.width, bitmap.height), (32, 32));
+        let left: usize = (0..8).map(|x| column_count(&bitmap, x)).sum();
+        let right: usize = (24..32).map(|x| column_count(&bitmap, x)).sum();
+        assert!(left > 200, "left side has only {} pixels on", left);
+        assert!(right < 56, "right side has {} pixels on", right);
+    }
+
+    /// Load the wide JPEG fixture: it is centered vertically with white borders.
+    #[test]
+    fn load_jpeg_centered() {
+        let bitmap = Bitmap::open("tests/fixtures/halves.jpg", 32, 32, Dithering::Ordered)
+            .expect("Should load the fixture");
+        // The picture is 2:1, so it uses 32x16 pixels from row 8 to 23
+        assert_eq!(column_count(&bitmap, 0), 16);
+        assert!(!bitmap.get(0, 7) && bitmap.get(0, 8) && bitmap.get(0, 23) && !bitmap.get(0, 24));
+        // The right half is white
+        assert_eq!(column_count(&bitmap, 31), 0);
+    }
+
+    /// The pixels are translated to the given position.
+    #[test]
+    fn pixels_position() {
+        let bitmap = Bitmap::from_luma(&GrayImage::from_pixel(2, 2, Luma([0])), Dithering::Ordered);
+        let pixels = bitmap.pixels(Point::new(10, 20));
+        assert_eq!(pixels.len(), 4);
+        assert!(pixels.contains(&Pixel(Point::new(11, 21), BinaryColor::On)));
+    }
+}
@@ -1,18 +1,22 @@
+use crate::component::Marquee;
 use crate::style::{FILL_ON, TITLE_BOLD};
 use embedded_graphics::drawable::Drawable;
+use embedded_graphics::egrectangle;
 use embedded_graphics::geometry::Point;
 use embedded_graphics::pixelcolor::BinaryColor;
 use embedded_graphics::prelude::*;
 use embedded_graphics::DrawTarget;
-use embedded_graphics::{egrectangle, egtext};
+use ibm437::Ibm437Font8x8Bold;
 
 /// An app bar component
 /// It's essentially a black bold title on a white background
+///
+/// A too long title scrolls when the app bar is ticked.
 #[derive(Clone, Debug)]
 pub struct AppBar {
     top_left: Point,
     bottom_right: Point,
-    title: String,
+    title: Marquee,
 }
 
 impl AppBar {
@@ -21,9 +25,19 @@ impl AppBar {
         Self {
             top_left,
             bottom_right,
-            title: title.into(),
+            title: Marquee::new(
+                title,
+                (bottom_right.x - top_left.x).max(0) as u32,
+                Ibm437Font8x8Bold,
+            ),
         }
     }
+
+    /// Scroll the title of one step (if it is too long).
+    /// Returns true if the app bar should be redrawn.
+    pub fn tick(&mut self) -> bool {
+        self.title.tick()
+    }
 }
 
 impl Drawable<BinaryColor> for AppBar {
@@ -46,19 +60,16 @@ impl IntoIterator for AppBar {
         .into_iter()
         .chain(
             // and chain with the black bold title (with an offset of 1;1)
-            egtext!(
-                text = &self.title,
-                top_left = Point::from((1, 1)) + self.top_left,
-                style = *TITLE_BOLD,
-            )
-            .into_iter()
-            // Filter to get only the pixel in the appbar container
-            .filter(|pixel| {
-                self.top_left.x <= pixel.0.x
-                    && pixel.0.x <= self.bottom_right.x
-                    && self.top_left.y <= pixel.0.y
-                    && pixel.0.y <= self.bottom_right.y
-            }),
+            self.title
+                .pixels(Point::from((1, 1)) + self.top_left, *TITLE_BOLD)
+                .into_iter()
+                // Filter to get only the pixel in the appbar container
+                .filter(|pixel| {
+                    self.top_left.x <= pixel.0.x
+                        && pixel.0.x <= self.bottom_right.x
+                        && self.top_left.y <= pixel.0.y
+                        && pixel.0.y <= self.bottom_right.y
+                }),
         )
         .collect::<Vec<_>>()
         .into_iter()
@@ -0,0 +1,79 @@
+use crate::style::{Glyphs, TEXT_BOLD};
+use embedded_graphics::drawable::{Drawable, Pixel};
+use embedded_graphics::fonts::Text;
+use embedded_graphics::geometry::Point;
+use embedded_graphics::pixelcolor::BinaryColor;
+use embedded_graphics::DrawTarget;
+use ibm437::Ibm437Font8x8Bold;
+
+/// A big text component, a bold text scaled up (each pixel becomes a square)
+///
+/// Mainly used for the large digits of the time apps.
+#[derive(Clone, Debug)]
+pub struct BigText {
+    text: String,
+    top_left: Point,
+    scale: u32,
+}
+
+impl BigText {
+    /// Create a big text from its text, its position and its scale.
+    /// The text is normalized to be drawn with the glyphs of the bold font.
+    pub fn new<T: Into<String>>(text: T, top_left: Point, scale: u32) -> Self {
+        Self {
+            text: Ibm437Font8x8Bold::normalize(&text.into()),
+            top_left,
+            scale: scale.max(1),
+        }
+    }
+}
+
+impl Drawable<BinaryColor> for BigText {
+    fn draw<D: DrawTarget<BinaryColor>>(self, display: &mut D) -> Result<(), D::Error> {
+        self.into_iter().draw(display)
+    }
+}
+
+impl IntoIterator for BigText {
+    type Item = Pixel<BinaryColor>;
+    type IntoIter = std::vec::IntoIter<Pixel<BinaryColor>>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        let scale = self.scale as i32;
+        let top_left = self.top_left;
+        Text::new(&self.text, Point::zero())
+            .into_styled(*TEXT_BOLD)
+            .into_iter()
+            // Each pixel of the text becomes a scale x scale square
+            .flat_map(|Pixel(point, color)| {
+                (0..scale * scale).map(move |i| {
+                    let offset =
+                        Point::new(point.x * scale + i % scale, point.y * scale + i / scale);
+                    Pixel(top_left + offset, color)
+                })
+            })
+            .collect::<Vec<_>>()
+            .into_iter()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::component::BigText;
+    use embedded_graphics::pixelcolor::BinaryColor;
+    use embedded_graphics::prelude::*;
+
+    /// The text is scaled from its top left corner.
+    #[test]
+    fn scaled_text() {
+        let pixels: Vec<_> = BigText::new("8", Point::new(10, 5), 2)
+            .into_iter()
+            .collect();
+        // A 8x8 glyph becomes 16x16
+        assert_eq!(pixels.len(), 16 * 16);
+        assert!(pixels
+            .iter()
+            .all(|p| (10..26).contains(&p.0.x) && (5..21).contains(&p.0.y)));
+        assert!(pixels.iter().any(|p| p.1 == BinaryColor::On));
+    }
+}
@@ -1,4 +1,4 @@
-use crate::style::TEXT_BOLD;
+use crate::style::{Glyphs, TEXT_BOLD};
 use embedded_graphics::drawable::Drawable;
 use embedded_graphics::drawable::Pixel;
 use embedded_graphics::fonts::Text;
@@ -8,6 +8,7 @@ use embedded_graphics::geometry::Size;
 use embedded_graphics::pixelcolor::BinaryColor;
 use embedded_graphics::transform::Transform;
 use embedded_graphics::DrawTarget;
+use ibm437::Ibm437Font8x8Bold;
 
 /// The constant height of a button, here 8px.
 const BUTTON_HEIGHT: u32 = 8;
@@ -43,7 +44,8 @@ impl Button {
 
     /// Create a text button with the given str
     pub fn from_str(text: &str) -> Button {
-        let comp = Text::new(text, Point::zero()).into_styled(*TEXT_BOLD);
+        let text = Ibm437Font8x8Bold::normalize(text);
+        let comp = Text::new(&text, Point::zero()).into_styled(*TEXT_BOLD);
         Button::from_drawable(&comp, comp.top_left(), comp.bottom_right())
     }
 }
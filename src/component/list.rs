@@ -1,23 +1,32 @@
+use crate::component::Marquee;
 use crate::error::DisplayError;
-use crate::style::TEXT_LIGHT;
+use crate::style::{Glyphs, TEXT_LIGHT};
 use embedded_graphics::drawable::Drawable;
 use embedded_graphics::drawable::Pixel;
-use embedded_graphics::fonts::Text;
+use embedded_graphics::fonts::{Font, Font6x8, Text};
 use embedded_graphics::geometry::Point;
 use embedded_graphics::pixelcolor::BinaryColor;
 use embedded_graphics::DrawTarget;
 
 /// A list component for g13 apps
+///
+/// The selected choice scrolls if it is too long to be shown.
 #[derive(Clone, Debug)]
 pub struct List {
     cursor: usize,
     content: Vec<String>,
+    /// The marquee of the selected choice, made on first draw
+    marquee: Option<Marquee>,
 }
 
 impl List {
     /// Init a new list component with a vec of choices
     pub fn new(content: Vec<String>) -> Self {
-        Self { cursor: 0, content }
+        Self {
+            cursor: 0,
+            content,
+            marquee: None,
+        }
     }
 
     /// Get the current cursor
@@ -28,6 +37,7 @@ impl List {
     /// Reset the cursor to 0
     pub fn reset(&mut self) {
         self.cursor = 0;
+        self.marquee = None;
     }
 
     /// Get the current choice selected
@@ -39,6 +49,7 @@ impl List {
     pub fn next(&mut self) {
         if self.cursor < self.content.len() - 1 {
             self.cursor += 1;
+            self.marquee = None;
         }
     }
 
@@ -46,12 +57,19 @@ impl List {
     pub fn previous(&mut self) {
         if self.cursor > 0 {
             self.cursor -= 1;
+            self.marquee = None;
         }
     }
 
+    /// Scroll the selected choice of one step (if it is too long).
+    /// Returns true if the list should be redrawn.
+    pub fn tick(&mut self) -> bool {
+        self.marquee.as_mut().is_some_and(|marquee| marquee.tick())
+    }
+
     /// Draw a list with its default size annd position
     /// Currently, it's (0,10) to (159,34)
-    pub fn draw_default<D>(&self, display: &mut D) -> Result<(), DisplayError>
+    pub fn draw_default<D>(&mut self, display: &mut D) -> Result<(), DisplayError>
     where
         D: DrawTarget<BinaryColor, Error = DisplayError>,
     {
@@ -60,7 +78,7 @@ impl List {
 
     /// Draw a list in the given border, defined by the points given of a rectangle.
     pub fn draw_within_border<D: DrawTarget<BinaryColor>>(
-        &self,
+        &mut self,
         display: &mut D,
         up_corner: Point,
         down_corner: Point,
@@ -69,6 +87,19 @@ impl List {
         let (left_offset, up_offset) = (up_corner.x, up_corner.y);
         let (right_offset, down_offset) = (down_corner.x, down_corner.y);
         let nshow: i32 = (down_offset - up_offset) / 8;
+        // The selected name is shown in a marquee, after the prefix
+        let char_width = Font6x8::CHARACTER_SIZE.width;
+        let name_offset = Point::new(left_offset + 1 + 2 * char_width as i32, up_offset + 8);
+        if let Some(name) = self
+            .content
+            .get(self.cursor)
+            .map(|name| Font6x8::normalize(name))
+        {
+            if self.marquee.as_ref().map(Marquee::get_text) != Some(name.as_str()) {
+                let width = (right_offset - name_offset.x + 1).max(0) as u32;
+                self.marquee = Some(Marquee::new(name, width, Font6x8));
+            }
+        }
         // For each visible choice
         for i in 0..nshow {
             // If cursor is 0, element -1 does not exist - just pass
@@ -77,21 +108,33 @@ impl List {
             }
             // Print the choice name
             if let Some(name) = self.content.get(self.cursor + (i as usize) - 1) {
-                let prefix = if i == 1 { ">" } else { " " }; // add the prefix if the choice is selected.
-                pixels.extend(
-                    Text::new(
-                        &format!("{} {}", prefix, name),
+                let choice = match (i, self.marquee.as_ref()) {
+                    // add the prefix if the choice is selected.
+                    (1, Some(marquee)) => {
+                        Text::new("> ", Point::new(left_offset + 1, up_offset + 8))
+                            .into_styled(*TEXT_LIGHT)
+                            .into_iter()
+                            .chain(marquee.pixels(name_offset, *TEXT_LIGHT))
+                            .collect()
+                    }
+                    _ => Text::new(
+                        &format!("  {}", Font6x8::normalize(name)),
                         Point::new(left_offset + 1, up_offset + 8 * i),
                     )
                     .into_styled(*TEXT_LIGHT)
                     .into_iter()
-                    // Remove the overflow
-                    .filter(|pixel| {
-                        left_offset <= pixel.0.x
-                            && pixel.0.x <= right_offset
-                            && up_offset <= pixel.0.y
-                            && pixel.0.y <= down_offset
-                    }),
+                    .collect::<Vec<_>>(),
+                };
+                pixels.extend(
+                    choice
+                        .into_iter()
+                        // Remove the overflow
+                        .filter(|pixel| {
+                            left_offset <= pixel.0.x
+                                && pixel.0.x <= right_offset
+                                && up_offset <= pixel.0.y
+                                && pixel.0.y <= down_offset
+                        }),
                 );
             }
         }
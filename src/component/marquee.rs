@@ -0,0 +1,141 @@
+use crate::style::Glyphs;
+use embedded_graphics::drawable::Pixel;
+use embedded_graphics::fonts::{Font, Text};
+use embedded_graphics::geometry::Point;
+use embedded_graphics::pixelcolor::BinaryColor;
+use embedded_graphics::style::TextStyle;
+
+/// The number of ticks the marquee waits at each end of the text
+const PAUSE_TICKS: u32 = 10;
+
+/// A marquee component, a single line of text which scrolls horizontally
+/// when it does not fit in its width.
+///
+/// The text waits a bit at its beginning, scrolls pixel by pixel (one per tick)
+/// to its end, waits again and goes back to its beginning.
+#[derive(Clone, Debug)]
+pub struct Marquee {
+    text: String,
+    /// The visible width (in pixels)
+    width: u32,
+    /// The width of the whole text (in pixels)
+    text_width: u32,
+    /// The current step in the scrolling cycle
+    step: u32,
+}
+
+impl Marquee {
+    /// Create a marquee from its text, its visible width (in pixels) and the font drawing it.
+    /// The text is normalized to be drawn with the glyphs of the font.
+    pub fn new<T: Into<String>, F: Font + Glyphs>(text: T, width: u32, _font: F) -> Self {
+        let text = F::normalize(&text.into());
+        Self {
+            text_width: text.chars().count() as u32 * F::CHARACTER_SIZE.width,
+            text,
+            width,
+            step: 0,
+        }
+    }
+
+    /// Get the (normalized) text of the marquee
+    pub fn get_text(&self) -> &str {
+        &self.text
+    }
+
+    /// Check if the text is too long for the marquee, and so if it scrolls
+    pub fn overflows(&self) -> bool {
+        self.text_width > self.width
+    }
+
+    /// Get the current scrolling offset (in pixels)
+    pub fn get_offset(&self) -> u32 {
+        let max = self.text_width.saturating_sub(self.width);
+        self.step.saturating_sub(PAUSE_TICKS).min(max)
+    }
+
+    /// Move the text of one step.
+    /// Returns true if the marquee has moved and should be redrawn.
+    pub fn tick(&mut self) -> bool {
+        if !self.overflows() {
+            return false;
+        }
+        let last = self.get_offset();
+        // The cycle: pause, scroll to the end, pause and back to the beginning
+        let cycle = 2 * PAUSE_TICKS + (self.text_width - self.width) + 1;
+        self.step = (self.step + 1) % cycle;
+        last != self.get_offset()
+    }
+
+    /// Get the visible pixels of the marquee, drawn with the given style
+    pub fn pixels<F: Font + Copy>(
+        &self,
+        top_left: Point,
+        style: TextStyle<BinaryColor, F>,
+    ) -> Vec<Pixel<BinaryColor>> {
+        let (left, right) = (top_left.x, top_left.x + self.width as i32);
+        Text::new(
+            &self.text,
+            top_left - Point::new(self.get_offset() as i32, 0),
+        )
+        .into_styled(style)
+        .into_iter()
+        // Remove the overflow
+        .filter(|pixel| left <= pixel.0.x && pixel.0.x < right)
+        .collect()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::component::Marquee;
+    use crate::style::TEXT_LIGHT;
+    use embedded_graphics::fonts::Font6x8;
+    use embedded_graphics::prelude::*;
+
+    /// A short text never scrolls.
+    #[test]
+    fn short_text() {
+        let mut marquee = Marquee::new("Hello", 60, Font6x8);
+        assert!(!marquee.overflows());
+        assert!((0..100).all(|_| !marquee.tick()));
+        assert_eq!(marquee.get_offset(), 0);
+    }
+
+    /// A long text waits, scrolls to its end, waits and goes back.
+    #[test]
+    fn scrolling_cycle() {
+        // 12 chars of 6px in a 60px marquee: 12px to scroll
+        let mut marquee = Marquee::new("Hello, world", 60, Font6x8);
+        assert!(marquee.overflows());
+        // The pause at the beginning
+        for _ in 0..10 {
+            marquee.tick();
+            assert_eq!(marquee.get_offset(), 0);
+        }
+        // Scroll one pixel per tick
+        for offset in 1..=12 {
+            assert!(marquee.tick());
+            assert_eq!(marquee.get_offset(), offset);
+        }
+        // The pause at the end
+        for _ in 0..10 {
+            assert!(!marquee.tick());
+            assert_eq!(marquee.get_offset(), 12);
+        }
+        // And back to the beginning
+        assert!(marquee.tick());
+        assert_eq!(marquee.get_offset(), 0);
+    }
+
+    /// The pixels out of the marquee are not drawn.
+    #[test]
+    fn clipped_pixels() {
+        let mut marquee = Marquee::new("Hello, world", 60, Font6x8);
+        (0..15).for_each(|_| {
+            marquee.tick();
+        });
+        let pixels = marquee.pixels(Point::new(10, 5), *TEXT_LIGHT);
+        assert!(!pixels.is_empty());
+        assert!(pixels.iter().all(|p| 10 <= p.0.x && p.0.x < 70));
+    }
+}
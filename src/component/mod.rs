@@ -1,7 +1,15 @@
 pub use appbar::AppBar;
+pub use bigtext::BigText;
 pub use buttonbar::{Button, ButtonBar};
 pub use list::List;
+pub use marquee::Marquee;
+pub use spinner::Spinner;
+pub use textblock::TextBlock;
 
 mod appbar;
+mod bigtext;
 mod buttonbar;
 mod list;
+mod marquee;
+mod spinner;
+mod textblock;
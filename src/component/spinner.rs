@@ -0,0 +1,87 @@
+use crate::style::{TEXT_BOLD, TITLE_BOLD};
+use embedded_graphics::drawable::Pixel;
+use embedded_graphics::fonts::Text;
+use embedded_graphics::geometry::Point;
+use embedded_graphics::pixelcolor::BinaryColor;
+
+/// A spinner component, a number chosen between a min and a max.
+///
+/// The value wraps around: incrementing the max gives the min.
+#[derive(Clone, Debug)]
+pub struct Spinner {
+    value: u32,
+    min: u32,
+    max: u32,
+}
+
+impl Spinner {
+    /// Create a spinner from its initial value (clamped) and its bounds
+    pub fn new(value: u32, min: u32, max: u32) -> Self {
+        Self {
+            value: value.max(min).min(max),
+            min,
+            max,
+        }
+    }
+
+    /// Get the current value
+    pub fn get_value(&self) -> u32 {
+        self.value
+    }
+
+    /// Increment the value (or go back to the min)
+    pub fn increment(&mut self) {
+        self.value = if self.value >= self.max {
+            self.min
+        } else {
+            self.value + 1
+        };
+    }
+
+    /// Decrement the value (or go to the max)
+    pub fn decrement(&mut self) {
+        self.value = if self.value <= self.min {
+            self.max
+        } else {
+            self.value - 1
+        };
+    }
+
+    /// Get the pixels of the value, padded with zeros to the width of the max.
+    /// The selected spinner is drawn inverted.
+    pub fn pixels(&self, top_left: Point, selected: bool) -> Vec<Pixel<BinaryColor>> {
+        let width = self.max.to_string().len();
+        let text = format!("{:0width$}", self.value, width = width);
+        let text = Text::new(&text, top_left);
+        if selected {
+            text.into_styled(*TITLE_BOLD).into_iter().collect()
+        } else {
+            text.into_styled(*TEXT_BOLD).into_iter().collect()
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::component::Spinner;
+    use embedded_graphics::prelude::*;
+
+    /// The value wraps around its bounds.
+    #[test]
+    fn wrapping() {
+        let mut hour = Spinner::new(23, 0, 23);
+        hour.increment();
+        assert_eq!(hour.get_value(), 0);
+        hour.decrement();
+        assert_eq!(hour.get_value(), 23);
+        assert_eq!(Spinner::new(99, 0, 59).get_value(), 59);
+    }
+
+    /// The value is padded to the width of the max.
+    #[test]
+    fn padded_value() {
+        // "07" in a 8x8 font
+        let pixels = Spinner::new(7, 0, 59).pixels(Point::zero(), false);
+        assert_eq!(pixels.len(), 2 * 8 * 8);
+    }
+}
@@ -0,0 +1,199 @@
+use crate::style::{Glyphs, FILL_OFF};
+use embedded_graphics::drawable::Pixel;
+use embedded_graphics::fonts::{Font, Text};
+use embedded_graphics::geometry::{Point, Size};
+use embedded_graphics::pixelcolor::BinaryColor;
+use embedded_graphics::prelude::*;
+use embedded_graphics::primitives::Rectangle;
+use embedded_graphics::style::TextStyle;
+
+/// A text block component, a multi-line text word-wrapped in a rectangle.
+///
+/// The newlines of the text are kept. When the text does not fit in the rectangle,
+/// it is split in pages which can be scrolled.
+#[derive(Clone, Debug)]
+pub struct TextBlock {
+    lines: Vec<String>,
+    top_left: Point,
+    bottom_right: Point,
+    /// The size of a character (in pixels)
+    char_size: Size,
+    /// The number of lines per page
+    rows: usize,
+    page: usize,
+}
+
+impl TextBlock {
+    /// Create a text block from its text, the corners of its rectangle and the font drawing it.
+    /// The text is normalized to be drawn with the glyphs of the font.
+    pub fn new<T: Into<String>, F: Font + Glyphs>(
+        text: T,
+        top_left: Point,
+        bottom_right: Point,
+        _font: F,
+    ) -> Self {
+        let char_size = F::CHARACTER_SIZE;
+        let width = (bottom_right.x - top_left.x + 1).max(0) as u32;
+        let height = (bottom_right.y - top_left.y + 1).max(0) as u32;
+        let columns = (width / char_size.width).max(1) as usize;
+        let rows = (height / char_size.height).max(1) as usize;
+        Self {
+            lines: wrap(&F::normalize(&text.into()), columns),
+            top_left,
+            bottom_right,
+            char_size,
+            rows,
+            page: 0,
+        }
+    }
+
+    /// Get the number of pages
+    pub fn pages(&self) -> usize {
+        self.lines.len().div_ceil(self.rows).max(1)
+    }
+
+    /// Get the current page (from 0)
+    pub fn get_page(&self) -> usize {
+        self.page
+    }
+
+    /// Go to the next page.
+    /// Returns true if the page has changed.
+    pub fn next_page(&mut self) -> bool {
+        if self.page + 1 < self.pages() {
+            self.page += 1;
+            return true;
+        }
+        false
+    }
+
+    /// Go to the previous page.
+    /// Returns true if the page has changed.
+    pub fn previous_page(&mut self) -> bool {
+        if self.page > 0 {
+            self.page -= 1;
+            return true;
+        }
+        false
+    }
+
+    /// Get the lines of the current page
+    pub fn get_lines(&self) -> &[String] {
+        let start = self.page * self.rows;
+        let end = (start + self.rows).min(self.lines.len());
+        &self.lines[start..end]
+    }
+
+    /// Get the pixels of the current page, drawn with the given style.
+    /// The whole rectangle is cleared first.
+    pub fn pixels<F: Font + Copy>(
+        &self,
+        style: TextStyle<BinaryColor, F>,
+    ) -> Vec<Pixel<BinaryColor>> {
+        let background = Rectangle::new(self.top_left, self.bottom_right)
+            .into_styled(*FILL_OFF)
+            .into_iter();
+        let text = self.get_lines().iter().enumerate().flat_map(|(row, line)| {
+            let position =
+                self.top_left + Point::new(0, (row as u32 * self.char_size.height) as i32);
+            Text::new(line, position)
+                .into_styled(style)
+                .into_iter()
+                .collect::<Vec<_>>()
+        });
+        background.chain(text).collect()
+    }
+}
+
+/// Word-wrap a text in lines of (at most) the given number of characters.
+///
+/// The newlines are kept, the spaces between two lines are removed
+/// and the words longer than a line are split.
+fn wrap(text: &str, columns: usize) -> Vec<String> {
+    let mut lines = Vec::new();
+    for paragraph in text.split('\n') {
+        let mut line = String::new();
+        let mut length = 0;
+        for word in paragraph.split(' ') {
+            let mut word: Vec<char> = word.chars().collect();
+            // Split the words longer than a line
+            while word.len() > columns {
+                if length > 0 {
+                    lines.push(std::mem::take(&mut line));
+                }
+                lines.push(word.drain(..columns).collect());
+                length = 0;
+            }
+            if length > 0 && length + 1 + word.len() > columns {
+                lines.push(std::mem::take(&mut line));
+                length = 0;
+            }
+            if length > 0 {
+                line.push(' ');
+                length += 1;
+            }
+            line.extend(word.iter());
+            length += word.len();
+        }
+        lines.push(line);
+    }
+    lines
+}
+
+#[cfg(test)]
+mod test {
+    use super::wrap;
+    use crate::component::TextBlock;
+    use crate::style::TEXT_LIGHT;
+    use embedded_graphics::fonts::Font6x8;
+    use embedded_graphics::prelude::*;
+
+    /// The words are not broken and the newlines are kept.
+    #[test]
+    fn word_wrap() {
+        assert_eq!(
+            wrap("Oops\nNo error has occured\nSorry.", 10),
+            vec!["Oops", "No error", "has", "occured", "Sorry."]
+        );
+        assert_eq!(wrap("a\n\nb", 10), vec!["a", "", "b"]);
+        assert_eq!(wrap("", 10), vec![""]);
+    }
+
+    /// The words too long for a line are split.
+    #[test]
+    fn long_words() {
+        assert_eq!(
+            wrap("see /org/mpris/MediaPlayer2 now", 10),
+            vec!["see", "/org/mpris", "/MediaPlay", "er2 now"]
+        );
+    }
+
+    /// The text is split in pages which can be scrolled.
+    #[test]
+    fn paging() {
+        // 26 columns and 3 rows
+        let text = "one two three four five six seven eight nine ten eleven twelve thirteen";
+        let block = TextBlock::new(text, Point::new(0, 11), Point::new(159, 34), Font6x8);
+        assert_eq!(block.pages(), 1);
+        assert_eq!(block.get_lines()[2], "eleven twelve thirteen");
+
+        let mut block = TextBlock::new(
+            format!("{}\nthe end", text),
+            Point::new(0, 11),
+            Point::new(159, 34),
+            Font6x8,
+        );
+        assert_eq!(block.pages(), 2);
+        assert!(!block.previous_page());
+        assert!(block.next_page());
+        assert_eq!(block.get_page(), 1);
+        assert_eq!(block.get_lines(), &["the end"]);
+        assert!(!block.next_page());
+
+        // The pixels stay in the rectangle
+        let pixels = block.pixels(*TEXT_LIGHT);
+        assert!(pixels
+            .iter()
+            .all(|p| (0..160).contains(&p.0.x) && (11..35).contains(&p.0.y)));
+    }
+}
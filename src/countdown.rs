@@ -0,0 +1,381 @@
+use crate::settings;
+use crate::time::{MonotonicSource, SystemTime, TimeSource};
+use crate::xdg;
+use chrono::NaiveDate;
+use log::{info, warn};
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+use std::sync::{Mutex, MutexGuard, PoisonError};
+use std::time::{Duration, Instant};
+
+/// The countdown, shared by the timer app and the background check
+static COUNTDOWN: Lazy<Mutex<Countdown<SystemTime>>> = Lazy::new(|| {
+    let presets = match xdg::config_file("timers.toml") {
+        Some(path) => load_presets(path),
+        None => TimerConfig::default().presets,
+    };
+    let mut countdown = Countdown::new(SystemTime, presets);
+    if let Some(path) = xdg::state_file("cycles") {
+        countdown.load_cycles(path);
+    }
+    Mutex::new(countdown)
+});
+
+/// The longest duration of a phase (in minutes)
+const MAX_MINUTES: u64 = 180;
+
+/// A countdown preset: a work phase, optionally followed by a rest phase
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct Preset {
+    pub name: String,
+    /// The work duration (in minutes)
+    pub work: u32,
+    /// The rest duration (in minutes), if any
+    pub rest: Option<u32>,
+}
+
+impl Preset {
+    /// Create a preset from its name and its durations (in minutes)
+    pub fn new(name: &str, work: u32, rest: Option<u32>) -> Self {
+        Self {
+            name: name.to_owned(),
+            work,
+            rest,
+        }
+    }
+}
+
+/// The content of the timers file
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(default)]
+pub struct TimerConfig {
+    pub presets: Vec<Preset>,
+}
+
+impl Default for TimerConfig {
+    fn default() -> Self {
+        Self {
+            presets: vec![
+                Preset::new("Pomodoro", 25, Some(5)),
+                Preset::new("Tea", 10, None),
+            ],
+        }
+    }
+}
+
+/// Load the presets from the given file, or the default ones if it can't be read
+fn load_presets(path: PathBuf) -> Vec<Preset> {
+    let config: TimerConfig = settings::read_toml(&path, "timers");
+    if config.presets.is_empty() {
+        TimerConfig::default().presets
+    } else {
+        config.presets
+    }
+}
+
+/// The phases of a cycle
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Phase {
+    Work,
+    Rest,
+}
+
+/// A countdown through the phases of its presets, counting the completed cycles per day
+#[derive(Debug)]
+pub struct Countdown<T: MonotonicSource> {
+    time: T,
+    presets: Vec<Preset>,
+    /// The index of the current preset
+    preset: usize,
+    phase: Phase,
+    /// The full duration of the current phase
+    duration: Duration,
+    /// The remaining time when the countdown was (re)started or paused
+    remaining: Duration,
+    /// When the countdown was (re)started, None if it is paused
+    started: Option<Instant>,
+    /// Whether the current phase is over
+    finished: bool,
+    /// The day of the completed cycles, and their count
+    cycles: (Option<NaiveDate>, u32),
+    /// The file where the completed cycles are saved
+    path: Option<PathBuf>,
+}
+
+impl<T: MonotonicSource> Countdown<T> {
+    /// Create a countdown, ready to start the first preset (there must be at least one)
+    pub fn new(time: T, presets: Vec<Preset>) -> Self {
+        let mut countdown = Self {
+            time,
+            presets,
+            preset: 0,
+            phase: Phase::Work,
+            duration: Duration::default(),
+            remaining: Duration::default(),
+            started: None,
+            finished: false,
+            cycles: (None, 0),
+            path: None,
+        };
+        countdown.stop();
+        countdown
+    }
+
+    /// Load the completed cycles from the given file ("<date> <count>"),
+    /// they will be saved in this file.
+    fn load_cycles(&mut self, path: PathBuf) {
+        if let Ok(content) = fs::read_to_string(&path) {
+            let mut fields = content.split_whitespace();
+            let date = fields.next().and_then(|date| date.parse().ok());
+            let count = fields.next().and_then(|count| count.parse().ok());
+            if let (Some(date), Some(count)) = (date, count) {
+                self.cycles = (Some(date), count);
+            }
+        }
+        self.path = Some(path);
+    }
+
+    /// Save the completed cycles in their file (if any)
+    fn save_cycles(&self) -> Result<(), io::Error> {
+        if let (Some(path), (Some(date), count)) = (&self.path, self.cycles) {
+            if let Some(dir) = path.parent() {
+                fs::create_dir_all(dir)?;
+            }
+            fs::write(path, format!("{} {}\n", date, count))?;
+        }
+        Ok(())
+    }
+
+    /// Get the current preset
+    pub fn get_preset(&self) -> &Preset {
+        &self.presets[self.preset]
+    }
+
+    /// Get the current phase
+    pub fn get_phase(&self) -> Phase {
+        self.phase
+    }
+
+    /// Get the full duration of the current phase
+    pub fn get_duration(&self) -> Duration {
+        self.duration
+    }
+
+    /// Get the remaining time of the current phase
+    pub fn remaining(&self) -> Duration {
+        match self.started {
+            Some(started) => self
+                .remaining
+                .checked_sub(self.time.instant().saturating_duration_since(started))
+                .unwrap_or_default(),
+            None => self.remaining,
+        }
+    }
+
+    /// Check if the countdown is running
+    pub fn is_running(&self) -> bool {
+        self.started.is_some()
+    }
+
+    /// Check if the current phase is over
+    pub fn is_finished(&self) -> bool {
+        self.finished
+    }
+
+    /// Check if the current phase has not been started yet
+    pub fn is_idle(&self) -> bool {
+        !self.is_running() && !self.finished && self.remaining == self.duration
+    }
+
+    /// Get the number of completed cycles of the given day
+    pub fn get_cycles(&self, today: NaiveDate) -> u32 {
+        match self.cycles {
+            (Some(date), count) if date == today => count,
+            _ => 0,
+        }
+    }
+
+    /// Start the countdown or pause it.
+    /// If the phase is over, start the next one.
+    pub fn toggle(&mut self) {
+        if self.finished {
+            self.phase = match (self.phase, self.get_preset().rest) {
+                (Phase::Work, Some(_)) => Phase::Rest,
+                _ => Phase::Work,
+            };
+            self.reset_phase();
+            self.started = Some(self.time.instant());
+        } else if self.is_running() {
+            self.remaining = self.remaining();
+            self.started = None;
+        } else {
+            self.started = Some(self.time.instant());
+        }
+    }
+
+    /// Stop the countdown, back to the work phase of the current preset
+    pub fn stop(&mut self) {
+        self.phase = Phase::Work;
+        self.reset_phase();
+    }
+
+    /// Select the next preset (or the first after the last), only if the countdown is idle
+    pub fn next_preset(&mut self) {
+        if self.is_idle() {
+            self.preset = (self.preset + 1) % self.presets.len();
+            self.stop();
+        }
+    }
+
+    /// Change the duration of the current phase (and its remaining time) of some minutes
+    pub fn adjust(&mut self, minutes: i64) {
+        let idle = self.is_idle();
+        let step = Duration::from_secs(minutes.unsigned_abs() * 60);
+        let max = Duration::from_secs(MAX_MINUTES * 60);
+        let (duration, remaining) = if minutes >= 0 {
+            ((self.duration + step).min(max), self.remaining() + step)
+        } else {
+            let one = Duration::from_secs(60);
+            let duration = self.duration.checked_sub(step).unwrap_or_default().max(one);
+            (
+                duration,
+                self.remaining().checked_sub(step).unwrap_or_default(),
+            )
+        };
+        self.duration = duration;
+        // An idle countdown always starts with its full duration
+        self.remaining = if idle {
+            duration
+        } else {
+            remaining.min(duration)
+        };
+        if self.is_running() {
+            self.started = Some(self.time.instant());
+        }
+    }
+
+    /// Check if the current phase is over, counting the cycle of the given day
+    /// if it was a work phase. Returns true if the phase just ended.
+    pub fn check(&mut self, today: NaiveDate) -> bool {
+        if !self.is_running() || self.remaining() > Duration::default() {
+            return false;
+        }
+        self.started = None;
+        self.remaining = Duration::default();
+        self.finished = true;
+        if self.phase == Phase::Work {
+            let count = self.get_cycles(today) + 1;
+            self.cycles = (Some(today), count);
+            if let Err(err) = self.save_cycles() {
+                warn!("Can't save the completed cycles: {}", err);
+            }
+        }
+        true
+    }
+
+    /// Set the remaining time to the full duration of the current phase
+    fn reset_phase(&mut self) {
+        let preset = self.get_preset();
+        let minutes = match self.phase {
+            Phase::Work => preset.work,
+            Phase::Rest => preset.rest.unwrap_or(preset.work),
+        };
+        self.duration = Duration::from_secs(u64::from(minutes).clamp(1, MAX_MINUTES) * 60);
+        self.remaining = self.duration;
+        self.started = None;
+        self.finished = false;
+    }
+}
+
+/// Get the countdown of the g13 apps
+pub fn countdown() -> MutexGuard<'static, Countdown<SystemTime>> {
+    COUNTDOWN.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+/// Check the countdown at the day given by the source.
+/// Returns true if its phase just ended.
+pub fn check<T: TimeSource>(time: &T) -> bool {
+    let mut countdown = countdown();
+    let ended = countdown.check(time.now().date_naive());
+    if ended {
+        info!("The {} countdown is over", countdown.get_preset().name);
+    }
+    ended
+}
+
+#[cfg(test)]
+mod test {
+    use crate::countdown::{Countdown, Phase, Preset, TimerConfig};
+    use crate::time::ManualTime;
+    use chrono::NaiveDate;
+    use std::time::Duration;
+
+    fn minutes(minutes: u64) -> Duration {
+        Duration::from_secs(minutes * 60)
+    }
+
+    /// A pomodoro alternates work and rest, only the work phases are counted.
+    #[test]
+    fn pomodoro_cycle() {
+        let clock = ManualTime::new();
+        let today = NaiveDate::from_ymd_opt(2020, 7, 14).unwrap();
+        let mut countdown = Countdown::new(clock.clone(), TimerConfig::default().presets);
+        assert!(countdown.is_idle());
+        assert_eq!(countdown.remaining(), minutes(25));
+
+        countdown.toggle();
+        clock.advance(minutes(10));
+        countdown.toggle();
+        clock.advance(minutes(30));
+        // Paused
+        assert!(!countdown.check(today));
+        assert_eq!(countdown.remaining(), minutes(15));
+
+        countdown.toggle();
+        clock.advance(minutes(15));
+        assert!(countdown.check(today));
+        assert!(countdown.is_finished());
+        assert_eq!(countdown.get_cycles(today), 1);
+
+        // Then the rest
+        countdown.toggle();
+        assert_eq!(countdown.get_phase(), Phase::Rest);
+        clock.advance(minutes(5));
+        assert!(countdown.check(today));
+        assert_eq!(countdown.get_cycles(today), 1);
+        assert_eq!(countdown.get_cycles(today.succ_opt().unwrap()), 0);
+
+        // And work again
+        countdown.toggle();
+        assert_eq!(countdown.get_phase(), Phase::Work);
+        assert!(countdown.is_running());
+    }
+
+    /// The presets can be changed when idle, and their durations adjusted.
+    #[test]
+    fn presets_and_adjust() {
+        let clock = ManualTime::new();
+        let presets = vec![Preset::new("Tea", 10, None), Preset::new("Egg", 1, None)];
+        let mut countdown = Countdown::new(clock.clone(), presets);
+        countdown.adjust(2);
+        assert_eq!(countdown.get_duration(), minutes(12));
+
+        countdown.next_preset();
+        assert_eq!(countdown.get_preset().name, "Egg");
+        // At least a minute
+        countdown.adjust(-1);
+        assert_eq!(countdown.get_duration(), minutes(1));
+        assert_eq!(countdown.remaining(), minutes(1));
+
+        countdown.toggle();
+        clock.advance(Duration::from_secs(30));
+        countdown.adjust(1);
+        assert_eq!(countdown.remaining(), Duration::from_secs(90));
+        // Not idle
+        countdown.next_preset();
+        assert_eq!(countdown.get_preset().name, "Egg");
+    }
+}
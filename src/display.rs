@@ -1,4 +1,5 @@
 use crate::error::DisplayError;
+use crate::protocol::{self, Message, FRAMEBUFFER_SIZE};
 use embedded_graphics::drawable::Pixel;
 use embedded_graphics::geometry::Size;
 use embedded_graphics::pixelcolor::BinaryColor;
@@ -6,13 +7,12 @@ use embedded_graphics::DrawTarget;
 use std::convert::TryInto;
 use std::marker::Unpin;
 use tokio::io::AsyncWrite;
-use tokio::io::AsyncWriteExt;
 
 /// Representation of the G13 LCD screen
 ///
 /// It works using a AsyncWriter to flush the buffer to the real screen.
 pub struct G13Display<'a, W: Unpin + AsyncWrite + 'a> {
-    framebuffer: [u8; 20 * 48],
+    framebuffer: [u8; FRAMEBUFFER_SIZE],
     tx: &'a mut W,
 }
 
@@ -48,20 +48,20 @@ impl<'a, W: Unpin + AsyncWrite + 'a> G13Display<'a, W> {
     /// Create a new instance of G13Display with the given writer
     pub fn new(writer: &'a mut W) -> Self {
         G13Display {
-            framebuffer: [0; 20 * 48],
+            framebuffer: [0; FRAMEBUFFER_SIZE],
             tx: writer,
         }
     }
 
     /// Get the inner framebuffer as a Vec<u8>
+    #[cfg(test)]
     fn to_bytes(&self) -> Vec<u8> {
         self.framebuffer.to_vec()
     }
 
     /// Flush the current framebuffer to the display using the inner writer
     pub async fn flush(&mut self) -> Result<(), DisplayError> {
-        self.tx.write(&self.to_bytes()).await?;
-        self.tx.flush().await?;
+        protocol::send(self.tx, &Message::Framebuffer(&self.framebuffer)).await?;
         Ok(())
     }
 }
@@ -0,0 +1,163 @@
+use crate::error::DisplayError;
+use crate::protocol::{self, Leds, Message, APP_KEYS};
+use std::marker::Unpin;
+use tokio::io::AsyncWrite;
+
+/// The state of a mode LED
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Led {
+    Off,
+    On,
+    /// Lit every other second
+    Blink,
+}
+
+impl Led {
+    /// Check if the LED is lit, in the given phase of the blinking
+    fn is_lit(self, blink: bool) -> bool {
+        match self {
+            Led::Off => false,
+            Led::On => true,
+            Led::Blink => blink,
+        }
+    }
+}
+
+/// The states of the mode LEDs declared by an app (None if the app does not care)
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct LedStates {
+    pub m1: Option<Led>,
+    pub m2: Option<Led>,
+    pub m3: Option<Led>,
+    pub mr: Option<Led>,
+}
+
+impl LedStates {
+    /// Put the declared states over the given ones (keeping them for the undeclared LEDs)
+    pub fn over(self, under: LedStates) -> LedStates {
+        LedStates {
+            m1: self.m1.or(under.m1),
+            m2: self.m2.or(under.m2),
+            m3: self.m3.or(under.m3),
+            mr: self.mr.or(under.mr),
+        }
+    }
+
+    /// Get the lit LEDs, in the given phase of the blinking (the undeclared ones are off)
+    pub fn resolve(self, blink: bool) -> Leds {
+        let lit = |led: Option<Led>| led.is_some_and(|led| led.is_lit(blink));
+        Leds {
+            m1: lit(self.m1),
+            m2: lit(self.m2),
+            m3: lit(self.m3),
+            mr: lit(self.mr),
+        }
+    }
+}
+
+/// The text commands of the G13 driver
+///
+/// They are written on the same pipe as the display framebuffer (see [crate::protocol]).
+pub struct G13Driver<'a, W: Unpin + AsyncWrite + 'a> {
+    tx: &'a mut W,
+}
+
+impl<'a, W: Unpin + AsyncWrite + 'a> G13Driver<'a, W> {
+    /// Create a new instance of G13Driver with the given writer
+    pub fn new(writer: &'a mut W) -> Self {
+        G13Driver { tx: writer }
+    }
+
+    /// Send a message to the driver
+    pub async fn send(&mut self, message: &Message<'_>) -> Result<(), DisplayError> {
+        Ok(protocol::send(self.tx, message).await?)
+    }
+
+    /// Set the color of the keyboard backlight (red, green, blue)
+    pub async fn set_backlight(&mut self, color: [u8; 3]) -> Result<(), DisplayError> {
+        self.send(&Message::Backlight(color)).await
+    }
+
+    /// Set the mode LEDs (the others are turned off)
+    pub async fn set_leds(&mut self, leds: Leds) -> Result<(), DisplayError> {
+        self.send(&Message::Leds(leds)).await
+    }
+
+    /// Bind the keys used by the g13 apps, in the current profile of the driver
+    pub async fn bind_app_keys(&mut self) -> Result<(), DisplayError> {
+        for key in APP_KEYS.iter() {
+            self.send(&Message::bind_output(*key)).await?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::driver::{G13Driver, Led, LedStates};
+    use crate::protocol::Leds;
+    use std::io::Cursor;
+    use tokio::runtime::Runtime;
+
+    /// The backlight color is sent as a rgb command.
+    #[test]
+    fn backlight_command() {
+        let mut rt = Runtime::new().unwrap();
+        let mut writer = Cursor::new(Vec::<u8>::new());
+        let mut driver = G13Driver::new(&mut writer);
+        rt.block_on(driver.set_backlight([255, 128, 0])).unwrap();
+        rt.block_on(driver.set_backlight([0, 0, 0])).unwrap();
+        assert_eq!(writer.into_inner(), b"rgb 255 128 0\nrgb 0 0 0\n".to_vec());
+    }
+
+    /// The mode LEDs are sent as a mask.
+    #[test]
+    fn leds_command() {
+        let mut rt = Runtime::new().unwrap();
+        let mut writer = Cursor::new(Vec::<u8>::new());
+        let leds = Leds {
+            m1: true,
+            mr: true,
+            ..Default::default()
+        };
+        rt.block_on(G13Driver::new(&mut writer).set_leds(leds))
+            .unwrap();
+        assert_eq!(writer.into_inner(), b"mod 9\n".to_vec());
+    }
+
+    /// The keys of the g13 apps are bound as in the supplied bind file.
+    #[test]
+    fn app_binds() {
+        let mut rt = Runtime::new().unwrap();
+        let mut writer = Cursor::new(Vec::<u8>::new());
+        rt.block_on(G13Driver::new(&mut writer).bind_app_keys())
+            .unwrap();
+        let binds = String::from_utf8(writer.into_inner()).unwrap();
+        let supplied = std::fs::read_to_string("g13-apps.bind").unwrap();
+        let supplied: Vec<&str> = supplied.lines().filter(|line| line.starts_with("bind")).collect();
+        assert_eq!(binds.lines().collect::<Vec<_>>(), supplied);
+    }
+
+    /// The states declared by the foreground app are put over the overlays.
+    #[test]
+    fn leds_arbitration() {
+        let app = LedStates {
+            m1: Some(Led::On),
+            mr: Some(Led::Off),
+            ..Default::default()
+        };
+        let overlays = LedStates {
+            m2: Some(Led::Blink),
+            mr: Some(Led::Blink),
+            ..Default::default()
+        };
+        let states = app.over(overlays);
+        let lit = Leds {
+            m1: true,
+            m2: true,
+            ..Default::default()
+        };
+        assert_eq!(states.resolve(true), lit);
+        assert_eq!(states.resolve(false), Leds { m2: false, ..lit });
+    }
+}
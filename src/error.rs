@@ -38,6 +38,18 @@ pub enum AppError {
     #[error(transparent)]
     MusicError(crate::app::MusicError),
 
+    /// Represents an error while saving the alarms
+    #[error(transparent)]
+    AlarmError(#[from] crate::alarm::AlarmError),
+
+    /// Represents an error while saving the settings
+    #[error(transparent)]
+    SettingsError(#[from] crate::settings::SettingsError),
+
+    /// Represents an error while loading a profile
+    #[error(transparent)]
+    ProfileError(#[from] crate::profile::ProfileError),
+
     /// Represents an error caused by the G13 display
     #[error(transparent)]
     DisplayError(#[from] crate::error::DisplayError),
@@ -0,0 +1,273 @@
+use crate::random::Rng;
+
+/// The chance of a cell to be alive in a random soup (one in ...)
+const SOUP_DENSITY: u32 = 3;
+
+/// The starting patterns of a game of life, in plain text (`O` for the live cells)
+#[cfg(feature = "life")]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Pattern {
+    /// Random cells, from a seed
+    Soup,
+    /// The Gosper glider gun, shooting a glider every 30 generations
+    GliderGun,
+    /// A small glider, crossing the world
+    Glider,
+    /// A methuselah, stabilizing after 1103 generations
+    RPentomino,
+    /// A methuselah, stabilizing after 5206 generations
+    Acorn,
+}
+
+#[cfg(feature = "life")]
+impl Pattern {
+    pub const ALL: [Pattern; 5] = [
+        Pattern::Soup,
+        Pattern::GliderGun,
+        Pattern::Glider,
+        Pattern::RPentomino,
+        Pattern::Acorn,
+    ];
+
+    /// Get the name shown of the pattern
+    pub fn name(self) -> &'static str {
+        match self {
+            Pattern::Soup => "Random",
+            Pattern::GliderGun => "Glider gun",
+            Pattern::Glider => "Glider",
+            Pattern::RPentomino => "R-pentomino",
+            Pattern::Acorn => "Acorn",
+        }
+    }
+
+    /// Get the rows of the pattern (empty for the soup)
+    fn rows(self) -> &'static [&'static str] {
+        match self {
+            Pattern::Soup => &[],
+            Pattern::GliderGun => &[
+                "........................O...........",
+                "......................O.O...........",
+                "............OO......OO............OO",
+                "...........O...O....OO............OO",
+                "OO........O.....O...OO..............",
+                "OO........O...O.OO....O.O...........",
+                "..........O.....O.......O...........",
+                "...........O...O....................",
+                "............OO......................",
+            ],
+            Pattern::Glider => &[".O.", "..O", "OOO"],
+            Pattern::RPentomino => &[".OO", "OO.", ".O."],
+            Pattern::Acorn => &[".O.....", "...O...", "OO..OOO"],
+        }
+    }
+
+    /// Get the pattern after this one (the first after the last)
+    pub fn next(self) -> Self {
+        let index = Self::ALL.iter().position(|pattern| *pattern == self);
+        Self::ALL[index.map_or(0, |index| (index + 1) % Self::ALL.len())]
+    }
+}
+
+/// Conway's game of life, on a world wrapping around its edges.
+///
+/// A live cell survives with 2 or 3 live neighbours, a dead one is born with exactly 3.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Life {
+    width: i32,
+    height: i32,
+    /// The cells, row by row (true if alive)
+    cells: Vec<bool>,
+    generation: u32,
+}
+
+impl Life {
+    /// Create an empty world
+    pub fn new(width: i32, height: i32) -> Self {
+        Life {
+            width,
+            height,
+            cells: vec![false; (width * height) as usize],
+            generation: 0,
+        }
+    }
+
+    /// Create a world of random cells, from the generator
+    pub fn soup(width: i32, height: i32, rng: &mut Rng) -> Self {
+        let mut life = Self::new(width, height);
+        for cell in life.cells.iter_mut() {
+            *cell = rng.below(SOUP_DENSITY) == 0;
+        }
+        life
+    }
+
+    /// Create a world with the given pattern: the gun on the top left,
+    /// the others in the middle, the soup from the generator
+    #[cfg(feature = "life")]
+    pub fn with_pattern(width: i32, height: i32, pattern: Pattern, rng: &mut Rng) -> Self {
+        let mut life = Self::new(width, height);
+        match pattern {
+            Pattern::Soup => life = Self::soup(width, height, rng),
+            Pattern::GliderGun => life.place(pattern.rows(), 1, 1),
+            _ => {
+                let rows = pattern.rows();
+                let columns = rows.iter().map(|row| row.len()).max().unwrap_or_default();
+                let x = (width - columns as i32) / 2;
+                let y = (height - rows.len() as i32) / 2;
+                life.place(rows, x, y);
+            }
+        }
+        life
+    }
+
+    /// Place a pattern with its top left on the given cell (`O` for the live cells)
+    #[cfg(any(feature = "life", test))]
+    pub fn place(&mut self, rows: &[&str], x: i32, y: i32) {
+        for (dy, row) in rows.iter().enumerate() {
+            for (dx, cell) in row.chars().enumerate() {
+                if cell == 'O' {
+                    self.set(x + dx as i32, y + dy as i32, true);
+                }
+            }
+        }
+    }
+
+    /// Set a cell alive or dead (the coordinates wrap around)
+    #[cfg(any(feature = "life", test))]
+    pub fn set(&mut self, x: i32, y: i32, alive: bool) {
+        let index = self.index(x, y);
+        self.cells[index] = alive;
+    }
+
+    /// Check if a cell is alive (the coordinates wrap around)
+    pub fn is_alive(&self, x: i32, y: i32) -> bool {
+        self.cells[self.index(x, y)]
+    }
+
+    /// Get the live cells (column, row)
+    pub fn alive(&self) -> impl Iterator<Item = (i32, i32)> + '_ {
+        let width = self.width;
+        self.cells
+            .iter()
+            .enumerate()
+            .filter(|(_, alive)| **alive)
+            .map(move |(index, _)| (index as i32 % width, index as i32 / width))
+    }
+
+    /// Get the number of live cells
+    pub fn population(&self) -> usize {
+        self.cells.iter().filter(|alive| **alive).count()
+    }
+
+    pub fn get_generation(&self) -> u32 {
+        self.generation
+    }
+
+    /// Compute the next generation, telling if any cell changed
+    pub fn step(&mut self) -> bool {
+        let mut next = Vec::with_capacity(self.cells.len());
+        for y in 0..self.height {
+            let rows = [y - 1, y, y + 1];
+            for x in 0..self.width {
+                let mut neighbours = 0;
+                for row in rows.iter() {
+                    for column in [x - 1, x, x + 1].iter() {
+                        if self.is_alive(*column, *row) {
+                            neighbours += 1;
+                        }
+                    }
+                }
+                // The cell itself is counted with its neighbours
+                let alive = self.is_alive(x, y);
+                next.push(neighbours == 3 || (neighbours == 4 && alive));
+            }
+        }
+        let changed = next != self.cells;
+        self.cells = next;
+        self.generation += 1;
+        changed
+    }
+
+    /// Get the index of a cell, wrapping around the edges
+    fn index(&self, x: i32, y: i32) -> usize {
+        (y.rem_euclid(self.height) * self.width + x.rem_euclid(self.width)) as usize
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::life::Life;
+    #[cfg(feature = "life")]
+    use crate::life::Pattern;
+    use crate::random::Rng;
+
+    /// Get the sorted live cells
+    fn alive(life: &Life) -> Vec<(i32, i32)> {
+        let mut cells: Vec<_> = life.alive().collect();
+        cells.sort_unstable();
+        cells
+    }
+
+    /// A blinker oscillates with a period of 2, a block never changes.
+    #[test]
+    fn oscillators() {
+        let mut life = Life::new(8, 8);
+        life.place(&["OOO"], 1, 1);
+        life.place(&["OO", "OO"], 5, 5);
+        let start = alive(&life);
+        assert!(life.step());
+        assert_eq!(
+            alive(&life),
+            vec![(2, 0), (2, 1), (2, 2), (5, 5), (5, 6), (6, 5), (6, 6)]
+        );
+        assert!(life.step());
+        assert_eq!(alive(&life), start);
+        assert_eq!(life.get_generation(), 2);
+
+        let mut block = Life::new(4, 4);
+        block.place(&["OO", "OO"], 1, 1);
+        assert!(!block.step());
+        assert_eq!(block.population(), 4);
+    }
+
+    /// A glider moves by one cell diagonally every 4 generations, wrapping around the edges.
+    #[test]
+    fn glider() {
+        let mut life = Life::new(10, 6);
+        life.place(&[".O.", "..O", "OOO"], 6, 2);
+        let start = alive(&life);
+        for _ in 0..4 {
+            life.step();
+        }
+        let moved: Vec<_> = start.iter().map(|(x, y)| (x + 1, y + 1)).collect();
+        assert_eq!(alive(&life), moved);
+        // Back to its start after 30 moves (the least common multiple of 10 and 6)
+        for _ in 4..4 * 30 {
+            life.step();
+        }
+        assert_eq!(alive(&life), start);
+    }
+
+    /// The glider gun shoots a glider every 30 generations.
+    #[cfg(feature = "life")]
+    #[test]
+    fn glider_gun() {
+        let mut rng = Rng::new(1);
+        let mut life = Life::with_pattern(160, 43, Pattern::GliderGun, &mut rng);
+        assert_eq!(life.population(), 36);
+        for _ in 0..30 {
+            life.step();
+        }
+        assert_eq!(life.population(), 36 + 5);
+        assert_eq!(Pattern::Acorn.next(), Pattern::Soup);
+    }
+
+    /// The soup is replayed from its seed.
+    #[test]
+    fn soup() {
+        let soup = |seed| Life::soup(160, 43, &mut Rng::new(seed));
+        assert_eq!(soup(3), soup(3));
+        assert_ne!(soup(3), soup(4));
+        // About one cell in three
+        assert!((160 * 43 / 4..160 * 43 / 2).contains(&soup(3).population()));
+    }
+}
@@ -0,0 +1,243 @@
+use crate::profile;
+use crate::protocol::Key;
+use crate::settings;
+use crate::xdg;
+use log::warn;
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::sync::{Mutex, PoisonError};
+use std::time::{Duration, Instant};
+
+/// The lock asked by the session (true to lock, false to unlock), until taken by the runtime
+static SESSION: Lazy<Mutex<Option<bool>>> = Lazy::new(Default::default);
+
+/// The delay to press all the keys of the lock combo
+const COMBO_DELAY: Duration = Duration::from_secs(1);
+
+/// The content of the lock file
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(default)]
+pub struct LockConfig {
+    /// The G keys to type to unlock (e.g. `["G1", "G7", "G3", "G9"]`).
+    /// Without them (or without profile to restore the G keys), only the session can lock and unlock.
+    pub pin: Vec<String>,
+    /// The keys to press in a row (within a second) to lock
+    pub combo: Vec<String>,
+    /// Lock instead of starting the screensaver
+    pub idle: bool,
+    /// Follow the lock of the session (logind and the desktop screensaver)
+    pub session: bool,
+    /// The profile loaded on unlock, to restore the G keys (by default the active one)
+    pub profile: Option<String>,
+}
+
+impl Default for LockConfig {
+    fn default() -> Self {
+        Self {
+            pin: Vec::new(),
+            combo: vec!["BD".to_owned(), "BD".to_owned(), "BD".to_owned()],
+            idle: false,
+            session: true,
+            profile: None,
+        }
+    }
+}
+
+impl LockConfig {
+    /// Load the lock configuration (from `$XDG_CONFIG_HOME/g13-apps/lock.toml`)
+    pub fn load() -> Self {
+        let path = match xdg::config_file("lock.toml") {
+            Some(path) => path,
+            None => return Self::default(),
+        };
+        settings::read_toml(&path, "lock")
+    }
+
+    /// Get the keys of the PIN (only the G keys can be typed while locked)
+    pub fn get_pin(&self) -> Vec<Key> {
+        self.pin
+            .iter()
+            .filter_map(|name| match name.parse() {
+                Ok(Key::G(number)) => Some(Key::G(number)),
+                _ => {
+                    warn!("Ignored key {:?} of the lock PIN: not a G key", name);
+                    None
+                }
+            })
+            .collect()
+    }
+
+    /// Get the profile loaded on unlock: the configured one, or else the active one
+    pub fn get_profile(&self) -> Option<String> {
+        self.profile
+            .clone()
+            .or_else(|| profile::profiles().get_active().map(str::to_owned))
+    }
+
+    /// Check if the PIN can unlock: it is set, and a profile restores the G keys afterwards
+    /// (the G keys are bound to the g13 apps to type it)
+    pub fn can_unlock(&self) -> bool {
+        !self.get_pin().is_empty() && self.get_profile().is_some()
+    }
+
+    /// Get the keys of the lock combo (none without PIN: it could not be unlocked)
+    pub fn get_combo(&self) -> Vec<Key> {
+        if self.get_pin().is_empty() {
+            return Vec::new();
+        }
+        self.combo
+            .iter()
+            .filter_map(|name| {
+                name.parse()
+                    .map_err(|err| warn!("Ignored key of the lock combo: {}", err))
+                    .ok()
+            })
+            .collect()
+    }
+
+    /// Check if the lock replaces the screensaver
+    pub fn is_idle_lock(&self) -> bool {
+        self.idle && self.can_unlock()
+    }
+}
+
+/// The detection of the lock combo, among the pressed keys
+#[derive(Clone, Debug, Default)]
+pub struct Combo {
+    /// The keys of the combo
+    keys: Vec<Key>,
+    /// The last keys pressed, and when
+    pressed: VecDeque<(Key, Instant)>,
+}
+
+impl Combo {
+    /// Create the detection of the given combo (never detected if empty)
+    pub fn new(keys: Vec<Key>) -> Self {
+        Combo {
+            keys,
+            pressed: VecDeque::new(),
+        }
+    }
+
+    /// Register a pressed key, checking if the combo is complete
+    pub fn press(&mut self, key: Key, now: Instant) -> bool {
+        if self.keys.is_empty() {
+            return false;
+        }
+        self.pressed.push_back((key, now));
+        while self.pressed.len() > self.keys.len() {
+            self.pressed.pop_front();
+        }
+        let complete = self.pressed.iter().map(|(key, _)| key).eq(self.keys.iter())
+            && self
+                .pressed
+                .front()
+                .is_some_and(|(_, first)| now.duration_since(*first) <= COMBO_DELAY);
+        if complete {
+            self.pressed.clear();
+        }
+        complete
+    }
+}
+
+/// Ask for the lock (or the unlock) of the g13 apps, as the session does
+#[cfg(feature = "session")]
+pub fn request(locked: bool) {
+    *SESSION.lock().unwrap_or_else(PoisonError::into_inner) = Some(locked);
+}
+
+/// Take the lock asked by the session, if any
+pub fn take_request() -> Option<bool> {
+    SESSION
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .take()
+}
+
+/// Follow the lock of the session: the `Lock` and `Unlock` signals of logind (system bus),
+/// and the `ActiveChanged` signal of the desktop screensaver (session bus).
+#[cfg(feature = "session")]
+pub fn watch_session() {
+    use dbus::blocking::Connection;
+    use dbus::message::MatchRule;
+
+    std::thread::spawn(|| {
+        let watch = || -> Result<(), dbus::Error> {
+            let system = Connection::new_system()?;
+            for (member, locked) in [("Lock", true), ("Unlock", false)].iter() {
+                let rule = MatchRule::new_signal("org.freedesktop.login1.Session", *member);
+                system.add_match(rule, move |(): (), _, _| {
+                    request(*locked);
+                    true
+                })?;
+            }
+            loop {
+                system.process(Duration::from_secs(60))?;
+            }
+        };
+        if let Err(err) = watch() {
+            warn!("Can't follow the lock of the session (logind): {}", err);
+        }
+    });
+    std::thread::spawn(|| {
+        let watch = || -> Result<(), dbus::Error> {
+            let session = Connection::new_session()?;
+            let rule = MatchRule::new_signal("org.freedesktop.ScreenSaver", "ActiveChanged");
+            session.add_match(rule, |(active,): (bool,), _, _| {
+                request(active);
+                true
+            })?;
+            loop {
+                session.process(Duration::from_secs(60))?;
+            }
+        };
+        if let Err(err) = watch() {
+            warn!(
+                "Can't follow the lock of the session (screensaver): {}",
+                err
+            );
+        }
+    });
+}
+
+#[cfg(test)]
+mod test {
+    use crate::lock::{Combo, LockConfig};
+    use crate::protocol::Key;
+    use std::time::{Duration, Instant};
+
+    /// The PIN is made of G keys, the combo needs a PIN.
+    #[test]
+    fn lock_file() {
+        let config: LockConfig = toml::from_str("pin = [\"G1\", \"L1\", \"G22\"]\n").unwrap();
+        assert_eq!(config.get_pin(), vec![Key::G(1), Key::G(22)]);
+        assert_eq!(config.get_combo(), vec![Key::BD, Key::BD, Key::BD]);
+        assert!(config.session && !config.is_idle_lock());
+        assert!(LockConfig::default().get_combo().is_empty());
+        // The G keys are only bound if a profile restores them
+        let config: LockConfig =
+            toml::from_str("pin = [\"G1\"]\nidle = true\nprofile = \"office\"\n").unwrap();
+        assert!(config.can_unlock() && config.is_idle_lock());
+        assert_eq!(config.get_profile().as_deref(), Some("office"));
+    }
+
+    /// The combo is detected when its keys are pressed in a row, quickly enough.
+    #[test]
+    fn combo() {
+        let mut combo = Combo::new(vec![Key::BD, Key::L(1)]);
+        let start = Instant::now();
+        let at = |millis| start + Duration::from_millis(millis);
+        assert!(!combo.press(Key::BD, at(0)));
+        assert!(combo.press(Key::L(1), at(300)));
+        // Not twice
+        assert!(!combo.press(Key::L(1), at(400)));
+        // Too slow
+        assert!(!combo.press(Key::BD, at(1000)));
+        assert!(!combo.press(Key::L(1), at(2500)));
+        // Once again
+        assert!(!combo.press(Key::BD, at(3000)));
+        assert!(combo.press(Key::L(1), at(3100)));
+        assert!(!Combo::default().press(Key::BD, at(0)));
+    }
+}
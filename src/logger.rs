@@ -0,0 +1,307 @@
+use crate::xdg;
+use chrono::{DateTime, Local};
+use log::{Level, LevelFilter, Log, Metadata, Record};
+use once_cell::sync::Lazy;
+use std::collections::VecDeque;
+use std::fmt;
+use std::fs::{self, File, OpenOptions};
+use std::io::{BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+
+/// The number of entries kept by the log
+const CAPACITY: usize = 200;
+
+/// The recent log entries, shared with the logs app
+static LOGS: Lazy<Mutex<RingLog>> = Lazy::new(|| Mutex::new(RingLog::new(CAPACITY)));
+
+/// The number of warnings and errors logged since the logs app was last opened
+static UNREAD: AtomicUsize = AtomicUsize::new(0);
+
+/// A log entry
+#[derive(Clone, Debug, PartialEq)]
+pub struct Entry {
+    pub time: DateTime<Local>,
+    pub level: Level,
+    pub target: String,
+    pub message: String,
+}
+
+impl Entry {
+    /// Serialize the entry as a single line, its fields separated by tabs.
+    /// The newlines, tabs and backslashes of the message are escaped.
+    fn to_line(&self) -> String {
+        let message = self
+            .message
+            .replace('\\', "\\\\")
+            .replace('\n', "\\n")
+            .replace('\t', "\\t");
+        format!(
+            "{}\t{}\t{}\t{}",
+            self.time.to_rfc3339(),
+            self.level,
+            self.target,
+            message
+        )
+    }
+
+    /// Parse an entry serialized by [Entry::to_line]
+    fn from_line(line: &str) -> Option<Self> {
+        let mut fields = line.splitn(4, '\t');
+        let time = DateTime::parse_from_rfc3339(fields.next()?).ok()?;
+        let level = Level::from_str(fields.next()?).ok()?;
+        let target = fields.next()?.to_owned();
+        // Unescape the message
+        let mut message = String::new();
+        let mut chars = fields.next()?.chars();
+        while let Some(c) = chars.next() {
+            if c != '\\' {
+                message.push(c);
+                continue;
+            }
+            match chars.next() {
+                Some('n') => message.push('\n'),
+                Some('t') => message.push('\t'),
+                Some(c) => message.push(c),
+                None => {}
+            }
+        }
+        Some(Self {
+            time: time.with_timezone(&Local),
+            level,
+            target,
+            message,
+        })
+    }
+}
+
+impl fmt::Display for Entry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} {} [{}] {}",
+            self.time.format("%Y-%m-%d %H:%M:%S"),
+            self.level,
+            self.target,
+            self.message
+        )
+    }
+}
+
+/// A bounded log: when full, the oldest entries are dropped.
+///
+/// If it has a file, the entries are appended to it and the file is
+/// compacted when it grows too much, to keep it bounded too.
+#[derive(Debug)]
+pub struct RingLog {
+    entries: VecDeque<Entry>,
+    capacity: usize,
+    file: Option<PathBuf>,
+    /// The number of lines in the file
+    file_lines: usize,
+}
+
+impl RingLog {
+    /// Create an in-memory log keeping the given number of entries
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            entries: VecDeque::with_capacity(capacity),
+            capacity,
+            file: None,
+            file_lines: 0,
+        }
+    }
+
+    /// Use the given file to persist the entries.
+    /// The entries already in the file are loaded, before the ones in memory.
+    pub fn open<P: AsRef<Path>>(&mut self, path: P) -> std::io::Result<()> {
+        let path = path.as_ref();
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        let mut entries: VecDeque<Entry> = match File::open(path) {
+            Ok(file) => BufReader::new(file)
+                .lines()
+                .filter_map(|line| Entry::from_line(&line.ok()?))
+                .collect(),
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => VecDeque::new(),
+            Err(err) => return Err(err),
+        };
+        entries.extend(self.entries.drain(..));
+        while entries.len() > self.capacity {
+            entries.pop_front();
+        }
+        self.entries = entries;
+        self.file = Some(path.to_owned());
+        self.compact()
+    }
+
+    /// Add an entry, dropping the oldest one if the log is full
+    pub fn push(&mut self, entry: Entry) -> std::io::Result<()> {
+        if self.entries.len() == self.capacity {
+            self.entries.pop_front();
+        }
+        let line = entry.to_line();
+        self.entries.push_back(entry);
+        if let Some(path) = &self.file {
+            // Rewrite the file with only the kept entries, once it is twice too long
+            if self.file_lines >= 2 * self.capacity {
+                return self.compact();
+            }
+            let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+            writeln!(file, "{}", line)?;
+            self.file_lines += 1;
+        }
+        Ok(())
+    }
+
+    /// Get the entries, from the oldest to the newest
+    pub fn entries(&self) -> impl DoubleEndedIterator<Item = &Entry> {
+        self.entries.iter()
+    }
+
+    /// Rewrite the file with the entries in memory
+    fn compact(&mut self) -> std::io::Result<()> {
+        if let Some(path) = &self.file {
+            let mut file = File::create(path)?;
+            for entry in self.entries.iter() {
+                writeln!(file, "{}", entry.to_line())?;
+            }
+            self.file_lines = self.entries.len();
+        }
+        Ok(())
+    }
+}
+
+/// The logger of the g13 apps.
+///
+/// The records are printed on stderr and kept in the (on-disk) ring log.
+struct Logger;
+
+impl Log for Logger {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        metadata.level() <= log::max_level()
+    }
+
+    fn log(&self, record: &Record) {
+        if !self.enabled(record.metadata()) {
+            return;
+        }
+        let entry = Entry {
+            time: Local::now(),
+            level: record.level(),
+            target: record.target().to_owned(),
+            message: record.args().to_string(),
+        };
+        eprintln!("{}", entry);
+        if entry.level <= Level::Warn {
+            UNREAD.fetch_add(1, Ordering::Relaxed);
+        }
+        if let Ok(mut logs) = LOGS.lock() {
+            if let Err(err) = logs.push(entry) {
+                eprintln!("Can't write the log file: {}", err);
+            }
+        }
+    }
+
+    fn flush(&self) {}
+}
+
+/// Install the logger, and load the previous entries from the log file.
+///
+/// The level can be set with the `G13_LOG` env var (info by default).
+pub fn init() {
+    let level = std::env::var("G13_LOG")
+        .ok()
+        .and_then(|level| LevelFilter::from_str(&level).ok())
+        .unwrap_or(LevelFilter::Info);
+    if log::set_logger(&Logger).is_err() {
+        return;
+    }
+    log::set_max_level(level);
+    if let Some(path) = xdg::state_file("log") {
+        if let Ok(Err(err)) = LOGS.lock().map(|mut logs| logs.open(&path)) {
+            eprintln!("Can't open the log file {:?}: {}", path, err);
+        }
+    }
+}
+
+/// Get the recent log entries, from the newest to the oldest
+pub fn recent() -> Vec<Entry> {
+    LOGS.lock()
+        .map(|logs| logs.entries().rev().cloned().collect())
+        .unwrap_or_default()
+}
+
+/// Get the number of warnings and errors logged since they were read
+pub fn unread() -> usize {
+    UNREAD.load(Ordering::Relaxed)
+}
+
+/// Mark all the warnings and errors as read
+pub fn mark_read() {
+    UNREAD.store(0, Ordering::Relaxed);
+}
+
+#[cfg(test)]
+mod test {
+    use crate::logger::{Entry, RingLog};
+    use chrono::Local;
+    use log::Level;
+
+    fn entry(message: &str) -> Entry {
+        Entry {
+            time: Local::now(),
+            level: Level::Warn,
+            target: "g13_apps::app".to_owned(),
+            message: message.to_owned(),
+        }
+    }
+
+    /// The entries are serialized on a single line.
+    #[test]
+    fn line_round_trip() {
+        let entry = entry("DBus error\n\tcaused by: C:\\path");
+        let line = entry.to_line();
+        assert!(!line.contains('\n'));
+        let parsed = Entry::from_line(&line).expect("Should parse the line");
+        assert_eq!(parsed.message, entry.message);
+        assert_eq!(parsed.level, Level::Warn);
+        assert_eq!(parsed.time.timestamp(), entry.time.timestamp());
+        assert_eq!(Entry::from_line("not a log line"), None);
+    }
+
+    /// Only the last entries are kept.
+    #[test]
+    fn bounded_ring() {
+        let mut logs = RingLog::new(3);
+        for i in 0..5 {
+            logs.push(entry(&i.to_string())).unwrap();
+        }
+        let messages: Vec<&str> = logs.entries().map(|e| e.message.as_str()).collect();
+        assert_eq!(messages, vec!["2", "3", "4"]);
+    }
+
+    /// The entries are persisted and the file stays bounded.
+    #[test]
+    fn on_disk_ring() {
+        let path = std::env::temp_dir().join(format!("g13-apps-test-{}.log", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        let mut logs = RingLog::new(3);
+        logs.open(&path).unwrap();
+        for i in 0..10 {
+            logs.push(entry(&i.to_string())).unwrap();
+        }
+        let lines = std::fs::read_to_string(&path).unwrap().lines().count();
+        assert!(lines <= 6, "the file has {} lines", lines);
+
+        // Reload the file in a new log
+        let mut reloaded = RingLog::new(3);
+        reloaded.open(&path).unwrap();
+        let messages: Vec<&str> = reloaded.entries().map(|e| e.message.as_str()).collect();
+        assert_eq!(messages, vec!["7", "8", "9"]);
+        std::fs::remove_file(&path).unwrap();
+    }
+}
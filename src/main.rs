@@ -1,87 +1,331 @@
 use app::error::Error as ErrorApp;
+use app::AlarmRinging;
 use app::App;
 use app::Application;
-use error::Error;
+use app::KeyCheck;
+use app::Lock;
+use app::{Saver, Screensaver};
+use app::Timer;
+use driver::G13Driver;
+use error::{AppError, Error};
+use lock::{Combo, LockConfig};
+use profile::ProfileConfig;
+use protocol::{Key, Leds};
+use log::{debug, error, info, warn};
 use std::path::Path;
 use std::str::FromStr;
+use std::time::{Duration, Instant};
+use time::SystemTime;
 use tokio::fs::File;
 use tokio::fs::OpenOptions;
 use tokio::io::AsyncBufReadExt;
+use tokio::io::AsyncWrite;
 use tokio::io::BufReader;
 use tokio::io::BufWriter;
 use tokio::select;
 use tokio::stream::StreamExt;
 
+mod alarm;
 mod app;
+#[cfg(feature = "music")]
+mod bitmap;
 mod component;
+mod countdown;
 mod display;
+mod driver;
 mod error;
+mod life;
+mod lock;
+mod logger;
+mod profile;
+mod protocol;
+mod random;
+#[cfg(any(feature = "snake", feature = "tetris"))]
+mod score;
+mod settings;
 mod style;
+mod time;
+mod xdg;
 
 #[tokio::main]
 async fn main() {
-    println!("Hello, world!");
+    logger::init();
+    info!("g13-apps started");
 
-    // Open the G13 driver named pipes
-    let (input, mut output) = open_pipes(env!("G13_IN"), env!("G13_OUT"))
+    // Open the G13 driver named pipes (the commands are sent on their own handle,
+    // without waiting for the app drawing on the display)
+    let (input, mut output, mut commands) = open_pipes(env!("G13_IN"), env!("G13_OUT"))
         .await
         .expect("Can't open the communication pipe with the g13 driver!");
     let mut lines = input.lines();
 
-    // Start the menu app
-    let mut app = App::from_str("menu").expect("Menu app should exist!");
+    // Bind the keys of the g13 apps (unless the driver configuration is kept as is)
+    if settings::settings().install_binds {
+        if let Err(err) = G13Driver::new(&mut commands).bind_app_keys().await {
+            error!("Can't bind the keys of the g13 apps: {}", err);
+        }
+    }
+
+    // Turn off the mode LEDs left by a previous run
+    if let Err(err) = G13Driver::new(&mut commands).set_leds(Leds::default()).await {
+        error!("Can't reset the mode LEDs: {}", err);
+    }
+
+    // Switch the profiles with the active window (if configured)
+    profile::watch_windows();
+
+    // Lock with the session, the lock combo or when idle (if configured)
+    let lock_config = LockConfig::load();
+    let mut combo = Combo::new(lock_config.get_combo());
+    #[cfg(feature = "session")]
+    if lock_config.session {
+        lock::watch_session();
+    }
+
+    // Start the startup app (the menu by default)
+    let startup = settings::settings().startup.clone();
+    let mut app = App::from_str(&startup).unwrap_or_else(|_| {
+        warn!("Unknown startup app {:?}, opening the menu", startup);
+        App::from_str("menu").expect("Menu app should exist!")
+    });
+    // Warn if no key is received soon after the startup (if enabled)
+    let mut check_keys = settings::settings().check_keys;
+    // Check the alarms every second, even when another app is in foreground
+    let mut alarm_check = tokio::time::interval(Duration::from_secs(1));
+    // The last backlight color and mode LEDs sent to the driver
+    let mut indicators = Indicators::default();
+    // The phase of the blinking LEDs, switched every second
+    let mut blink = false;
+    // The last key pressed, for the screensaver
+    let mut last_key = Instant::now();
 
     // Wait for app to finish or user input (and do it forever)
     loop {
-        select! {
-            _app = app.execute(&mut output) => {
-                app = match _app {
-                    Ok(next) => next,
-                    Err(error) => show_error(error),
+        app::show_indicators(&app);
+        indicators.update(&mut commands, app.name(), blink).await;
+
+        // The app runs until it ends, a key is pressed or the display is taken over:
+        // the background checks don't restart it (the future borrows the app meanwhile)
+        let name = app.name();
+        let is_timer = matches!(app, App::Timer(_));
+        let can_idle = !matches!(
+            app,
+            App::Screensaver(_) | App::AlarmRinging(_) | App::KeyCheck(_) | App::Lock(_)
+        );
+        let mut execute = app.execute(&mut output);
+        let event = loop {
+            select! {
+                next = &mut execute => break Event::End(next),
+                _ = alarm_check.tick() => {
+                    blink = !blink;
+                    load_pending_profile(&mut commands).await;
+                    indicators.update(&mut commands, name, blink).await;
+                    // Follow the lock of the session, then take over the display if needed
+                    if let Some(locked) = lock::take_request() {
+                        break Event::Session(locked);
+                    } else if alarm::check(&SystemTime) {
+                        break Event::Alarm;
+                    } else if countdown::check(&SystemTime) && !is_timer {
+                        break Event::Countdown;
+                    } else if check_keys && last_key.elapsed() >= KeyCheck::TIMEOUT {
+                        break Event::NoKey;
+                    } else if can_idle && is_idle(last_key) {
+                        break Event::Idle;
+                    }
+                }
+                key = lines.next() => {
+                    if let Some(Ok(key)) = key {
+                        break Event::Key(key);
+                    }
+                }
+            }
+        };
+        drop(execute);
+
+        match event {
+            Event::End(Ok(next)) => {
+                debug!("{} app closed, opening {} app", app.name(), next.name());
+                app = next;
+            }
+            Event::End(Err(error)) => app = show_error(error, &mut app),
+            Event::Session(true) => lock_app(&mut app, &lock_config),
+            Event::Session(false) => {
+                // Even if the lock is covered, to be unlocked once shown again
+                if let Some(lock) = app.get_lock() {
+                    lock.unlock();
                 }
             }
-            key = lines.next() => {
-                if let Some(Ok(key)) = key {
-                    let rst = match key.as_str() {
-                        "BD" => app.button_bd().await,
-                        "L1" => app.button_l1().await,
-                        "L2" => app.button_l2().await,
-                        "L3" => app.button_l3().await,
-                        "L4" => app.button_l4().await,
-                        _ => Ok(()),
-                    };
-                    if let Err(error) = rst {
-                        app = show_error(error);
+            Event::Alarm => {
+                // Take over the display, the app is restored once the alarm stops
+                let previous = std::mem::replace(&mut app, App::Menu(Default::default()));
+                app = App::AlarmRinging(AlarmRinging::new(previous.wake()));
+            }
+            Event::Countdown => {
+                // Same for the end of a countdown (shown by the timer app itself if open)
+                let previous = std::mem::replace(&mut app, App::Menu(Default::default()));
+                app = App::Timer(Timer::over(previous.wake()));
+            }
+            Event::NoKey => {
+                // Same for the warning, until the first key
+                warn!("No key received from the driver, check its binds (see g13-apps.bind)");
+                check_keys = false;
+                let previous = std::mem::replace(&mut app, App::Menu(Default::default()));
+                app = App::KeyCheck(KeyCheck::new(previous));
+            }
+            Event::Idle if lock_config.is_idle_lock() => lock_app(&mut app, &lock_config),
+            Event::Idle => {
+                // Same for the screensaver, until the next key
+                let saver = settings::settings().screensaver.parse().unwrap_or(Saver::Stars);
+                let previous = std::mem::replace(&mut app, App::Menu(Default::default()));
+                app = App::Screensaver(Screensaver::new(previous, saver));
+            }
+            Event::Key(key) => {
+                last_key = Instant::now();
+                check_keys = false;
+                let rst = match key.parse() {
+                    Ok(key) if combo.press(key, Instant::now()) => {
+                        if lock_config.can_unlock() {
+                            lock_app(&mut app, &lock_config);
+                        } else {
+                            warn!("Not locked: no profile to restore the G keys after the PIN");
+                        }
+                        Ok(())
                     }
+                    Ok(Key::BD) => app.button_bd().await,
+                    Ok(Key::L(1)) => app.button_l1().await,
+                    Ok(Key::L(2)) => app.button_l2().await,
+                    Ok(Key::L(3)) => app.button_l3().await,
+                    Ok(Key::L(4)) => app.button_l4().await,
+                    Ok(Key::G(number)) => app.button_g(number).await,
+                    Ok(_) => Ok(()),
+                    Err(err) => {
+                        debug!("Ignored driver output: {}", err);
+                        Ok(())
+                    }
+                };
+                if let Err(error) = rst {
+                    app = show_error(error, &mut app);
                 }
             }
         }
     }
 }
 
+/// What interrupts the foreground app
+enum Event {
+    /// The app ended, with the app to open next
+    End(Result<App, AppError>),
+    /// A line read from the driver (a key)
+    Key(String),
+    /// The session was locked (true) or unlocked (false)
+    Session(bool),
+    /// An alarm starts to ring
+    Alarm,
+    /// The countdown is over
+    Countdown,
+    /// No key was received from the driver since the startup
+    NoKey,
+    /// No key was pressed for a while
+    Idle,
+}
+
+/// The backlight color and mode LEDs last sent to the driver
+#[derive(Default)]
+struct Indicators {
+    backlight: Option<[u8; 3]>,
+    leds: Option<Leds>,
+}
+
+impl Indicators {
+    /// Send the backlight color and the mode LEDs published by the foreground app if they changed
+    /// (the color configured for the app by default, the app LEDs over the background ones)
+    async fn update<W: AsyncWrite + Unpin>(&mut self, tx: &mut W, name: &str, blink: bool) {
+        // The bind file of a profile may have changed them
+        if profile::profiles().take_loaded() {
+            *self = Self::default();
+        }
+        let (backlight, leds) = app::indicators();
+        let color = backlight.unwrap_or_else(|| settings::settings().get_backlight(name));
+        if self.backlight != Some(color) {
+            match G13Driver::new(tx).set_backlight(color).await {
+                Ok(()) => self.backlight = Some(color),
+                Err(err) => error!("Can't set the backlight: {}", err),
+            }
+        }
+
+        let lit = leds.over(app::background_leds()).resolve(blink);
+        if self.leds != Some(lit) {
+            match G13Driver::new(tx).set_leds(lit).await {
+                Ok(()) => self.leds = Some(lit),
+                Err(err) => error!("Can't set the mode LEDs: {}", err),
+            }
+        }
+    }
+}
+
+/// Load the profile of the active window, if it changed
+async fn load_pending_profile<W: AsyncWrite + Unpin>(tx: &mut W) {
+    let pending = profile::profiles().take_pending();
+    if let Some(name) = pending {
+        let directory = ProfileConfig::load().get_directory().unwrap_or_default();
+        if let Err(err) = profile::load(tx, &directory, &name).await {
+            error!("Can't switch to the profile {}: {}", name, err);
+        }
+    }
+}
+
 /// Open the G13 named pipes given.
-/// Pipes are open in BufReader and BufWriter.
+/// Pipes are open in BufReader and BufWriter, the input pipe twice: for the display and the commands.
 ///
-/// path_in is path for the g13 input pipe
-/// and path_out is path for the g13 output pipe
+/// path_in is path for the g13 input pipe (written: the display and the commands)
+/// and path_out is path for the g13 output pipe (read: the keys)
 async fn open_pipes<P: AsRef<Path>, Q: AsRef<Path>>(
     path_in: P,
     path_out: Q,
-) -> Result<(BufReader<File>, BufWriter<File>), Error> {
-    let pipe_in = OpenOptions::new().write(true).open(path_in).await?;
-    let pipe_out = OpenOptions::new().read(true).open(path_out).await?;
-    let output = BufWriter::new(pipe_in);
-    let input = BufReader::new(pipe_out);
-    Ok((input, output))
+) -> Result<(BufReader<File>, BufWriter<File>, BufWriter<File>), Error> {
+    let driver_in = OpenOptions::new().write(true).open(path_in).await?;
+    let driver_out = OpenOptions::new().read(true).open(path_out).await?;
+    let keys = BufReader::new(driver_out);
+    // Each flush is a single write, smaller than the atomic size of a pipe
+    let commands = BufWriter::new(driver_in.try_clone().await?);
+    let display = BufWriter::new(driver_in);
+    Ok((keys, display, commands))
 }
 
-/// Print and create an Error app with the fiven error
-fn show_error<E: std::error::Error>(error: E) -> App {
-    eprintln!("Error: {}", error);
+/// Check if the screensaver should be started: no key pressed for a while
+/// (the screens waiting for a key are never covered)
+fn is_idle(last_key: Instant) -> bool {
+    let idle = settings::settings().get_idle();
+    idle.is_some_and(|idle| last_key.elapsed() >= idle)
+}
+
+/// Lock the g13 apps, over the foreground app (unless already locked, even if covered).
+/// Without profile to restore the G keys after the PIN, only the session unlocks.
+fn lock_app(app: &mut App, config: &LockConfig) {
+    if app.get_lock().is_none() {
+        info!("Locked");
+        let pin = if config.can_unlock() {
+            config.get_pin()
+        } else {
+            Vec::new()
+        };
+        let previous = std::mem::replace(app, App::Menu(Default::default()));
+        *app = App::Lock(Lock::new(previous.wake(), pin));
+    }
+}
+
+/// Log and create an Error app with the given error, raised by the given app
+/// (if locked, the error is only logged and the lock screen shown again)
+fn show_error<E: std::error::Error>(error: E, origin: &mut App) -> App {
+    let mut message = format!("{} failed: {}", origin.name(), error);
     let mut source : Option<&(dyn std::error::Error + 'static)> = error.source();
     while let Some(&err) = source.as_ref() {
-        eprintln!("source: {}", err);
+        message.push_str(&format!("\nCaused by: {}", err));
         source = err.source();
     }
-    App::ErrorApp(ErrorApp::new(error))
+    error!("{}", message);
+    if let Some(lock) = origin.take_lock() {
+        return lock;
+    }
+    App::ErrorApp(ErrorApp::from_error(&error).with_origin(origin))
 }
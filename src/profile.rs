@@ -0,0 +1,296 @@
+use crate::protocol::{self, Message, APP_KEYS};
+use crate::settings;
+use crate::xdg;
+use log::{error, info, warn};
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::io;
+use std::marker::Unpin;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, MutexGuard, PoisonError};
+use std::time::Duration;
+use thiserror::Error;
+use tokio::io::AsyncWrite;
+
+/// The extension of the bind files
+const EXTENSION: &str = "bind";
+
+/// The profiles: the loaded one, and the one to load for the active window
+static PROFILES: Lazy<Mutex<Profiles>> = Lazy::new(Default::default);
+
+/// All errors that can be produced while loading a profile
+#[derive(Error, Debug)]
+pub enum ProfileError {
+    /// Represents an error while reading the bind file or sending it to the driver
+    #[error("can't load the profile")]
+    Io(#[from] io::Error),
+
+    /// Represents a profile without bind file
+    #[error("no bind file for the profile {0}")]
+    NotFound(String),
+}
+
+/// The content of the profiles file
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq)]
+#[serde(default)]
+pub struct ProfileConfig {
+    /// The directory of the bind files ("~/" is the home directory),
+    /// by default `$XDG_CONFIG_HOME/g13-apps/profiles`
+    pub directory: Option<String>,
+    /// The command printing the class of the active window (e.g. `xdotool getactivewindow getwindowclassname`).
+    /// Without it, the profiles are not switched automatically.
+    pub window_command: Option<String>,
+    /// The profile of each window class
+    pub windows: BTreeMap<String, String>,
+    /// The profile of the other window classes (if none, the profile is kept)
+    pub fallback: Option<String>,
+}
+
+impl ProfileConfig {
+    /// Load the profiles configuration (from `$XDG_CONFIG_HOME/g13-apps/profiles.toml`)
+    pub fn load() -> Self {
+        let path = match xdg::config_file("profiles.toml") {
+            Some(path) => path,
+            None => return Self::default(),
+        };
+        settings::read_toml(&path, "profiles")
+    }
+
+    /// Get the directory of the bind files
+    pub fn get_directory(&self) -> Option<PathBuf> {
+        match &self.directory {
+            Some(directory) => xdg::expand_home(directory),
+            None => xdg::config_file("profiles"),
+        }
+    }
+
+    /// Get the profile of a window class
+    pub fn profile_for(&self, class: &str) -> Option<&str> {
+        self.windows
+            .get(class)
+            .or(self.fallback.as_ref())
+            .map(String::as_str)
+    }
+}
+
+/// The state of the profiles
+#[derive(Debug, Default)]
+pub struct Profiles {
+    /// The profile loaded by the g13 apps
+    active: Option<String>,
+    /// The profile to load, for the active window
+    pending: Option<String>,
+    /// Whether a profile was loaded since the indicators were last sent
+    /// (its `rgb` and `mod` lines may have changed the backlight and the mode LEDs)
+    loaded: bool,
+}
+
+impl Profiles {
+    /// Get the profile loaded by the g13 apps
+    pub fn get_active(&self) -> Option<&str> {
+        self.active.as_deref()
+    }
+
+    /// Ask for a profile to be loaded (unless already loaded)
+    pub fn switch(&mut self, name: &str) {
+        if self.active.as_deref() != Some(name) {
+            self.pending = Some(name.to_owned());
+        }
+    }
+
+    /// Take the profile to load
+    pub fn take_pending(&mut self) -> Option<String> {
+        self.pending.take()
+    }
+
+    /// Check if a profile was loaded since the last call
+    pub fn take_loaded(&mut self) -> bool {
+        std::mem::take(&mut self.loaded)
+    }
+}
+
+/// Get the state of the profiles
+pub fn profiles() -> MutexGuard<'static, Profiles> {
+    PROFILES.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+/// List the profiles of a directory: the names of its bind files, sorted
+pub fn list(directory: &Path) -> Vec<String> {
+    let mut names: Vec<String> = match fs::read_dir(directory) {
+        Ok(entries) => entries
+            .filter_map(|entry| entry.ok())
+            .map(|entry| entry.path())
+            .filter(|path| {
+                path.extension()
+                    .is_some_and(|extension| extension == EXTENSION)
+            })
+            .filter_map(|path| Some(path.file_stem()?.to_str()?.to_owned()))
+            .collect(),
+        Err(err) => {
+            warn!("Can't read the profiles directory {:?}: {}", directory, err);
+            Vec::new()
+        }
+    };
+    names.sort();
+    names
+}
+
+/// Make the messages loading a profile from its bind file.
+///
+/// The invalid lines and the bindings of the keys used by the g13 apps are skipped,
+/// then these keys are bound again (the new profile may not have them).
+pub fn messages(name: &str, content: &str) -> Vec<Message<'static>> {
+    let mut messages = vec![Message::Profile(name.to_owned())];
+    for line in content.lines().map(str::trim) {
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        match line.parse() {
+            Ok(Message::Bind { key, .. }) if APP_KEYS.contains(&key) => {
+                warn!(
+                    "The {} key of the profile {} is kept for the g13 apps",
+                    key, name
+                );
+            }
+            Ok(message) => messages.push(message),
+            Err(err) => warn!("Skipped line of the profile {} ({:?}): {}", name, line, err),
+        }
+    }
+    messages.extend(APP_KEYS.iter().map(|key| Message::bind_output(*key)));
+    messages
+}
+
+/// Load a profile of the directory, sending its bind file to the driver
+pub async fn load<W: Unpin + AsyncWrite>(
+    tx: &mut W,
+    directory: &Path,
+    name: &str,
+) -> Result<(), ProfileError> {
+    let path = directory.join(name).with_extension(EXTENSION);
+    let content = match fs::read_to_string(&path) {
+        Err(err) if err.kind() == io::ErrorKind::NotFound => {
+            return Err(ProfileError::NotFound(name.to_owned()))
+        }
+        content => content?,
+    };
+    for message in messages(name, &content) {
+        protocol::send(tx, &message).await?;
+    }
+    info!("Profile {} loaded", name);
+    let mut profiles = profiles();
+    profiles.active = Some(name.to_owned());
+    profiles.loaded = true;
+    Ok(())
+}
+
+/// Watch the active window (if a window command is configured),
+/// asking for its profile to be loaded each time its class changes.
+pub fn watch_windows() {
+    let config = ProfileConfig::load();
+    let command = match config.window_command.clone() {
+        Some(command) => command,
+        None => return,
+    };
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(Duration::from_secs(1));
+        let mut last_class = String::new();
+        loop {
+            interval.tick().await;
+            let output = tokio::process::Command::new("sh")
+                .arg("-c")
+                .arg(&command)
+                .output()
+                .await;
+            let class = match output {
+                Ok(output) if output.status.success() => {
+                    String::from_utf8_lossy(&output.stdout).trim().to_owned()
+                }
+                // No active window, or no X session (yet)
+                Ok(_) => continue,
+                Err(err) => {
+                    error!("Can't run the window command {:?}: {}", command, err);
+                    return;
+                }
+            };
+            if class != last_class {
+                if let Some(profile) = config.profile_for(&class) {
+                    profiles().switch(profile);
+                }
+                last_class = class;
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod test {
+    use crate::profile::{self, list, messages, ProfileConfig, Profiles};
+    use crate::protocol::{Key, Message};
+    use std::path::Path;
+
+    /// The bind files of the directory are listed.
+    #[test]
+    fn list_profiles() {
+        assert_eq!(
+            list(Path::new("tests/fixtures/profiles")),
+            vec!["games", "office"]
+        );
+    }
+
+    /// The keys of the g13 apps are kept, the invalid lines are skipped.
+    #[test]
+    fn profile_messages() {
+        let content = std::fs::read_to_string("tests/fixtures/profiles/games.bind").unwrap();
+        let messages = messages("games", &content);
+        assert_eq!(messages[0], Message::Profile("games".to_owned()));
+        assert_eq!(
+            messages[1],
+            Message::Bind {
+                key: Key::G(4),
+                action: "KEY_A".to_owned()
+            }
+        );
+        assert_eq!(messages[5], Message::Backlight([255, 0, 0]));
+        // Then the keys of the g13 apps
+        assert_eq!(messages.len(), 6 + 5);
+        assert_eq!(messages[6], Message::bind_output(Key::L(1)));
+        assert_eq!(messages[10], Message::bind_output(Key::BD));
+    }
+
+    /// A loaded profile is active, and its changes of the indicators are noticed once.
+    #[test]
+    fn load_profile() {
+        let mut rt = tokio::runtime::Runtime::new().unwrap();
+        let mut sent = Vec::new();
+        let directory = Path::new("tests/fixtures/profiles");
+        rt.block_on(profile::load(&mut sent, directory, "games"))
+            .unwrap();
+        assert!(String::from_utf8(sent).unwrap().contains("rgb 255 0 0"));
+        let mut profiles = profile::profiles();
+        assert_eq!(profiles.get_active(), Some("games"));
+        assert!(profiles.take_loaded());
+        assert!(!profiles.take_loaded());
+    }
+
+    /// The profiles follow the active window, when it changes.
+    #[test]
+    fn window_profiles() {
+        let config: ProfileConfig = toml::from_str(
+            "window_command = \"xdotool getactivewindow getwindowclassname\"\n[windows]\nSteam = \"games\"\n",
+        )
+        .unwrap();
+        assert_eq!(config.profile_for("Steam"), Some("games"));
+        assert_eq!(config.profile_for("Firefox"), None);
+
+        let mut profiles = Profiles::default();
+        profiles.switch("games");
+        assert_eq!(profiles.take_pending().as_deref(), Some("games"));
+        assert_eq!(profiles.take_pending(), None);
+        // Already loaded
+        profiles.active = Some("games".to_owned());
+        profiles.switch("games");
+        assert_eq!(profiles.take_pending(), None);
+    }
+}
@@ -0,0 +1,286 @@
+use std::fmt;
+use std::io;
+use std::marker::Unpin;
+use std::str::FromStr;
+use thiserror::Error;
+use tokio::io::AsyncWrite;
+use tokio::io::AsyncWriteExt;
+
+/// The size of the LCD framebuffer: 160 columns of 48 rows, 8 rows per byte
+pub const FRAMEBUFFER_SIZE: usize = 20 * 48;
+
+/// The keys bound by the g13 apps: their name is written on the output pipe when pressed
+pub const APP_KEYS: [Key; 5] = [Key::L(1), Key::L(2), Key::L(3), Key::L(4), Key::BD];
+
+/// All errors that can be produced while parsing the driver messages
+#[derive(Error, Debug, PartialEq)]
+pub enum ProtocolError {
+    /// Represents a key name unknown by the driver
+    #[error("unknown key {0:?}")]
+    UnknownKey(String),
+
+    /// Represents a command unknown (or not handled) by the g13 apps
+    #[error("unknown command {0:?}")]
+    UnknownCommand(String),
+
+    /// Represents a command with missing or invalid arguments
+    #[error("invalid arguments for the {0} command")]
+    InvalidArguments(&'static str),
+}
+
+/// A key of the G13, as named by the driver
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Key {
+    /// A macro key, from G1 to G22
+    G(u8),
+    /// A key under the LCD, from L1 to L4
+    L(u8),
+    /// A mode key, from M1 to M3
+    M(u8),
+    /// The macro record key
+    MR,
+    /// The round key left of the LCD
+    BD,
+    /// The keys around the stick
+    Left,
+    Down,
+    Top,
+    /// The backlight key
+    Light,
+}
+
+impl FromStr for Key {
+    type Err = ProtocolError;
+
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        let unknown = || ProtocolError::UnknownKey(name.to_owned());
+        let numbered = |prefix: &str, max: u8| -> Option<u8> {
+            let number: u8 = name.strip_prefix(prefix)?.parse().ok()?;
+            Some(number).filter(|number| (1..=max).contains(number))
+        };
+        match name {
+            "MR" => Ok(Key::MR),
+            "BD" => Ok(Key::BD),
+            "LEFT" => Ok(Key::Left),
+            "DOWN" => Ok(Key::Down),
+            "TOP" => Ok(Key::Top),
+            "LIGHT" => Ok(Key::Light),
+            _ => numbered("G", 22)
+                .map(Key::G)
+                .or_else(|| numbered("L", 4).map(Key::L))
+                .or_else(|| numbered("M", 3).map(Key::M))
+                .ok_or_else(unknown),
+        }
+    }
+}
+
+impl fmt::Display for Key {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Key::G(number) => write!(f, "G{}", number),
+            Key::L(number) => write!(f, "L{}", number),
+            Key::M(number) => write!(f, "M{}", number),
+            Key::MR => write!(f, "MR"),
+            Key::BD => write!(f, "BD"),
+            Key::Left => write!(f, "LEFT"),
+            Key::Down => write!(f, "DOWN"),
+            Key::Top => write!(f, "TOP"),
+            Key::Light => write!(f, "LIGHT"),
+        }
+    }
+}
+
+/// The mode LEDs of the G13, above the M1, M2, M3 and MR keys
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Leds {
+    pub m1: bool,
+    pub m2: bool,
+    pub m3: bool,
+    pub mr: bool,
+}
+
+impl Leds {
+    /// Get the mask of the lit LEDs, as expected by the driver
+    pub fn mask(self) -> u8 {
+        self.m1 as u8 | (self.m2 as u8) << 1 | (self.m3 as u8) << 2 | (self.mr as u8) << 3
+    }
+
+    /// Get the LEDs lit by a mask
+    pub fn from_mask(mask: u8) -> Self {
+        Leds {
+            m1: mask & 1 != 0,
+            m2: mask & 2 != 0,
+            m3: mask & 4 != 0,
+            mr: mask & 8 != 0,
+        }
+    }
+}
+
+/// A message sent to the G13 driver, on its input pipe
+#[derive(Clone, Debug, PartialEq)]
+pub enum Message<'a> {
+    /// The LCD framebuffer (an LPBM image), sent as is
+    Framebuffer(&'a [u8; FRAMEBUFFER_SIZE]),
+    /// Set the color of the keyboard backlight (`rgb R G B`)
+    Backlight([u8; 3]),
+    /// Set the mode LEDs, the others are turned off (`mod MASK`)
+    Leds(Leds),
+    /// Bind a key to an action of the current profile (`bind KEY ACTION`),
+    /// e.g. `>L1` to write "L1" on the output pipe
+    Bind { key: Key, action: String },
+    /// Switch to a profile, created if needed (`profile NAME`)
+    Profile(String),
+}
+
+impl Message<'_> {
+    /// Make the binding of a key writing its name on the output pipe, as needed by the g13 apps
+    pub fn bind_output(key: Key) -> Message<'static> {
+        Message::Bind {
+            key,
+            action: format!(">{}", key),
+        }
+    }
+
+    /// Serialize the message: the raw framebuffer, or a line of text for the commands
+    pub fn to_bytes(&self) -> Vec<u8> {
+        match self {
+            Message::Framebuffer(framebuffer) => framebuffer.to_vec(),
+            Message::Backlight([red, green, blue]) => {
+                format!("rgb {} {} {}\n", red, green, blue).into_bytes()
+            }
+            Message::Leds(leds) => format!("mod {}\n", leds.mask()).into_bytes(),
+            Message::Bind { key, action } => format!("bind {} {}\n", key, action).into_bytes(),
+            Message::Profile(name) => format!("profile {}\n", name).into_bytes(),
+        }
+    }
+}
+
+impl FromStr for Message<'static> {
+    type Err = ProtocolError;
+
+    /// Parse a text command, as written in a bind file
+    fn from_str(line: &str) -> Result<Self, Self::Err> {
+        let mut words = line.split_whitespace();
+        let command = words.next().unwrap_or_default();
+        match command {
+            "rgb" => {
+                let channels: Vec<u8> = words
+                    .map(str::parse)
+                    .collect::<Result<_, _>>()
+                    .map_err(|_| ProtocolError::InvalidArguments("rgb"))?;
+                match channels.as_slice() {
+                    [red, green, blue] => Ok(Message::Backlight([*red, *green, *blue])),
+                    _ => Err(ProtocolError::InvalidArguments("rgb")),
+                }
+            }
+            "mod" => match (words.next().map(str::parse), words.next()) {
+                (Some(Ok(mask)), None) if mask < 16 => Ok(Message::Leds(Leds::from_mask(mask))),
+                _ => Err(ProtocolError::InvalidArguments("mod")),
+            },
+            "bind" => {
+                let key = words
+                    .next()
+                    .ok_or(ProtocolError::InvalidArguments("bind"))?
+                    .parse()?;
+                let action = words.collect::<Vec<_>>().join(" ");
+                if action.is_empty() {
+                    return Err(ProtocolError::InvalidArguments("bind"));
+                }
+                Ok(Message::Bind { key, action })
+            }
+            "profile" => match (words.next(), words.next()) {
+                (Some(name), None) => Ok(Message::Profile(name.to_owned())),
+                _ => Err(ProtocolError::InvalidArguments("profile")),
+            },
+            _ => Err(ProtocolError::UnknownCommand(command.to_owned())),
+        }
+    }
+}
+
+/// Send a message to the driver (and flush it)
+pub async fn send<W: Unpin + AsyncWrite>(tx: &mut W, message: &Message<'_>) -> io::Result<()> {
+    tx.write_all(&message.to_bytes()).await?;
+    tx.flush().await
+}
+
+#[cfg(test)]
+mod test {
+    use crate::protocol::{Key, Leds, Message, ProtocolError, FRAMEBUFFER_SIZE};
+
+    /// The keys are named as by the driver.
+    #[test]
+    fn key_names() {
+        assert_eq!("L1".parse(), Ok(Key::L(1)));
+        assert_eq!("G22".parse(), Ok(Key::G(22)));
+        assert_eq!("BD".parse(), Ok(Key::BD));
+        assert_eq!(Key::M(3).to_string(), "M3");
+        assert_eq!(Key::Left.to_string(), "LEFT");
+        for name in ["L5", "G0", "M", "l1", "KEY_A"].iter() {
+            assert_eq!(
+                name.parse::<Key>(),
+                Err(ProtocolError::UnknownKey(name.to_string()))
+            );
+        }
+    }
+
+    /// The commands are lines of text, the framebuffer is sent raw.
+    #[test]
+    fn serialize_messages() {
+        let framebuffer = [0xAA; FRAMEBUFFER_SIZE];
+        assert_eq!(
+            Message::Framebuffer(&framebuffer).to_bytes(),
+            framebuffer.to_vec()
+        );
+        assert_eq!(
+            Message::Backlight([255, 128, 0]).to_bytes(),
+            b"rgb 255 128 0\n".to_vec()
+        );
+        let leds = Leds {
+            m2: true,
+            mr: true,
+            ..Default::default()
+        };
+        assert_eq!(Message::Leds(leds).to_bytes(), b"mod 10\n".to_vec());
+        assert_eq!(
+            Message::bind_output(Key::L(1)).to_bytes(),
+            b"bind L1 >L1\n".to_vec()
+        );
+        assert_eq!(
+            Message::Profile("games".to_owned()).to_bytes(),
+            b"profile games\n".to_vec()
+        );
+    }
+
+    /// The text commands are parsed back, the invalid ones are errors.
+    #[test]
+    fn parse_commands() {
+        for line in [
+            "rgb 0 64 255",
+            "mod 5",
+            "bind G1 KEY_LEFTCTRL+KEY_C",
+            "profile games",
+        ]
+        .iter()
+        {
+            let message: Message = line.parse().unwrap();
+            let serialized = String::from_utf8(message.to_bytes()).unwrap();
+            assert_eq!(serialized.trim_end(), *line);
+        }
+        assert_eq!(
+            "rgb 0 256 0".parse::<Message>(),
+            Err(ProtocolError::InvalidArguments("rgb"))
+        );
+        assert_eq!(
+            "bind L1".parse::<Message>(),
+            Err(ProtocolError::InvalidArguments("bind"))
+        );
+        assert_eq!(
+            "bind X1 >X1".parse::<Message>(),
+            Err(ProtocolError::UnknownKey("X1".to_owned()))
+        );
+        assert_eq!(
+            "stickmode KEYS".parse::<Message>(),
+            Err(ProtocolError::UnknownCommand("stickmode".to_owned()))
+        );
+    }
+}
@@ -0,0 +1,64 @@
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// The state used instead of a zero seed (a xorshift generator would only give zeros)
+const DEFAULT_SEED: u64 = 0x9E37_79B9_7F4A_7C15;
+
+/// A xorshift pseudo-random generator, for the animations and the games.
+///
+/// It is not secure, but a given seed always gives the same numbers, so the tests can replay them.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Rng {
+    state: u64,
+}
+
+impl Rng {
+    /// Create a generator from the given seed
+    pub fn new(seed: u64) -> Self {
+        Rng {
+            state: if seed == 0 { DEFAULT_SEED } else { seed },
+        }
+    }
+
+    /// Create a generator seeded by the current time
+    pub fn from_time() -> Self {
+        let seed = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|elapsed| elapsed.as_nanos() as u64)
+            .unwrap_or_default();
+        Self::new(seed)
+    }
+
+    /// Get the next random number
+    pub fn next_u64(&mut self) -> u64 {
+        let mut x = self.state;
+        x ^= x << 13;
+        x ^= x >> 7;
+        x ^= x << 17;
+        self.state = x;
+        x
+    }
+
+    /// Get a random number in `0..bound` (the bound must not be zero)
+    pub fn below(&mut self, bound: u32) -> u32 {
+        (self.next_u64() % bound as u64) as u32
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::random::Rng;
+
+    /// A seed always gives the same numbers, within the bounds.
+    #[test]
+    fn seeded() {
+        let numbers = |seed| {
+            let mut rng = Rng::new(seed);
+            (0..100).map(|_| rng.below(10)).collect::<Vec<_>>()
+        };
+        assert_eq!(numbers(42), numbers(42));
+        assert_ne!(numbers(42), numbers(43));
+        assert!(numbers(42).iter().all(|number| *number < 10));
+        // Even a zero seed
+        assert!(numbers(0).iter().any(|number| *number != 0));
+    }
+}
@@ -0,0 +1,113 @@
+use crate::xdg;
+use log::warn;
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+
+/// The number of high scores kept for each game
+const KEPT: usize = 5;
+
+/// The high scores of a game, best first.
+///
+/// They are saved in `$XDG_STATE_HOME/g13-apps/<game>.scores`, one score per line.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct HighScores {
+    /// The path of the scores file (None if unknown)
+    path: Option<PathBuf>,
+    scores: Vec<u32>,
+}
+
+impl HighScores {
+    /// Load the high scores of a game (none if not played yet)
+    pub fn load(game: &str) -> Self {
+        let path = xdg::state_file(&format!("{}.scores", game));
+        let scores = match path.as_ref().map(fs::read_to_string) {
+            Some(Ok(content)) => parse(&content),
+            Some(Err(err)) if err.kind() != io::ErrorKind::NotFound => {
+                warn!("Can't read the {} scores: {}", game, err);
+                Vec::new()
+            }
+            _ => Vec::new(),
+        };
+        HighScores { path, scores }
+    }
+
+    /// Get the high scores, best first
+    pub fn get_scores(&self) -> &[u32] {
+        &self.scores
+    }
+
+    /// Get the best score (0 if not played yet)
+    pub fn best(&self) -> u32 {
+        self.scores.first().copied().unwrap_or_default()
+    }
+
+    /// Add a score, getting its rank (from 0) if it is kept
+    pub fn add(&mut self, score: u32) -> Option<usize> {
+        if score == 0 {
+            return None;
+        }
+        let rank = self
+            .scores
+            .iter()
+            .take_while(|kept| **kept >= score)
+            .count();
+        if rank >= KEPT {
+            return None;
+        }
+        self.scores.insert(rank, score);
+        self.scores.truncate(KEPT);
+        Some(rank)
+    }
+
+    /// Save the high scores
+    pub fn save(&self) -> io::Result<()> {
+        let path = match &self.path {
+            Some(path) => path,
+            None => return Ok(()),
+        };
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        let content: String = self
+            .scores
+            .iter()
+            .map(|score| format!("{}\n", score))
+            .collect();
+        fs::write(path, content)
+    }
+}
+
+/// Parse the content of a scores file, skipping the invalid lines
+fn parse(content: &str) -> Vec<u32> {
+    let mut scores: Vec<u32> = content
+        .lines()
+        .filter_map(|line| line.trim().parse().ok())
+        .collect();
+    scores.sort_unstable_by(|a, b| b.cmp(a));
+    scores.truncate(KEPT);
+    scores
+}
+
+#[cfg(test)]
+mod test {
+    use crate::score::{parse, HighScores};
+
+    /// The best scores are kept, best first.
+    #[test]
+    fn high_scores() {
+        let mut scores = HighScores {
+            path: None,
+            scores: parse("12\n40\ninvalid\n7\n"),
+        };
+        assert_eq!(scores.get_scores(), &[40, 12, 7]);
+        assert_eq!(scores.add(20), Some(1));
+        assert_eq!(scores.add(0), None);
+        assert_eq!(scores.add(50), Some(0));
+        assert_eq!(scores.add(10), Some(4));
+        assert_eq!(scores.add(1), None);
+        assert_eq!(scores.get_scores(), &[50, 40, 20, 12, 10]);
+        assert_eq!(scores.best(), 50);
+        assert_eq!(HighScores::default().best(), 0);
+    }
+}
@@ -0,0 +1,208 @@
+use crate::xdg;
+use log::{error, warn};
+use once_cell::sync::Lazy;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::io;
+use std::path::Path;
+use std::sync::{Mutex, MutexGuard, PoisonError};
+use std::time::Duration;
+use thiserror::Error;
+
+/// The name of the settings app, which can't be hidden
+pub const SETTINGS_APP: &str = "settings";
+
+/// The colors proposed for the keyboard backlight
+pub const COLORS: &[(&str, [u8; 3])] = &[
+    ("White", [255, 255, 255]),
+    ("Red", [255, 0, 0]),
+    ("Orange", [255, 128, 0]),
+    ("Yellow", [255, 255, 0]),
+    ("Green", [0, 255, 0]),
+    ("Cyan", [0, 255, 255]),
+    ("Blue", [0, 0, 255]),
+    ("Purple", [128, 0, 255]),
+    ("Pink", [255, 0, 128]),
+    ("Off", [0, 0, 0]),
+];
+
+/// The settings, shared by the settings app and the apps they change
+static SETTINGS: Lazy<Mutex<Settings>> = Lazy::new(|| {
+    Mutex::new(match xdg::config_file("settings.toml") {
+        Some(path) => Settings::load(&path),
+        None => Settings::default(),
+    })
+});
+
+/// All errors that can be produced while saving the settings
+#[derive(Error, Debug)]
+pub enum SettingsError {
+    /// Represents an error while writing a settings file
+    #[error("can't write the settings file")]
+    Io(#[from] io::Error),
+
+    /// Represents an error while serializing the settings
+    #[error("can't serialize the settings")]
+    Serialize(#[from] toml::ser::Error),
+}
+
+/// The content of the settings file
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(default)]
+pub struct Settings {
+    /// The app opened at startup
+    pub startup: String,
+    /// The refresh interval of the list based apps (in milliseconds)
+    pub refresh: u64,
+    /// The color of the keyboard backlight (red, green, blue)
+    pub backlight: [u8; 3],
+    /// The apps hidden from the menu
+    pub hidden: Vec<String>,
+    /// The delay without any key pressed before the screensaver (in minutes, 0 for never)
+    pub idle: u64,
+    /// The animation of the screensaver
+    pub screensaver: String,
+    /// Bind the keys used by the g13 apps at startup (instead of the driver configuration)
+    pub install_binds: bool,
+    /// Warn if no key is received soon after the startup
+    pub check_keys: bool,
+    /// The dithering of the album arts
+    pub dithering: String,
+    /// The backlight colors of some apps, instead of the default one
+    pub colors: BTreeMap<String, [u8; 3]>,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            startup: "menu".to_owned(),
+            refresh: 100,
+            backlight: [255, 255, 255],
+            hidden: Vec::new(),
+            idle: 10,
+            screensaver: "stars".to_owned(),
+            install_binds: true,
+            check_keys: true,
+            dithering: "floyd_steinberg".to_owned(),
+            colors: BTreeMap::new(),
+        }
+    }
+}
+
+impl Settings {
+    /// Load the settings from the given file (or the default ones)
+    pub fn load(path: &Path) -> Self {
+        read_toml(path, "settings")
+    }
+
+    /// Save the settings (in `$XDG_CONFIG_HOME/g13-apps/settings.toml`)
+    pub fn save(&self) -> Result<(), SettingsError> {
+        match xdg::config_file("settings.toml") {
+            Some(path) => write_toml(&path, self),
+            None => Ok(()),
+        }
+    }
+
+    /// Get the refresh interval of the list based apps
+    pub fn get_refresh(&self) -> Duration {
+        // Too short, the driver can't keep up
+        Duration::from_millis(self.refresh.max(20))
+    }
+
+    /// Get the delay without any key pressed before the screensaver (None if disabled)
+    pub fn get_idle(&self) -> Option<Duration> {
+        match self.idle {
+            0 => None,
+            minutes => Some(Duration::from_secs(60 * minutes)),
+        }
+    }
+
+    /// Get the backlight color of an app
+    pub fn get_backlight(&self, app: &str) -> [u8; 3] {
+        self.colors.get(app).copied().unwrap_or(self.backlight)
+    }
+
+    /// Check if an app is hidden from the menu (the settings app never is)
+    pub fn is_hidden(&self, name: &str) -> bool {
+        name != SETTINGS_APP && self.hidden.iter().any(|hidden| hidden == name)
+    }
+}
+
+/// Get the name of a color (its hexadecimal code if not proposed)
+pub fn color_name(color: [u8; 3]) -> String {
+    match COLORS.iter().find(|(_, rgb)| *rgb == color) {
+        Some((name, _)) => (*name).to_owned(),
+        None => format!("#{:02X}{:02X}{:02X}", color[0], color[1], color[2]),
+    }
+}
+
+/// Read a configuration file, named by its kind in the logs.
+/// A missing file gives the default configuration, like an invalid one (which is logged).
+pub fn read_toml<T: DeserializeOwned + Default>(path: &Path, kind: &str) -> T {
+    match fs::read_to_string(path) {
+        Ok(content) => toml::from_str(&content).unwrap_or_else(|err| {
+            error!("Invalid {} file {:?}: {}", kind, path, err);
+            T::default()
+        }),
+        Err(err) => {
+            if err.kind() != io::ErrorKind::NotFound {
+                warn!("Can't read the {} file {:?}: {}", kind, path, err);
+            }
+            T::default()
+        }
+    }
+}
+
+/// Write a configuration file (and its directory)
+pub fn write_toml<T: Serialize>(path: &Path, content: &T) -> Result<(), SettingsError> {
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    fs::write(path, toml::to_string(content)?)?;
+    Ok(())
+}
+
+/// Get the settings of the g13 apps
+pub fn settings() -> MutexGuard<'static, Settings> {
+    SETTINGS.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+/// Get the refresh interval of the list based apps
+pub fn refresh() -> Duration {
+    settings().get_refresh()
+}
+
+#[cfg(test)]
+mod test {
+    use crate::settings::{color_name, Settings};
+    use std::time::Duration;
+
+    /// The settings file is a readable TOML file, with defaults.
+    #[test]
+    fn settings_file() {
+        let settings: Settings = toml::from_str(
+            "startup = \"clock\"\nhidden = [\"logs\", \"settings\"]\n[colors]\nclock = [0, 0, 255]\n",
+        )
+        .unwrap();
+        assert_eq!(settings.startup, "clock");
+        assert_eq!(settings.get_refresh(), Duration::from_millis(100));
+        assert!(settings.is_hidden("logs"));
+        // Else it could never be shown again
+        assert!(!settings.is_hidden("settings"));
+        assert_eq!(settings.get_backlight("clock"), [0, 0, 255]);
+        assert_eq!(settings.get_backlight("logs"), [255, 255, 255]);
+        assert!(settings.install_binds && settings.check_keys);
+        assert_eq!(settings.get_idle(), Some(Duration::from_secs(600)));
+        let saved = toml::to_string(&settings).unwrap();
+        assert_eq!(toml::from_str::<Settings>(&saved).unwrap(), settings);
+    }
+
+    /// The proposed colors are named.
+    #[test]
+    fn colors() {
+        assert_eq!(color_name([255, 128, 0]), "Orange");
+        assert_eq!(color_name([18, 52, 86]), "#123456");
+    }
+}
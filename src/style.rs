@@ -5,6 +5,132 @@ use embedded_graphics::style::{
 };
 use ibm437::*;
 use once_cell::sync::Lazy;
+use unicode_normalization::char::is_combining_mark;
+use unicode_normalization::UnicodeNormalization;
+
+/// All the printable glyphs of the IBM437 code page (in the code page order).
+const IBM437_GLYPHS: &str = concat!(
+    "\u{263A}\u{263B}\u{2665}\u{2666}\u{2663}\u{2660}\u{2022}\u{25D8}",
+    "\u{25CB}\u{25D9}\u{2642}\u{2640}\u{266A}\u{266B}\u{263C}\u{25BA}",
+    "\u{25C4}\u{2195}\u{203C}\u{00B6}\u{00A7}\u{25AC}\u{21A8}\u{2191}",
+    "\u{2193}\u{2192}\u{2190}\u{221F}\u{2194}\u{25B2}\u{25BC}",
+    " !\"#$%&'()*+,-./0123456789:;<=>?@ABCDEFGHIJKLMNOPQRSTUVWXYZ[\\]^_`",
+    "abcdefghijklmnopqrstuvwxyz{|}~\u{2302}",
+    "\u{C7}\u{FC}\u{E9}\u{E2}\u{E4}\u{E0}\u{E5}\u{E7}\u{EA}\u{EB}\u{E8}\u{EF}\u{EE}\u{EC}\u{C4}\u{C5}",
+    "\u{C9}\u{E6}\u{C6}\u{F4}\u{F6}\u{F2}\u{FB}\u{F9}\u{FF}\u{D6}\u{DC}\u{A2}\u{A3}\u{A5}\u{20A7}\u{192}",
+    "\u{E1}\u{ED}\u{F3}\u{FA}\u{F1}\u{D1}\u{AA}\u{BA}\u{BF}\u{2310}\u{AC}\u{BD}\u{BC}\u{A1}\u{AB}\u{BB}",
+    "\u{2591}\u{2592}\u{2593}\u{2502}\u{2524}\u{2561}\u{2562}\u{2556}",
+    "\u{2555}\u{2563}\u{2551}\u{2557}\u{255D}\u{255C}\u{255B}\u{2510}",
+    "\u{2514}\u{2534}\u{252C}\u{251C}\u{2500}\u{253C}\u{255E}\u{255F}",
+    "\u{255A}\u{2554}\u{2569}\u{2566}\u{2560}\u{2550}\u{256C}\u{2567}",
+    "\u{2568}\u{2564}\u{2565}\u{2559}\u{2558}\u{2552}\u{2553}\u{256B}",
+    "\u{256A}\u{2518}\u{250C}\u{2588}\u{2584}\u{258C}\u{2590}\u{2580}",
+    "\u{3B1}\u{DF}\u{393}\u{3C0}\u{3A3}\u{3C3}\u{B5}\u{3C4}\u{3A6}\u{398}\u{3A9}\u{3B4}\u{221E}\u{3C6}\u{3B5}\u{2229}",
+    "\u{2261}\u{B1}\u{2265}\u{2264}\u{2320}\u{2321}\u{F7}\u{2248}\u{B0}\u{2219}\u{B7}\u{221A}\u{207F}\u{B2}\u{25A0}\u{A0}",
+);
+
+/// The replacements of the common chars which have no glyph nor decomposition in the fonts
+const REPLACEMENTS: &[(char, &str)] = &[
+    ('\u{2018}', "'"),
+    ('\u{2019}', "'"),
+    ('\u{201A}', "'"),
+    ('\u{201B}', "'"),
+    ('\u{2032}', "'"),
+    ('\u{201C}', "\""),
+    ('\u{201D}', "\""),
+    ('\u{201E}', "\""),
+    ('\u{201F}', "\""),
+    ('\u{2033}', "\""),
+    ('\u{2039}', "<"),
+    ('\u{203A}', ">"),
+    ('\u{2010}', "-"),
+    ('\u{2011}', "-"),
+    ('\u{2012}', "-"),
+    ('\u{2013}', "-"),
+    ('\u{2014}', "-"),
+    ('\u{2015}', "-"),
+    ('\u{2212}', "-"),
+    ('\u{2026}', "..."),
+    ('\u{00D7}', "x"),
+    ('\u{00A9}', "(C)"),
+    ('\u{00AE}', "(R)"),
+    ('\u{20AC}', "EUR"),
+    ('\u{0152}', "OE"),
+    ('\u{0153}', "oe"),
+    ('\u{00D8}', "O"),
+    ('\u{00F8}', "o"),
+    ('\u{0141}', "L"),
+    ('\u{0142}', "l"),
+    ('\u{0110}', "D"),
+    ('\u{0111}', "d"),
+    ('\u{00D0}', "D"),
+    ('\u{00F0}', "d"),
+    ('\u{00DE}', "Th"),
+    ('\u{00FE}', "th"),
+    ('\u{0131}', "i"),
+    ('\u{1E9E}', "SS"),
+    ('\t', " "),
+];
+
+/// The glyphs a font can draw, to normalize the texts drawn with it
+pub trait Glyphs {
+    /// Check if the font has a glyph for the char
+    fn has_glyph(c: char) -> bool;
+
+    /// Normalize a text to be drawn with the font.
+    ///
+    /// The chars of the font are kept, the others are replaced by their closest glyphs
+    /// (ã becomes a, smart quotes become ASCII quotes, etc...) and the unknown ones by `?`.
+    /// Line feeds are kept, the other control chars are removed.
+    fn normalize(text: &str) -> String {
+        let mut normalized = String::with_capacity(text.len());
+        for c in text.chars() {
+            if c == '\n' || Self::has_glyph(c) {
+                normalized.push(c);
+            } else if let Some((_, replacement)) = REPLACEMENTS.iter().find(|(from, _)| *from == c)
+            {
+                normalized.push_str(replacement);
+            } else if c.is_control() {
+                continue;
+            } else {
+                // Decompose the char (accents, ligatures, full width forms...) and keep its base
+                let base: String = c.nfkd().filter(|c| !is_combining_mark(*c)).collect();
+                if !base.is_empty() && base.chars().all(Self::has_glyph) {
+                    normalized.push_str(&base);
+                } else {
+                    normalized.push('?');
+                }
+            }
+        }
+        normalized
+    }
+}
+
+/// ASCII and the Latin-1 supplement
+impl Glyphs for Font6x8 {
+    fn has_glyph(c: char) -> bool {
+        matches!(c, ' '..='~' | '\u{A1}'..='\u{FF}')
+    }
+}
+
+/// ASCII, the Latin-1 punctuation and the euro sign
+impl Glyphs for Font6x6 {
+    fn has_glyph(c: char) -> bool {
+        matches!(c, ' '..='~' | '\u{A1}'..='\u{BF}' | '\u{20AC}')
+    }
+}
+
+impl Glyphs for Ibm437Font8x8Normal {
+    fn has_glyph(c: char) -> bool {
+        IBM437_GLYPHS.contains(c)
+    }
+}
+
+impl Glyphs for Ibm437Font8x8Bold {
+    fn has_glyph(c: char) -> bool {
+        IBM437_GLYPHS.contains(c)
+    }
+}
 
 pub static TEXT_SMALL: Lazy<TextStyle<BinaryColor, Font6x6>> = Lazy::new(|| {
     TextStyleBuilder::new(Font6x6)
@@ -67,3 +193,96 @@ pub static BORDER_1: Lazy<PrimitiveStyle<BinaryColor>> = Lazy::new(|| {
         .stroke_width(1)
         .build()
 });
+
+#[cfg(test)]
+mod test {
+    use crate::style::{Glyphs, IBM437_GLYPHS, TEXT_LIGHT};
+    use embedded_graphics::fonts::{Font6x6, Font6x8, Text};
+    use embedded_graphics::pixelcolor::BinaryColor;
+    use embedded_graphics::prelude::*;
+    use ibm437::Ibm437Font8x8Bold;
+
+    /// The code page has 255 printable glyphs (all but NUL).
+    #[test]
+    fn code_page_size() {
+        assert_eq!(IBM437_GLYPHS.chars().count(), 255);
+    }
+
+    /// ASCII and IBM437 chars are kept as is.
+    #[test]
+    fn keep_ibm437() {
+        assert_eq!(
+            Ibm437Font8x8Bold::normalize("Hello, world!"),
+            "Hello, world!"
+        );
+        assert_eq!(
+            Ibm437Font8x8Bold::normalize("Caf\u{e9} stra\u{df}e \u{266b}"),
+            "Caf\u{e9} stra\u{df}e \u{266b}"
+        );
+        assert_eq!(
+            Ibm437Font8x8Bold::normalize("Oops\nNo error"),
+            "Oops\nNo error"
+        );
+    }
+
+    /// Other chars are replaced by their closest glyphs.
+    #[test]
+    fn transliterate() {
+        assert_eq!(
+            Ibm437Font8x8Bold::normalize("S\u{e3}o Jo\u{e3}o"),
+            "Sao Joao"
+        );
+        assert_eq!(
+            Ibm437Font8x8Bold::normalize("\u{201c}It\u{2019}s\u{201d} \u{2014} ok\u{2026}"),
+            "\"It's\" - ok..."
+        );
+        assert_eq!(
+            Ibm437Font8x8Bold::normalize("\u{142}\u{f3}d\u{17a} \u{153}uvre"),
+            "l\u{f3}dz oeuvre"
+        );
+        assert_eq!(Ibm437Font8x8Bold::normalize("\u{fb01}n \u{ff21}"), "fin A");
+        assert_eq!(Ibm437Font8x8Bold::normalize("a\tb\r"), "a b");
+    }
+
+    /// Unknown chars are replaced by a question mark.
+    #[test]
+    fn unknown_chars() {
+        assert_eq!(
+            Ibm437Font8x8Bold::normalize("\u{65e5}\u{672c} \u{1f3b5}"),
+            "?? ?"
+        );
+    }
+
+    /// The Latin-1 letters are kept for the light font, but not its missing IBM437 glyphs.
+    #[test]
+    fn latin1() {
+        assert_eq!(
+            Font6x8::normalize("S\u{e3}o Jo\u{e3}o \u{d8}"),
+            "S\u{e3}o Jo\u{e3}o \u{d8}"
+        );
+        assert_eq!(
+            Font6x8::normalize("\u{3b1}\u{3b2} \u{266b} \u{2500}"),
+            "?? ? ?"
+        );
+        assert_eq!(Font6x8::normalize("\u{201c}\u{a0}\u{201d}"), "\" \"");
+        assert_eq!(Font6x6::normalize("10\u{20ac} \u{e3}"), "10\u{20ac} a");
+    }
+
+    /// The normalized text is drawn without fallback glyphs by the light font.
+    #[test]
+    fn draw_latin1() {
+        let draw = |text: &str| -> Vec<Pixel<BinaryColor>> {
+            Text::new(text, Point::zero())
+                .into_styled(*TEXT_LIGHT)
+                .into_iter()
+                .collect()
+        };
+        let text = Font6x8::normalize("\u{c3}\u{e3}\u{f5} \u{266a}");
+        assert_eq!(text, "\u{c3}\u{e3}\u{f5} ?");
+        for (letter, base) in text.chars().zip("Aao".chars()) {
+            let glyph = draw(&letter.to_string());
+            assert_ne!(glyph, draw("?"));
+            assert_ne!(glyph, draw(&base.to_string()));
+        }
+    }
+}
@@ -0,0 +1,65 @@
+use chrono::{DateTime, Local};
+use std::time::Instant;
+
+/// A source of the current time.
+///
+/// The time-dependent logic takes one, so a fake time can be used in tests.
+pub trait TimeSource {
+    /// Get the current local time
+    fn now(&self) -> DateTime<Local>;
+}
+
+/// The system clock
+#[derive(Clone, Copy, Debug, Default)]
+pub struct SystemTime;
+
+impl TimeSource for SystemTime {
+    fn now(&self) -> DateTime<Local> {
+        Local::now()
+    }
+}
+
+/// A source of monotonic time, for the durations (never going back).
+///
+/// The duration-dependent logic takes one, so a fake clock can be used in tests.
+pub trait MonotonicSource {
+    /// Get the current instant
+    fn instant(&self) -> Instant;
+}
+
+impl MonotonicSource for SystemTime {
+    fn instant(&self) -> Instant {
+        Instant::now()
+    }
+}
+
+/// A monotonic clock only moving when told to, for the tests
+#[cfg(test)]
+#[derive(Clone, Debug)]
+pub struct ManualTime {
+    start: Instant,
+    elapsed: std::rc::Rc<std::cell::Cell<std::time::Duration>>,
+}
+
+#[cfg(test)]
+impl ManualTime {
+    /// Create a clock, stopped at an arbitrary instant
+    pub fn new() -> Self {
+        Self {
+            start: Instant::now(),
+            elapsed: Default::default(),
+        }
+    }
+
+    /// Move the clock (and its clones) forward
+    pub fn advance(&self, duration: std::time::Duration) {
+        self.elapsed.set(self.elapsed.get() + duration);
+    }
+}
+
+#[cfg(test)]
+impl MonotonicSource for ManualTime {
+    fn instant(&self) -> Instant {
+        self.start + self.elapsed.get()
+    }
+}
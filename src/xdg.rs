@@ -0,0 +1,40 @@
+use std::path::PathBuf;
+
+/// The name of the g13-apps directories
+const APP_DIR: &str = "g13-apps";
+
+/// Get an XDG base directory from its env var,
+/// or from its default path relative to the home directory.
+fn base_dir(var: &str, default: &str) -> Option<PathBuf> {
+    std::env::var_os(var)
+        .map(PathBuf::from)
+        // Relative paths are invalid and must be ignored
+        .filter(|path| path.is_absolute())
+        .or_else(|| std::env::var_os("HOME").map(|home| PathBuf::from(home).join(default)))
+}
+
+/// Get the path of a g13-apps state file (`$XDG_STATE_HOME/g13-apps/<name>`)
+pub fn state_file(name: &str) -> Option<PathBuf> {
+    Some(
+        base_dir("XDG_STATE_HOME", ".local/state")?
+            .join(APP_DIR)
+            .join(name),
+    )
+}
+
+/// Get the path of a g13-apps configuration file (`$XDG_CONFIG_HOME/g13-apps/<name>`)
+pub fn config_file(name: &str) -> Option<PathBuf> {
+    Some(
+        base_dir("XDG_CONFIG_HOME", ".config")?
+            .join(APP_DIR)
+            .join(name),
+    )
+}
+
+/// Expand a configured path: "~/" is the home directory (None if it is unknown)
+pub fn expand_home(path: &str) -> Option<PathBuf> {
+    match path.strip_prefix("~/") {
+        Some(path) => Some(PathBuf::from(std::env::var_os("HOME")?).join(path)),
+        None => Some(PathBuf::from(path)),
+    }
+}